@@ -3,19 +3,25 @@ use crate::game::combat::combat_ui::CombatUiPlugin;
 use crate::game::combat::end_turn::EndTurnPlugin;
 use crate::game::combat::leave_combat::LeaveCombatPlugin;
 use crate::game::combat::local_combat_data::LocalCombatData;
+use crate::game::combat::resume_combat::{PendingCombatSnapshot, ResumeCombatPlugin};
 use crate::game::combat::unit_actions::UnitActionPlugin;
 use crate::game::combat::unit_animations::UnitAnimationPlugin;
 use crate::game::combat::unit_placement::UnitPlacementPlugin;
+use crate::game::desync_detection::ApplyServerUpdates;
+use crate::game::sprite_builder;
+use crate::load::CharacterSprites;
 use crate::map::{ActiveUnitHighlights, MapState};
 use crate::networking::LocalPlayerId;
 use crate::ApplicationState;
 use bevy::app::{App, Plugin, Update};
 use bevy::prelude::{
-    error, in_state, on_event, Commands, EventReader, EventWriter, IntoSystemConfigs, NextState,
-    OnEnter, Reflect, Res, ResMut, States,
+    error, in_state, not, on_event, resource_exists, Commands, EventReader, EventWriter,
+    IntoSystemConfigs, NextState, OnEnter, Reflect, Res, ResMut, States,
 };
+use bevy_sprite3d::Sprite3dParams;
 use game_common::combat_data::CombatData;
 use game_common::combat_turn::CombatTurn;
+use game_common::game_map::GameMap;
 use game_common::network_events::client_to_server::ClientToServerMessage;
 use game_common::network_events::server_to_client::{
     CombatFinished, PlayerTurnToPlaceUnit, StartUnitTurn,
@@ -33,10 +39,13 @@ impl Plugin for CombatPlugin {
         app.add_plugins(UnitAnimationPlugin);
         app.add_plugins(EndTurnPlugin);
         app.add_plugins(LeaveCombatPlugin);
+        app.add_plugins(ResumeCombatPlugin);
         app.init_state::<CombatState>();
         app.add_systems(
             OnEnter(MapState::Ready),
-            on_map_loaded.run_if(in_state(ApplicationState::InGame)),
+            on_map_loaded
+                .run_if(in_state(ApplicationState::InGame))
+                .run_if(not(resource_exists::<PendingCombatSnapshot>)),
         );
         app.add_systems(
             Update,
@@ -45,6 +54,7 @@ impl Plugin for CombatPlugin {
                 on_start_unit_turn.run_if(on_event::<StartUnitTurn>()),
                 on_combat_finished.run_if(on_event::<CombatFinished>()),
             )
+                .in_set(ApplyServerUpdates)
                 .run_if(in_state(ApplicationState::InGame)),
         );
     }
@@ -99,8 +109,29 @@ pub fn on_start_unit_turn(
     mut next_combat_state: ResMut<NextState<CombatState>>,
     mut combat_data: ResMut<CombatData>,
     local_player_id: Res<LocalPlayerId>,
+    locals: Res<LocalCombatData>,
+    map: Res<GameMap>,
+    character_sprites: Res<CharacterSprites>,
+    mut sprite_params: Sprite3dParams,
 ) {
     for event in event.read() {
+        if let CombatTurn::UnitTurn(turn) = &combat_data.current_turn {
+            let previous_unit_id = turn.unit_id;
+            combat_data.end_unit_turn();
+
+            let previous_unit = &combat_data.units[&previous_unit_id];
+            if previous_unit.is_dead() {
+                commands
+                    .entity(locals.unit_entities[&previous_unit_id])
+                    .insert(sprite_builder::build_dead_unit_sprite(
+                        previous_unit,
+                        &character_sprites,
+                        &map,
+                        &mut sprite_params,
+                    ));
+            }
+        }
+
         let locally_computed_next = combat_data.get_unit_for_next_turn();
         if locally_computed_next != event.unit_id {
             error!("Turn order seems to be out of sync? Server said to start turn for unit {}, but locally, it would be {}'s turn.",
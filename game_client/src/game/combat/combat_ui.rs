@@ -13,6 +13,8 @@ use bevy_egui::{egui, EguiContexts, EguiPlugin};
 use game_common::combat_data::CombatData;
 use game_common::combat_turn::CombatTurn;
 use game_common::combat_unit::CombatUnit;
+use game_common::game_data::element::Resistances;
+use game_common::game_data::movement_type::MovementType;
 use game_common::game_data::GameData;
 use game_common::player_resources::PlayerResources;
 
@@ -105,7 +107,25 @@ fn draw_unit_info(mut egui: EguiContexts, unit: &CombatUnit, anchor: Align2) {
         "Move: {} | Jump: {}",
         unit.stats_after_buffs.movement, unit.stats_after_buffs.jump
     ));
+    if unit.movement_type != MovementType::Walker {
+        lines.push(unit.movement_type.to_string());
+    }
+    lines.push(format!("Strength: {}", unit.stats_after_buffs.strength));
     lines.push(format!("Speed: {}", unit.stats_after_buffs.speed));
+    let resistances = unit.resistances;
+    if resistances != Resistances::default() {
+        lines.push(format!(
+            "Resist: Fire {}% | Ice {}% | Lightning {}% | Earth {}%",
+            resistances.fire, resistances.ice, resistances.lightning, resistances.earth
+        ));
+    }
+    for effect in &unit.status_effects {
+        lines.push(format!(
+            "{} ({} turns left)",
+            effect.kind.name(),
+            effect.remaining_turns
+        ));
+    }
     lines.push(format!(
         "TurnCounter: {} [{}]",
         unit.turn_counter, unit.turn_tiebreaker
@@ -199,6 +219,9 @@ fn build_this_player_unit_turn_ui(
 ) {
     let unit = combat_data.current_turn_unit();
     ui.label(format!("Your turn: {}", unit.name));
+    if unit.is_stunned() {
+        ui.label("Stunned!");
+    }
     ui.horizontal(|ui| {
         let turn = combat_data.current_turn.as_unit_turn().unwrap();
         ui.add_enabled_ui(turn.remaining_movement > 0, |ui| {
@@ -4,9 +4,14 @@ mod combat_ui;
 mod end_turn;
 mod leave_combat;
 mod local_combat_data;
+mod resume_combat;
 mod unit_actions;
 mod unit_actions_highlights;
 mod unit_animations;
 mod unit_placement;
 
 pub use combat_plugin::CombatPlugin;
+pub use combat_plugin::CombatState;
+pub use leave_combat::LeaveCombatCommand;
+pub use local_combat_data::LocalCombatData;
+pub use resume_combat::PendingCombatSnapshot;
@@ -0,0 +1,103 @@
+use crate::game::combat::combat_plugin::CombatState;
+use crate::game::combat::local_combat_data::LocalCombatData;
+use crate::game::combat::unit_placement::{spawn_unit_entity, UnitMarker};
+use crate::game::sprite_builder;
+use crate::load::CharacterSprites;
+use crate::map::{ActiveUnitHighlights, MapState};
+use crate::networking::LocalPlayerId;
+use crate::ApplicationState;
+use bevy::prelude::*;
+use bevy_sprite3d::Sprite3dParams;
+use game_common::combat_data::CombatData;
+use game_common::combat_turn::CombatTurn;
+use game_common::game_map::GameMap;
+use game_common::network_events::client_to_server::ClientToServerMessage;
+
+pub struct ResumeCombatPlugin;
+impl Plugin for ResumeCombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(MapState::Ready),
+            restore_combat_snapshot
+                .run_if(in_state(ApplicationState::InGame))
+                .run_if(resource_exists::<PendingCombatSnapshot>),
+        );
+    }
+}
+
+/// The state of a combat we rejoined. It gets restored as soon as its map has been spawned.
+#[derive(Resource)]
+pub struct PendingCombatSnapshot {
+    pub combat_data: CombatData,
+}
+
+fn restore_combat_snapshot(
+    mut commands: Commands,
+    snapshot: Res<PendingCombatSnapshot>,
+    stale_units: Query<Entity, With<UnitMarker>>,
+    local_player_id: Res<LocalPlayerId>,
+    map: Res<GameMap>,
+    character_sprites: Res<CharacterSprites>,
+    mut sprite_params: Sprite3dParams,
+    mut next_combat_state: ResMut<NextState<CombatState>>,
+    mut client_to_server_messages: EventWriter<ClientToServerMessage>,
+) {
+    for entity in stale_units.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let combat_data = snapshot.combat_data.clone();
+    let mut locals = LocalCombatData {
+        unit_entities: Default::default(),
+    };
+    for unit in combat_data.units.values() {
+        let entity = spawn_unit_entity(
+            &mut commands,
+            &character_sprites,
+            &map,
+            &mut sprite_params,
+            unit,
+        );
+        if unit.is_dead() {
+            commands
+                .entity(entity)
+                .insert(sprite_builder::build_dead_unit_sprite(
+                    unit,
+                    &character_sprites,
+                    &map,
+                    &mut sprite_params,
+                ));
+        }
+        locals.unit_entities.insert(unit.id, entity);
+    }
+
+    match &combat_data.current_turn {
+        CombatTurn::Undefined => {
+            // We might have lost our connection before telling the server we are done loading
+            client_to_server_messages.send(ClientToServerMessage::FinishedLoading);
+            next_combat_state.set(CombatState::WaitingForServer);
+        }
+        CombatTurn::PlaceUnit(turn) => {
+            if turn.player_id == local_player_id.id {
+                next_combat_state.set(CombatState::PlaceUnit);
+            } else {
+                next_combat_state.set(CombatState::WaitingForOtherPlayer);
+            }
+        }
+        CombatTurn::UnitTurn(turn) => {
+            let unit = &combat_data.units[&turn.unit_id];
+            if unit.owner == local_player_id.actor {
+                next_combat_state.set(CombatState::ThisPlayerUnitTurn);
+            } else {
+                next_combat_state.set(CombatState::WaitingForOtherPlayer);
+            }
+            commands.insert_resource(ActiveUnitHighlights {
+                tile: unit.position,
+            });
+        }
+    }
+
+    commands.insert_resource(combat_data);
+    commands.insert_resource(locals);
+    commands.remove_resource::<PendingCombatSnapshot>();
+}
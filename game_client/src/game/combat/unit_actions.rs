@@ -2,16 +2,20 @@ use std::cmp::PartialEq;
 use std::ops::Deref;
 
 use bevy::app::App;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_sprite3d::Sprite3dParams;
+use hexx::Hex;
 use leafwing_input_manager::action_state::ActionState;
 
 use game_common::combat_data::CombatData;
+use game_common::combat_unit::UnitId;
 use game_common::game_data::skill::{SkillId, SkillTargeting, DEBUG_SINGLE_TARGET_ATTACK_ID};
 use game_common::game_data::GameData;
-use game_common::game_map::GameMap;
+use game_common::game_map::{GameMap, TileData};
 use game_common::network_events::client_to_server::ClientToServerMessage;
 use game_common::network_events::{client_to_server, server_to_client};
+use game_common::skill_effect::{apply_skill_effects, SkillEffect};
 use game_common::DESYNC_TODO_MESSAGE;
 
 use crate::game::combat::combat_input::CombatAction;
@@ -20,9 +24,13 @@ use crate::game::combat::end_turn::EndTurnCommand;
 use crate::game::combat::local_combat_data::LocalCombatData;
 use crate::game::combat::unit_actions_highlights::UnitActionHighlightPlugin;
 use crate::game::combat::unit_animations::{MoveUnitComponent, UnitAttackAnimationComponent};
+use crate::game::desync_detection::ApplyServerUpdates;
 use crate::game::sprite_builder;
 use crate::load::CharacterSprites;
-use crate::map::{ActiveUnitHighlights, CursorOnTile, PathHighlights, RangeHighlights};
+use crate::map::map_utils::unit_position_on_hexagon;
+use crate::map::{
+    ActiveUnitHighlights, CursorOnTile, PathHighlights, RangeHighlights, TileChangeEvent,
+};
 use crate::networking::LocalPlayerId;
 use crate::ApplicationState;
 
@@ -48,8 +56,12 @@ impl Plugin for UnitActionPlugin {
                     .run_if(in_state(ApplicationState::InGame))
                     .run_if(in_state(CombatState::ThisPlayerUnitTurn))
                     .run_if(resource_exists::<ActiveUnitAction>),
-                on_move_unit.run_if(on_event::<server_to_client::MoveUnit>()),
-                on_use_skill.run_if(on_event::<server_to_client::UseSkill>()),
+                (
+                    on_move_unit.run_if(on_event::<server_to_client::MoveUnit>()),
+                    on_use_skill.run_if(on_event::<server_to_client::UseSkill>()),
+                    on_reaction_triggered.run_if(on_event::<server_to_client::ReactionTriggered>()),
+                )
+                    .in_set(ApplyServerUpdates),
             ),
         );
     }
@@ -176,6 +188,9 @@ pub fn on_move_unit(
     local_combat_data: Res<LocalCombatData>,
     mut next_combat_state: ResMut<NextState<CombatState>>,
     local_player_id: Res<LocalPlayerId>,
+    map: Res<GameMap>,
+    character_sprites: Res<CharacterSprites>,
+    mut sprite_params: Sprite3dParams,
 ) {
     for event in events.read() {
         debug_assert!(
@@ -186,6 +201,9 @@ pub fn on_move_unit(
         let old_pos = &event.path[0];
         let new_pos = &event.path[event.path.len() - 1];
         let unit_id = combat_data.current_turn.as_unit_turn().unwrap().unit_id;
+        let cost = map
+            .calculate_total_path_cost(combat_data.current_turn_unit(), &combat_data, &event.path)
+            .expect(DESYNC_TODO_MESSAGE);
 
         combat_data.unit_positions.remove(old_pos);
         combat_data.unit_positions.insert(new_pos.clone(), unit_id);
@@ -193,7 +211,7 @@ pub fn on_move_unit(
             .current_turn
             .as_unit_turn_mut()
             .unwrap()
-            .remaining_movement -= event.path.len() as u8 - 1;
+            .remaining_movement -= cost as u8;
 
         let unit = combat_data
             .units
@@ -201,12 +219,24 @@ pub fn on_move_unit(
             .expect(DESYNC_TODO_MESSAGE);
 
         unit.position = new_pos.clone();
+        unit.face_along_path(&event.path);
+        map.apply_fluid_contact(unit, &event.path[1..]);
 
         let entity = local_combat_data.unit_entities[&unit_id];
 
         commands
             .entity(entity)
             .insert(MoveUnitComponent::new(event.path.clone()));
+        if unit.is_dead() {
+            commands
+                .entity(entity)
+                .insert(sprite_builder::build_dead_unit_sprite(
+                    unit,
+                    &character_sprites,
+                    &map,
+                    &mut sprite_params,
+                ));
+        }
 
         commands.insert_resource(ActiveUnitHighlights {
             tile: unit.position,
@@ -220,58 +250,127 @@ pub fn on_move_unit(
     }
 }
 
-pub fn on_use_skill(
-    mut commands: Commands,
-    map: Res<GameMap>,
-    locals: Res<LocalCombatData>,
-    mut events: EventReader<server_to_client::UseSkill>,
-    mut combat_data: ResMut<CombatData>,
-    mut next_combat_state: ResMut<NextState<CombatState>>,
-    local_player_id: Res<LocalPlayerId>,
-    character_sprites: Res<CharacterSprites>,
-    mut sprite_params: Sprite3dParams,
-) {
-    for event in events.read() {
-        for x in &event.hits {
-            let target = combat_data.units.get_mut(&x.target_unit_id).unwrap();
+/// Everything needed to apply [SkillEffect]s to the local combat state and update their visuals.
+#[derive(SystemParam)]
+pub struct SkillEffectVisuals<'w, 's> {
+    commands: Commands<'w, 's>,
+    map: ResMut<'w, GameMap>,
+    combat_data: ResMut<'w, CombatData>,
+    locals: Res<'w, LocalCombatData>,
+    tile_change_event: EventWriter<'w, TileChangeEvent>,
+    transforms: Query<'w, 's, &'static mut Transform>,
+    character_sprites: Res<'w, CharacterSprites>,
+    sprite_params: Sprite3dParams<'w, 's>,
+}
+
+impl SkillEffectVisuals<'_, '_> {
+    fn apply(&mut self, effects: &[SkillEffect]) {
+        let alive_before: Vec<UnitId> = self
+            .combat_data
+            .units
+            .values()
+            .filter(|x| !x.is_dead())
+            .map(|x| x.id)
+            .collect();
+        let old_tiles: Vec<(Hex, TileData)> = effects
+            .iter()
+            .filter_map(|effect| match effect {
+                SkillEffect::ChangeTerrain { hex, .. } => {
+                    self.map.tiles.get(hex).map(|tile| (*hex, tile.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        apply_skill_effects(effects, &mut self.combat_data, &mut self.map);
+
+        for effect in effects {
+            if let SkillEffect::Move { target, path } = effect {
+                self.commands
+                    .entity(self.locals.unit_entities[target])
+                    .insert(MoveUnitComponent::new(path.clone()));
+            }
+        }
 
-            // TODO: Stat reduction should be extracted into common
-            // TODO: Consider extracting this into a "unit takes damage"-Event
-            if target.hp <= x.physical_damage {
-                target.hp = 0;
+        for (hex, old_data) in old_tiles {
+            if let Some(unit_id) = self.combat_data.unit_positions.get(&hex) {
+                if let Ok(mut transform) =
+                    self.transforms.get_mut(self.locals.unit_entities[unit_id])
+                {
+                    transform.translation = unit_position_on_hexagon(hex, &self.map);
+                }
+            }
+            self.tile_change_event
+                .send(TileChangeEvent { hex, old_data });
+        }
 
-                let entity = locals.unit_entities[&target.id];
-                commands
+        for unit_id in alive_before {
+            let target = &self.combat_data.units[&unit_id];
+            if target.is_dead() {
+                let entity = self.locals.unit_entities[&target.id];
+                self.commands
                     .entity(entity)
                     .insert(sprite_builder::build_dead_unit_sprite(
                         target,
-                        &character_sprites,
-                        &map,
-                        &mut sprite_params,
+                        &self.character_sprites,
+                        &self.map,
+                        &mut self.sprite_params,
                     ));
-            } else {
-                target.hp -= x.physical_damage;
             }
         }
+    }
 
-        let turn = combat_data.current_turn.as_unit_turn_mut().unwrap();
+    fn play_attack_animation(&mut self, unit_id: UnitId, target: Hex) {
+        let unit = &self.combat_data.units[&unit_id];
+        self.commands
+            .entity(self.locals.unit_entities[&unit_id])
+            .insert(UnitAttackAnimationComponent::new(unit, target, &self.map));
+    }
+}
+
+pub fn on_use_skill(
+    mut events: EventReader<server_to_client::UseSkill>,
+    mut visuals: SkillEffectVisuals,
+    mut next_combat_state: ResMut<NextState<CombatState>>,
+    local_player_id: Res<LocalPlayerId>,
+    game_data: Res<GameData>,
+) {
+    for event in events.read() {
+        visuals.apply(&event.effects);
+
+        let turn = visuals.combat_data.current_turn.as_unit_turn_mut().unwrap();
         turn.remaining_actions -= 1;
         let unit_id = turn.unit_id;
 
-        let unit = combat_data.units.get(&unit_id).unwrap();
+        let unit = visuals.combat_data.units.get_mut(&unit_id).unwrap();
+        unit.mp -= game_data.skills[&event.id].mp_costs;
+        unit.turn_towards(event.target_coordinates);
+        let owner = unit.owner;
 
-        commands
-            .entity(locals.unit_entities[&unit_id])
-            .insert(UnitAttackAnimationComponent::new(
-                unit,
-                event.target_coordinates,
-                &map,
-            ));
+        visuals.play_attack_animation(unit_id, event.target_coordinates);
 
-        if unit.owner == local_player_id.actor {
+        if owner == local_player_id.actor {
             next_combat_state.set(CombatState::ThisPlayerUnitTurn);
         } else {
             next_combat_state.set(CombatState::WaitingForOtherPlayer);
         }
     }
 }
+
+pub fn on_reaction_triggered(
+    mut events: EventReader<server_to_client::ReactionTriggered>,
+    mut visuals: SkillEffectVisuals,
+) {
+    for event in events.read() {
+        visuals.apply(&event.effects);
+        let unit = visuals
+            .combat_data
+            .units
+            .get_mut(&event.unit_id)
+            .expect(DESYNC_TODO_MESSAGE);
+        unit.is_reaction_ready = false;
+        unit.turn_towards(event.target_coordinates);
+
+        visuals.play_attack_animation(event.unit_id, event.target_coordinates);
+    }
+}
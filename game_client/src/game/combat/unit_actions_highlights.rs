@@ -7,6 +7,7 @@ use game_common::combat_data::CombatData;
 use game_common::game_data::skill::{SkillId, SkillTargeting};
 use game_common::game_data::GameData;
 use game_common::game_map::GameMap;
+use game_common::validation;
 use hexx::Hex;
 use std::ops::Deref;
 
@@ -78,12 +79,15 @@ pub fn show_skill_range_preview(
             return;
         }
         SkillTargeting::MouseCursor(range) => {
-            let tiles: Vec<Hex> = hexx::algorithms::range_fov(unit.position, range.max, |hex| {
-                !map.tiles.contains_key(&hex)
-            })
-            .into_iter()
-            .filter(|x| x.unsigned_distance_to(unit.position) >= range.min)
-            .collect();
+            // Uses the same validation as the server, so the preview never shows invalid targets
+            let tiles: Vec<Hex> = unit
+                .position
+                .range(range.max)
+                .filter(|hex| {
+                    validation::validate_skill_target_is_in_range(skill, unit.position, *hex, map)
+                        .is_ok()
+                })
+                .collect();
 
             commands.insert_resource(RangeHighlights { tiles })
         }
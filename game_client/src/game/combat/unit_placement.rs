@@ -1,6 +1,7 @@
 use crate::game::combat::combat_input::CombatAction;
 use crate::game::combat::combat_plugin::CombatState;
 use crate::game::combat::local_combat_data::LocalCombatData;
+use crate::game::desync_detection::ApplyServerUpdates;
 use crate::game::sprite_builder;
 use crate::load::CharacterSprites;
 use crate::map::{CursorOnTile, RangeHighlights};
@@ -26,7 +27,9 @@ impl Plugin for UnitPlacementPlugin {
             Update,
             (
                 on_switch_to_next_unit.run_if(on_event::<SwitchToNextUnitEvent>()),
-                on_server_placed_unit.run_if(on_event::<server_to_client::PlaceUnit>()),
+                on_server_placed_unit
+                    .run_if(on_event::<server_to_client::PlaceUnit>())
+                    .in_set(ApplyServerUpdates),
                 input_listener.run_if(in_state(CombatState::PlaceUnit)),
             ),
         );
@@ -213,7 +216,7 @@ fn on_server_placed_unit(
 #[derive(Component)]
 pub struct UnitMarker;
 
-fn spawn_unit_entity(
+pub(in crate::game::combat) fn spawn_unit_entity(
     commands: &mut Commands,
     character_sprites: &CharacterSprites,
     map: &GameMap,
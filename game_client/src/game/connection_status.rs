@@ -0,0 +1,145 @@
+use crate::game::combat::LeaveCombatCommand;
+use crate::networking::{
+    AbsentPlayers, ConnectedPlayers, ConnectionRefusedReason, Network, NetworkState,
+    ResumableSession,
+};
+use crate::ApplicationState;
+use bevy::app::{App, Plugin};
+use bevy::prelude::*;
+use bevy_egui::egui::Align2;
+use bevy_egui::{egui, EguiContexts};
+use std::time::Duration;
+
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(3);
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+
+pub struct ConnectionStatusPlugin;
+impl Plugin for ConnectionStatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(NetworkState::Disconnected),
+            start_reconnecting
+                .run_if(in_state(ApplicationState::InGame))
+                .run_if(resource_exists::<ResumableSession>),
+        );
+        app.add_systems(OnEnter(NetworkState::Connected), stop_reconnecting);
+        app.add_systems(OnExit(ApplicationState::InGame), stop_reconnecting);
+        app.add_systems(
+            Update,
+            (
+                (
+                    try_to_reconnect.run_if(in_state(NetworkState::Disconnected)),
+                    reconnect_ui,
+                )
+                    .run_if(resource_exists::<Reconnecting>),
+                absent_players_ui.run_if(in_state(NetworkState::Connected)),
+            )
+                .run_if(in_state(ApplicationState::InGame)),
+        );
+    }
+}
+
+/// Exists while we are trying to get back into a game after losing our connection.
+#[derive(Resource)]
+struct Reconnecting {
+    timer: Timer,
+    attempts: u32,
+}
+
+fn start_reconnecting(mut commands: Commands, reconnecting: Option<Res<Reconnecting>>) {
+    if reconnecting.is_none() {
+        warn!("Lost connection to server, trying to reconnect.");
+        commands.insert_resource(Reconnecting {
+            timer: Timer::new(RECONNECT_INTERVAL, TimerMode::Repeating),
+            attempts: 0,
+        });
+    }
+}
+
+fn stop_reconnecting(mut commands: Commands) {
+    commands.remove_resource::<Reconnecting>();
+}
+
+fn try_to_reconnect(
+    time: Res<Time>,
+    mut reconnecting: ResMut<Reconnecting>,
+    mut network: ResMut<Network>,
+    mut next_network_state: ResMut<NextState<NetworkState>>,
+    refused_reason: Option<Res<ConnectionRefusedReason>>,
+    mut leave_combat: EventWriter<LeaveCombatCommand>,
+) {
+    if let Some(refused_reason) = refused_reason {
+        warn!("Unable to resume session: {}", refused_reason.reason);
+        leave_combat.send(LeaveCombatCommand::quit());
+        return;
+    }
+
+    if !reconnecting.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    if reconnecting.attempts >= MAX_RECONNECT_ATTEMPTS {
+        warn!(
+            "Giving up on reconnecting after {} attempts.",
+            reconnecting.attempts
+        );
+        leave_combat.send(LeaveCombatCommand::quit());
+        return;
+    }
+
+    reconnecting.attempts += 1;
+    network.connect();
+    next_network_state.set(NetworkState::Connecting);
+}
+
+fn reconnect_ui(
+    mut egui: EguiContexts,
+    reconnecting: Res<Reconnecting>,
+    mut leave_combat: EventWriter<LeaveCombatCommand>,
+) {
+    egui::Window::new("Connection lost")
+        .collapsible(false)
+        .resizable(false)
+        .movable(false)
+        .anchor(Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(egui.ctx_mut(), |ui| {
+            ui.vertical_centered(|ui| {
+                ui.label(format!(
+                    "Reconnecting... (Attempt {}/{})",
+                    reconnecting.attempts, MAX_RECONNECT_ATTEMPTS
+                ));
+                if ui.button("Back to Main Menu").clicked() {
+                    leave_combat.send(LeaveCombatCommand::quit());
+                }
+            })
+        });
+}
+
+fn absent_players_ui(
+    mut egui: EguiContexts,
+    absent_players: Option<Res<AbsentPlayers>>,
+    connected_players: Option<Res<ConnectedPlayers>>,
+) {
+    let (Some(absent_players), Some(connected_players)) = (absent_players, connected_players)
+    else {
+        return;
+    };
+
+    if absent_players.players.is_empty() {
+        return;
+    }
+
+    egui::Window::new("Waiting for players")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_TOP, egui::Vec2::ZERO)
+        .show(egui.ctx_mut(), |ui| {
+            for player_id in &absent_players.players {
+                let name = connected_players
+                    .players
+                    .get(player_id)
+                    .map_or("Unknown player", |x| x.name.as_str());
+                ui.label(format!("{} lost their connection.", name));
+            }
+        });
+}
@@ -0,0 +1,129 @@
+use crate::game::combat::PendingCombatSnapshot;
+use crate::networking::NetworkState;
+use crate::ApplicationState;
+use bevy::app::{App, Plugin};
+use bevy::prelude::*;
+use game_common::combat_data::CombatData;
+use game_common::desync;
+use game_common::network_events::client_to_server::ClientToServerMessage;
+use game_common::network_events::server_to_client;
+use game_common::network_events::server_to_client::GameSnapshot;
+use game_common::player_resources::PlayerResources;
+
+pub struct DesyncDetectionPlugin;
+impl Plugin for DesyncDetectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                on_state_checksum
+                    .run_if(on_event::<server_to_client::StateChecksum>())
+                    .after(ApplyServerUpdates),
+                log_resync_differences
+                    .run_if(on_event::<server_to_client::ResumeGame>())
+                    .run_if(resource_exists::<ResyncRequested>),
+            )
+                .run_if(in_state(ApplicationState::InGame))
+                .run_if(in_state(NetworkState::Connected)),
+        );
+        app.add_systems(OnExit(ApplicationState::InGame), clear_resync_request);
+    }
+}
+
+/// Systems which apply the server's updates to our local state.
+/// Checksums are only compared once all of them ran, so updates arriving in the same frame are accounted for.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApplyServerUpdates;
+
+/// Exists while we are waiting for the server to send us a snapshot after noticing a desync.
+#[derive(Resource)]
+struct ResyncRequested;
+
+fn on_state_checksum(
+    mut commands: Commands,
+    mut events: EventReader<server_to_client::StateChecksum>,
+    player_resources: Option<Res<PlayerResources>>,
+    combat_data: Option<Res<CombatData>>,
+    pending_combat_snapshot: Option<Res<PendingCombatSnapshot>>,
+    resync_requested: Option<Res<ResyncRequested>>,
+    mut client_to_server_messages: EventWriter<ClientToServerMessage>,
+) {
+    for event in events.read() {
+        if resync_requested.is_some() {
+            continue;
+        }
+
+        let mut desynced = Vec::new();
+        if let Some(player_resources) = &player_resources {
+            if player_resources.content_hash() != event.player_resources {
+                desynced.push("PlayerResources");
+            }
+        }
+
+        // While combat is still loading, our CombatData might not exist yet or belong to the previous combat
+        if let (Some(expected), Some(combat_data), None) =
+            (event.combat_data, &combat_data, &pending_combat_snapshot)
+        {
+            if combat_data.content_hash() != expected {
+                desynced.push("CombatData");
+            }
+        }
+
+        if !desynced.is_empty() {
+            error!(
+                "Desync detected in {}, requesting a resync.",
+                desynced.join(" and ")
+            );
+            commands.insert_resource(ResyncRequested);
+            client_to_server_messages.send(ClientToServerMessage::RequestResync);
+            return;
+        }
+    }
+}
+
+fn log_resync_differences(
+    mut commands: Commands,
+    mut events: EventReader<server_to_client::ResumeGame>,
+    player_resources: Option<Res<PlayerResources>>,
+    combat_data: Option<Res<CombatData>>,
+) {
+    for event in events.read() {
+        let mut differences = Vec::new();
+        if let Some(player_resources) = &player_resources {
+            differences.extend(
+                desync::diff_player_resources(player_resources, &event.player_resources)
+                    .into_iter()
+                    .map(|x| format!("PlayerResources.{x}")),
+            );
+        }
+        if let (
+            Some(combat_data),
+            GameSnapshot::Combat {
+                combat_data: remote,
+                ..
+            },
+        ) = (&combat_data, &event.state)
+        {
+            differences.extend(
+                desync::diff_combat_data(combat_data, remote)
+                    .into_iter()
+                    .map(|x| format!("CombatData.{x}")),
+            );
+        }
+
+        if differences.is_empty() {
+            warn!("Received resync, but unable to find any differences to the local state.");
+        } else {
+            error!(
+                "Resyncing, local state differed in:\n{}",
+                differences.join("\n")
+            );
+        }
+    }
+
+    commands.remove_resource::<ResyncRequested>();
+}
+
+fn clear_resync_request(mut commands: Commands) {
+    commands.remove_resource::<ResyncRequested>();
+}
@@ -1,12 +1,16 @@
 use crate::game::choose_between_units;
 use crate::game::combat::CombatPlugin;
+use crate::game::connection_status::ConnectionStatusPlugin;
+use crate::game::desync_detection::{ApplyServerUpdates, DesyncDetectionPlugin};
+use crate::game::map_cache;
+use crate::game::resume_game::ResumeGamePlugin;
 use crate::map::SpawnMapCommand;
 use crate::networking::NetworkState;
 use crate::ApplicationState;
 use bevy::app::{App, Plugin};
 use bevy::log::error;
 use bevy::prelude::*;
-use game_common::game_map::GameMap;
+use game_common::network_events::client_to_server::{ClientToServerMessage, RequestMap};
 use game_common::network_events::server_to_client;
 use game_common::player_resources::PlayerResources;
 
@@ -15,13 +19,19 @@ impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(CombatPlugin);
         app.add_plugins(choose_between_units::ChooseBetweenUnitsPlugin);
+        app.add_plugins(ConnectionStatusPlugin);
+        app.add_plugins(DesyncDetectionPlugin);
+        app.add_plugins(ResumeGamePlugin);
         app.init_state::<GameState>();
         app.add_systems(OnEnter(ApplicationState::InGame), init_state);
         app.add_systems(
             Update,
             (
                 load_map_listener.run_if(on_event::<server_to_client::LoadMap>()),
-                add_unit_listener.run_if(on_event::<server_to_client::AddUnit>()),
+                map_data_listener.run_if(on_event::<server_to_client::MapData>()),
+                add_unit_listener
+                    .run_if(on_event::<server_to_client::AddUnit>())
+                    .in_set(ApplyServerUpdates),
             )
                 .run_if(in_state(NetworkState::Connected)),
         );
@@ -36,22 +46,44 @@ fn load_map_listener(
     mut commands: Commands,
     mut incoming_events: EventReader<server_to_client::LoadMap>,
     mut outgoing_events: EventWriter<SpawnMapCommand>,
+    mut client_to_server_messages: EventWriter<ClientToServerMessage>,
     mut next_game_state: ResMut<NextState<GameState>>,
 ) {
     for event in incoming_events.read() {
-        match GameMap::load_from_file(&event.path) {
-            Ok(map) => {
+        match map_cache::load_cached_map(event.hash) {
+            Some(map) => {
                 commands.insert_resource(map);
                 outgoing_events.send(SpawnMapCommand {});
                 next_game_state.set(GameState::Combat);
             }
-            Err(e) => {
-                error!("Failed to load map {} - error: {:?}", event.path, e)
+            None => {
+                client_to_server_messages.send(ClientToServerMessage::RequestMap(RequestMap {
+                    hash: event.hash,
+                }));
             }
         }
     }
 }
 
+fn map_data_listener(
+    mut commands: Commands,
+    mut incoming_events: EventReader<server_to_client::MapData>,
+    mut outgoing_events: EventWriter<SpawnMapCommand>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    for event in incoming_events.read() {
+        if event.map.content_hash() != event.hash {
+            error!("Received map does not match its hash {}!", event.hash);
+            continue;
+        }
+
+        map_cache::cache_map(event.hash, &event.map);
+        commands.insert_resource(event.map.clone());
+        outgoing_events.send(SpawnMapCommand {});
+        next_game_state.set(GameState::Combat);
+    }
+}
+
 fn add_unit_listener(
     mut events: EventReader<server_to_client::AddUnit>,
     mut player_resources: ResMut<PlayerResources>,
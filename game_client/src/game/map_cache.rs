@@ -0,0 +1,39 @@
+use bevy::log::{error, warn};
+use game_common::game_map::{GameMap, MapFormat, MapHash};
+use std::fs;
+use std::path::PathBuf;
+
+/// Maps received from servers are stored in here, so they only need to be downloaded once.
+pub const MAP_CACHE_DIRECTORY: &str = "map_cache";
+
+fn cached_map_path(hash: MapHash) -> PathBuf {
+    PathBuf::from(MAP_CACHE_DIRECTORY).join(format!("{}.map", hash))
+}
+
+/// Returns the cached map with the given hash, as long as its content still matches.
+#[must_use]
+pub fn load_cached_map(hash: MapHash) -> Option<GameMap> {
+    let path = cached_map_path(hash);
+    if !path.exists() {
+        return None;
+    }
+
+    let map = GameMap::load_from_file(path.to_str()?).ok()?;
+    if map.content_hash() != hash {
+        warn!("Cached map {} has been modified, ignoring it.", hash);
+        return None;
+    }
+
+    Some(map)
+}
+
+pub fn cache_map(hash: MapHash, map: &GameMap) {
+    if let Err(e) = fs::create_dir_all(MAP_CACHE_DIRECTORY) {
+        error!("Was unable to create map cache directory: {:?}", e);
+        return;
+    }
+
+    if let Some(path) = cached_map_path(hash).to_str() {
+        let _ = map.write_to_disk_as(path, MapFormat::Binary);
+    }
+}
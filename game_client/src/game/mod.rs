@@ -1,5 +1,9 @@
 mod choose_between_units;
 mod combat;
+mod connection_status;
+mod desync_detection;
 pub mod game_plugin;
+mod map_cache;
+mod resume_game;
 pub mod sprite_builder;
 mod ui_utils;
@@ -0,0 +1,79 @@
+use crate::game::combat::{CombatState, LocalCombatData, PendingCombatSnapshot};
+use crate::game::desync_detection::ApplyServerUpdates;
+use crate::game::game_plugin::GameState;
+use crate::map::{DespawnMapCommand, MapState};
+use crate::networking::NetworkState;
+use crate::ApplicationState;
+use bevy::app::{App, Plugin};
+use bevy::prelude::*;
+use game_common::combat_data::CombatData;
+use game_common::network_events::client_to_server::ClientToServerMessage;
+use game_common::network_events::server_to_client;
+use game_common::network_events::server_to_client::{GameSnapshot, LoadMap};
+
+pub struct ResumeGamePlugin;
+impl Plugin for ResumeGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            on_resume_game
+                .run_if(on_event::<server_to_client::ResumeGame>())
+                .in_set(ApplyServerUpdates)
+                .run_if(in_state(ApplicationState::InGame))
+                .run_if(in_state(NetworkState::Connected)),
+        );
+    }
+}
+
+/// Throws away whatever we displayed before losing our connection and rebuilds it from the server's snapshot.
+fn on_resume_game(
+    mut commands: Commands,
+    mut events: EventReader<server_to_client::ResumeGame>,
+    map_state: Res<State<MapState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_combat_state: ResMut<NextState<CombatState>>,
+    mut despawn_map_command: EventWriter<DespawnMapCommand>,
+    mut load_map: EventWriter<LoadMap>,
+    mut choose_between_units: EventWriter<server_to_client::ChooseBetweenUnits>,
+    mut client_to_server_messages: EventWriter<ClientToServerMessage>,
+) {
+    for event in events.read() {
+        commands.insert_resource(event.player_resources.clone());
+        next_combat_state.set(CombatState::WaitingForServer);
+
+        if let GameSnapshot::Combat {
+            map_hash,
+            combat_data,
+        } = &event.state
+        {
+            // Respawning the map will also restore the combat, see PendingCombatSnapshot
+            commands.insert_resource(PendingCombatSnapshot {
+                combat_data: combat_data.clone(),
+            });
+            load_map.send(LoadMap { hash: *map_hash });
+            continue;
+        }
+
+        commands.remove_resource::<CombatData>();
+        commands.remove_resource::<LocalCombatData>();
+        if map_state.get() != &MapState::Unloaded {
+            despawn_map_command.send(DespawnMapCommand {});
+        }
+
+        match &event.state {
+            GameSnapshot::WaitingForOthers => {
+                next_game_state.set(GameState::Inactive);
+            }
+            GameSnapshot::PickUnit { units } => {
+                choose_between_units.send(server_to_client::ChooseBetweenUnits {
+                    units: units.clone(),
+                });
+            }
+            GameSnapshot::CombatFinished => {
+                next_game_state.set(GameState::Inactive);
+                client_to_server_messages.send(ClientToServerMessage::Proceed);
+            }
+            GameSnapshot::Combat { .. } => unreachable!(),
+        }
+    }
+}
@@ -45,4 +45,13 @@ pub fn print_unit_definition_info(ui: &mut Ui, unit: &UnitDefinition, game_data:
         let skill = &game_data.skills[&skill];
         ui.label(skill.name.clone());
     }
+
+    ui.label(RichText::new("Reaction").heading());
+    if let Some(reaction) = unit.reaction {
+        let reaction = &game_data.reactions[&reaction];
+        ui.label(reaction.name.clone())
+            .on_hover_text(reaction.description.clone());
+    } else {
+        ui.label("None".to_string());
+    }
 }
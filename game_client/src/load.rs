@@ -1,4 +1,5 @@
 use crate::map::METERS_PER_TILE_HEIGHT_UNIT;
+use bevy::app::AppExit;
 use bevy::prelude::*;
 use bevy::render::mesh::{Indices, PrimitiveTopology};
 use bevy::render::render_asset::RenderAssetUsages;
@@ -14,7 +15,10 @@ use hexx::{ColumnMeshBuilder, HexLayout, MeshInfo, UVOptions};
 pub struct LoadPlugin;
 impl Plugin for LoadPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, (load_materials, load_meshes, load_sprites));
+        app.add_systems(
+            Startup,
+            (load_materials, load_meshes, load_sprites, load_game_data),
+        );
     }
 }
 
@@ -23,6 +27,7 @@ pub struct HexagonMaterials {
     pub top: HexagonMaterialsForSideOrTop,
     pub sides: HexagonMaterialsForSideOrTop,
     pub fluid: HexagonMaterialsForFluid,
+    pub prop: Handle<StandardMaterial>,
 }
 
 #[derive(Debug)]
@@ -53,6 +58,8 @@ impl HexagonMaterialsForSideOrTop {
 #[derive(Debug)]
 pub struct HexagonMaterialsForFluid {
     pub water: Handle<StandardMaterial>,
+    pub lava: Handle<StandardMaterial>,
+    pub slime: Handle<StandardMaterial>,
 }
 
 impl HexagonMaterialsForFluid {
@@ -60,6 +67,8 @@ impl HexagonMaterialsForFluid {
     pub fn surface_material(&self, fluid: &FluidKind) -> Handle<StandardMaterial> {
         match fluid {
             FluidKind::Water => self.water.clone(),
+            FluidKind::Lava => self.lava.clone(),
+            FluidKind::Slime => self.slime.clone(),
         }
     }
 }
@@ -77,6 +86,8 @@ pub struct HighlightMaterials {
 pub struct HexagonMeshes {
     pub flat: Handle<Mesh>,
     pub columns: HashMap<u8, Handle<Mesh>>,
+    /// A box one height level tall, which gets stretched to the height of each prop.
+    pub prop: Handle<Mesh>,
 }
 
 #[derive(Debug, Resource)]
@@ -101,7 +112,13 @@ pub fn load_meshes(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
         columns.insert(height, handle);
     }
 
-    commands.insert_resource(HexagonMeshes { flat, columns })
+    let prop = meshes.add(Cuboid::new(0.8, METERS_PER_TILE_HEIGHT_UNIT, 0.8));
+
+    commands.insert_resource(HexagonMeshes {
+        flat,
+        columns,
+        prop,
+    })
 }
 
 fn generate_hexagon_flat_mesh(hex_layout: &HexLayout) -> Mesh {
@@ -164,6 +181,17 @@ pub fn load_materials(
         alpha_mode: AlphaMode::Blend,
         ..default()
     });
+    let lava = materials.add(StandardMaterial {
+        base_color: Color::rgba(1.0, 0.3, 0.0, 0.9),
+        emissive: Color::rgb(0.8, 0.2, 0.0),
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    });
+    let slime = materials.add(StandardMaterial {
+        base_color: Color::rgba(0.4, 0.8, 0.1, 0.7),
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    });
 
     commands.insert_resource(HexagonMaterials {
         top: {
@@ -184,7 +212,8 @@ pub fn load_materials(
                 earth: generate_generic_side_mat(Color::TOMATO, &asset_server, &mut materials),
             }
         },
-        fluid: { HexagonMaterialsForFluid { water } },
+        fluid: { HexagonMaterialsForFluid { water, lava, slime } },
+        prop: generate_generic_top_mat(Color::BISQUE, &asset_server, &mut materials),
     });
 
     commands.insert_resource(HighlightMaterials {
@@ -279,5 +308,14 @@ pub fn load_sprites(mut commands: Commands, asset_server: Res<AssetServer>) {
         test_monster,
         test_monster_dead,
     });
-    commands.insert_resource(GameData::load());
+}
+
+pub fn load_game_data(mut commands: Commands, mut app_exit_events: EventWriter<AppExit>) {
+    match GameData::load() {
+        Ok(game_data) => commands.insert_resource(game_data),
+        Err(e) => {
+            error!("{}", e);
+            app_exit_events.send(AppExit);
+        }
+    }
 }
@@ -2,17 +2,23 @@ use std::process::Child;
 
 use bevy::app::{App, AppExit, Plugin};
 use bevy::prelude::{
-    error, in_state, info, on_event, resource_exists, warn, Commands, Event, EventReader,
-    EventWriter, IntoSystemConfigs, NextState, OnEnter, Reflect, Res, ResMut, Resource, State,
-    States, Update,
+    error, in_state, info, not, on_event, resource_exists, warn, Commands, Event, EventReader,
+    EventWriter, IntoSystemConfigs, Local, NextState, OnEnter, Reflect, Res, ResMut, Resource,
+    State, States, Update,
 };
 use bevy_egui::egui::Align2;
 use bevy_egui::{egui, EguiContexts};
 
-use game_common::network_events::client_to_server::ClientToServerMessage;
+use game_common::lobby::LobbyInfo;
+use game_common::network_events::client_to_server::{
+    ClientToServerMessage, CreateLobby, JoinLobby,
+};
 use game_common::network_events::server_to_client;
 
-use crate::networking::{Network, NetworkState};
+use crate::networking::{
+    ConnectedPlayers, ConnectionRefusedReason, CurrentLobby, Network, NetworkState,
+    ResumableSession,
+};
 use crate::ApplicationState;
 
 pub struct MainMenuPlugin;
@@ -20,7 +26,9 @@ impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<MenuState>();
         app.add_event::<HostLocalServerCommand>();
+        app.init_resource::<AvailableLobbies>();
         app.add_systems(OnEnter(ApplicationState::MainMenu), on_enter);
+        app.add_systems(OnEnter(NetworkState::Connected), request_lobby_list);
         app.add_systems(
             Update,
             (
@@ -35,7 +43,10 @@ impl Plugin for MainMenuPlugin {
                     (connection_menu).run_if(in_state(NetworkState::Connecting)),
                     (connection_menu).run_if(in_state(NetworkState::Authenticating)),
                     (
-                        play_menu,
+                        lobby_browser.run_if(not(resource_exists::<CurrentLobby>)),
+                        play_menu.run_if(resource_exists::<CurrentLobby>),
+                        lobby_list_listener.run_if(on_event::<server_to_client::LobbyList>()),
+                        request_lobby_list.run_if(on_event::<server_to_client::LeftLobby>()),
                         start_game_listener.run_if(on_event::<server_to_client::StartGame>()),
                     )
                         .run_if(in_state(NetworkState::Connected)),
@@ -54,6 +65,8 @@ fn on_enter(
     local_host: Option<ResMut<LocalHost>>,
 ) {
     menu_state.set(MenuState::MainMenu);
+    commands.remove_resource::<ResumableSession>();
+    commands.remove_resource::<CurrentLobby>();
 
     if let Some(mut network) = network {
         network.disconnect();
@@ -106,6 +119,7 @@ fn connection_menu(
     mut next_network_state: ResMut<NextState<NetworkState>>,
     mut next_menu_state: ResMut<NextState<MenuState>>,
     mut host_command: EventWriter<HostLocalServerCommand>,
+    refused_reason: Option<Res<ConnectionRefusedReason>>,
 ) {
     egui::Window::new("Connection Menu")
         .collapsible(false)
@@ -114,6 +128,12 @@ fn connection_menu(
         .anchor(Align2::CENTER_CENTER, egui::Vec2::ZERO)
         .show(egui.ctx_mut(), |ui| {
             ui.vertical_centered(|ui| {
+                if let Some(refused_reason) = &refused_reason {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        format!("Connection refused: {}", refused_reason.reason),
+                    );
+                }
                 ui.add_enabled_ui(network_state.get() == &NetworkState::Disconnected, |ui| {
                     if ui.button("Host").clicked() {
                         host_command.send(HostLocalServerCommand {});
@@ -131,10 +151,81 @@ fn connection_menu(
         });
 }
 
+/// The most recent list of lobbies we received from the server.
+#[derive(Resource, Default)]
+struct AvailableLobbies {
+    lobbies: Vec<LobbyInfo>,
+}
+
+fn request_lobby_list(mut event_writer: EventWriter<ClientToServerMessage>) {
+    event_writer.send(ClientToServerMessage::ListLobbies);
+}
+
+fn lobby_list_listener(
+    mut incoming_events: EventReader<server_to_client::LobbyList>,
+    mut available_lobbies: ResMut<AvailableLobbies>,
+) {
+    for x in incoming_events.read() {
+        available_lobbies.lobbies = x.lobbies.clone();
+    }
+}
+
+fn lobby_browser(
+    mut egui: EguiContexts,
+    mut event_writer: EventWriter<ClientToServerMessage>,
+    mut network: ResMut<Network>,
+    available_lobbies: Res<AvailableLobbies>,
+    mut lobby_name: Local<String>,
+) {
+    egui::Window::new("Lobbies")
+        .collapsible(false)
+        .resizable(false)
+        .movable(false)
+        .anchor(Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(egui.ctx_mut(), |ui| {
+            ui.vertical_centered(|ui| {
+                if available_lobbies.lobbies.is_empty() {
+                    ui.label("No lobbies yet. Create one!");
+                }
+                for lobby in &available_lobbies.lobbies {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} ({} players)", lobby.name, lobby.player_count));
+                        ui.add_enabled_ui(!lobby.in_game, |ui| {
+                            if ui.button("Join").clicked() {
+                                event_writer.send(ClientToServerMessage::JoinLobby(JoinLobby {
+                                    lobby_id: lobby.id,
+                                }));
+                            }
+                        });
+                    });
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut *lobby_name);
+                    ui.add_enabled_ui(!lobby_name.trim().is_empty(), |ui| {
+                        if ui.button("Create").clicked() {
+                            event_writer.send(ClientToServerMessage::CreateLobby(CreateLobby {
+                                name: lobby_name.trim().to_string(),
+                            }));
+                        }
+                    });
+                });
+                if ui.button("Refresh").clicked() {
+                    event_writer.send(ClientToServerMessage::ListLobbies);
+                }
+                if ui.button("Disconnect").clicked() {
+                    network.disconnect();
+                }
+            })
+        });
+}
+
 fn play_menu(
     mut egui: EguiContexts,
     mut event_writer: EventWriter<ClientToServerMessage>,
     mut network: ResMut<Network>,
+    current_lobby: Res<CurrentLobby>,
+    connected_players: Option<Res<ConnectedPlayers>>,
 ) {
     egui::Window::new("Play Menu")
         .collapsible(false)
@@ -143,9 +234,21 @@ fn play_menu(
         .anchor(Align2::CENTER_CENTER, egui::Vec2::ZERO)
         .show(egui.ctx_mut(), |ui| {
             ui.vertical_centered(|ui| {
+                ui.heading(&current_lobby.info.name);
+                if let Some(connected_players) = &connected_players {
+                    let mut players: Vec<_> = connected_players.players.values().collect();
+                    players.sort_by_key(|x| x.id);
+                    for player in players {
+                        ui.label(&player.name);
+                    }
+                }
+                ui.separator();
                 if ui.button("Start Game").clicked() {
                     event_writer.send(ClientToServerMessage::StartGame);
                 }
+                if ui.button("Leave Lobby").clicked() {
+                    event_writer.send(ClientToServerMessage::LeaveLobby);
+                }
                 if ui.button("Disconnect").clicked() {
                     network.disconnect();
                 }
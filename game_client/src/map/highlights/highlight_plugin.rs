@@ -99,10 +99,11 @@ fn clean_up(mut commands: Commands) {
 fn highlight_position(map: &GameMap, hex: &Hex, extra_height: f32) -> Vec3 {
     let position = HEX_LAYOUT.hex_to_world_pos(hex.clone());
     let height = if let Some(tile) = map.tiles.get(hex) {
+        let surface_height = tile.surface_height() as f32;
         if let Some(fluid) = &tile.fluid {
-            tile.height as f32 + fluid.height
+            (tile.height as f32 + fluid.height).max(surface_height)
         } else {
-            tile.height as f32
+            surface_height
         }
     } else {
         error!("Was unable to find a tile for {:?} in map.", hex);
@@ -49,4 +49,5 @@ pub struct MapTileEntityBundle {
     pub top: Entity,
     pub side: Option<Entity>,
     pub fluid: Option<Entity>,
+    pub props: Vec<Entity>,
 }
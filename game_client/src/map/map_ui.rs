@@ -5,6 +5,7 @@ use bevy::app::{App, First, Plugin, Update};
 use bevy::prelude::*;
 use bevy_egui::egui::{Align2, Pos2};
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
+use game_common::game_data::GameData;
 use game_common::game_map::GameMap;
 
 pub(in crate::map) struct MapUiPlugin;
@@ -41,7 +42,12 @@ fn detect_mouse_cursor_over_ui(
     }
 }
 
-fn tile_cursor_ui(mut egui: EguiContexts, cursor: Res<CursorOnTile>, map: Res<GameMap>) {
+fn tile_cursor_ui(
+    mut egui: EguiContexts,
+    cursor: Res<CursorOnTile>,
+    map: Res<GameMap>,
+    game_data: Res<GameData>,
+) {
     let text = if let Some(tile) = map.tiles.get(&cursor.hex) {
         if tile.height == 0 {
             return;
@@ -54,6 +60,17 @@ fn tile_cursor_ui(mut egui: EguiContexts, cursor: Res<CursorOnTile>, map: Res<Ga
         }
         lines.push(format!("Height: {}", tile.height));
         lines.push(format!("Surface: {}", tile.surface));
+        for prop in &tile.props {
+            let name = game_data
+                .props
+                .get(&prop.prop_id)
+                .map_or("Unknown Prop", |definition| &definition.name);
+            if let Some(destructible) = prop.components.destructible {
+                lines.push(format!("{}: {} HP", name, destructible.hp));
+            } else {
+                lines.push(name.to_string());
+            }
+        }
 
         lines.join("\n")
     } else {
@@ -13,7 +13,7 @@ pub fn unit_position_on_hexagon(hex: Hex, map: &GameMap) -> Vec3 {
             );
             0.0
         }
-        Some(tile_data) => tile_data.height as f32 * METERS_PER_TILE_HEIGHT_UNIT,
+        Some(tile_data) => tile_data.surface_height() as f32 * METERS_PER_TILE_HEIGHT_UNIT,
     };
 
     let hex_pos = HEX_LAYOUT.hex_to_world_pos(hex);
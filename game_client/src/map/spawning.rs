@@ -7,7 +7,7 @@ use crate::map::METERS_PER_TILE_HEIGHT_UNIT;
 use bevy::app::{App, Last, Plugin, Startup};
 use bevy::core::Name;
 use bevy::hierarchy::{BuildChildren, DespawnRecursiveExt};
-use bevy::math::{EulerRot, Quat};
+use bevy::math::{EulerRot, Quat, Vec3};
 use bevy::pbr::{
     AmbientLight, DirectionalLight, DirectionalLightBundle, NotShadowCaster, PbrBundle,
 };
@@ -160,6 +160,8 @@ pub fn spawn_map(
             None
         };
 
+        let props = spawn_prop_entities(&mut commands, &materials, &meshes, tile_data, hex, parent);
+
         entities.insert(
             hex,
             MapTileEntityBundle {
@@ -167,6 +169,7 @@ pub fn spawn_map(
                 top,
                 side,
                 fluid,
+                props,
             },
         );
     }
@@ -249,3 +252,46 @@ pub fn spawn_fluid_entity(
             .id(),
     )
 }
+
+/// Spawns one entity for every prop on the tile, stacked on top of each other.
+pub fn spawn_prop_entities(
+    commands: &mut Commands,
+    materials: &HexagonMaterials,
+    meshes: &HexagonMeshes,
+    tile_data: &TileData,
+    hex: Hex,
+    parent: Entity,
+) -> Vec<Entity> {
+    let mut bottom = tile_data.height as f32;
+    tile_data
+        .props
+        .iter()
+        .map(|prop| {
+            // Props which don't add any height still need to be visible
+            let height = prop.components.extra_height.max(1) as f32;
+            let offset = Vec3::from(prop.components.offset);
+            let center = Vec3::new(
+                0.0,
+                (bottom + height / 2.0) * METERS_PER_TILE_HEIGHT_UNIT,
+                0.0,
+            );
+            bottom += prop.components.extra_height as f32;
+
+            commands
+                .spawn((
+                    PbrBundle {
+                        transform: Transform::from_translation(center + offset)
+                            .with_scale(Vec3::new(1.0, height, 1.0)),
+                        mesh: meshes.prop.clone(),
+                        material: materials.prop.clone(),
+                        ..default()
+                    },
+                    RaycastMesh::<TileRaycastSet>::default(),
+                    HexagonTileComponent { hex },
+                    Name::new(format!("Tile Prop {} [{},{}]", prop.prop_id, hex.x, hex.y)),
+                ))
+                .set_parent(parent)
+                .id()
+        })
+        .collect()
+}
@@ -11,7 +11,9 @@ use game_common::game_map::{GameMap, TileData};
 
 use crate::load::{HexagonMaterials, HexagonMeshes};
 use crate::map::map_plugin::{HexagonTileComponent, MapTileEntities, MapTileEntityBundle};
-use crate::map::spawning::{are_tile_sides_necessary, spawn_fluid_entity, spawn_side_entity};
+use crate::map::spawning::{
+    are_tile_sides_necessary, spawn_fluid_entity, spawn_prop_entities, spawn_side_entity,
+};
 use crate::map::METERS_PER_TILE_HEIGHT_UNIT;
 
 /// Listens to `TileChangeEvent`s in order to push those changes into the existing tile entities.
@@ -73,6 +75,15 @@ pub fn update_tile_entity(
                     entities,
                 );
 
+                update_props(
+                    &mut commands,
+                    &meshes,
+                    &materials,
+                    &event.hex,
+                    tile_data,
+                    entities,
+                );
+
                 // TODO: Neighbor updates should be triggered separately to avoid updating tiles multiple times when editing an area instead of a single tile... if that ever becomes an option.
                 for neighbor in event.hex.all_neighbors() {
                     if let Some(neighbor_data) = map.tiles.get(&neighbor) {
@@ -206,3 +217,26 @@ fn update_fluid(
         }
     }
 }
+
+fn update_props(
+    commands: &mut Commands,
+    meshes: &HexagonMeshes,
+    materials: &HexagonMaterials,
+    hex: &Hex,
+    tile_data: &TileData,
+    entities: &mut MapTileEntityBundle,
+) {
+    // Props are rarely changed, so just respawning all of them is good enough
+    for prop_entity in entities.props.drain(..) {
+        commands.entity(prop_entity).remove_parent().despawn();
+    }
+
+    entities.props = spawn_prop_entities(
+        commands,
+        materials,
+        meshes,
+        tile_data,
+        *hex,
+        entities.parent,
+    );
+}
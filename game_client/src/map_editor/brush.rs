@@ -0,0 +1,176 @@
+use crate::map_editor::map_editor_action::MapEditorAction;
+use crate::map_editor::map_editor_tool::MapEditorTool;
+use bevy::prelude::{Res, ResMut, Resource};
+use bevy::utils::HashSet;
+use game_common::game_map::{GameMap, TileData};
+use hexx::Hex;
+use leafwing_input_manager::action_state::ActionState;
+use std::collections::VecDeque;
+use std::fmt::Formatter;
+
+pub const MAX_BRUSH_RADIUS: u32 = 10;
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum BrushShape {
+    /// Every tile within the brush radius around the cursor.
+    #[default]
+    Area,
+    /// Only the tiles at exactly the brush radius around the cursor.
+    Ring,
+    /// A straight line from where the mouse got pressed down to the cursor.
+    Line,
+    /// Every connected tile which looks just like the one below the cursor.
+    /// Only works for surfaces and fluids, other tools just affect the tile below the cursor.
+    Fill,
+}
+
+impl std::fmt::Display for BrushShape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BrushShape::Area => write!(f, "Area"),
+            BrushShape::Ring => write!(f, "Ring"),
+            BrushShape::Line => write!(f, "Line"),
+            BrushShape::Fill => write!(f, "Fill"),
+        }
+    }
+}
+
+/// Decides which tiles a tool is applied to.
+#[derive(Resource, Debug, Default)]
+pub struct Brush {
+    pub radius: u32,
+    pub shape: BrushShape,
+}
+
+impl Brush {
+    /// All hexes within the map which are affected when `tool` is used at `cursor`.
+    /// `origin` is the hex where the current stroke started.
+    #[must_use]
+    pub fn affected_hexes(
+        &self,
+        cursor: Hex,
+        origin: Option<Hex>,
+        tool: &MapEditorTool,
+        map: &GameMap,
+    ) -> Vec<Hex> {
+        let hexes: Vec<Hex> = match self.shape {
+            BrushShape::Area => cursor.range(self.radius).collect(),
+            BrushShape::Ring => cursor.ring(self.radius).collect(),
+            BrushShape::Line => origin.unwrap_or(cursor).line_to(cursor).collect(),
+            BrushShape::Fill => match tool {
+                MapEditorTool::PaintSurface(_) => {
+                    flood_fill(map, cursor, |a, b| a.surface == b.surface)
+                }
+                MapEditorTool::RaiseFluid(_) | MapEditorTool::LowerFluid => {
+                    flood_fill(map, cursor, |a, b| {
+                        a.height == b.height
+                            && a.fluid.as_ref().map(|x| x.kind) == b.fluid.as_ref().map(|x| x.kind)
+                    })
+                }
+                _ => vec![cursor],
+            },
+        };
+
+        hexes
+            .into_iter()
+            .filter(|hex| map.tiles.contains_key(hex))
+            .collect()
+    }
+}
+
+/// Every hex connected to `start` through tiles which are `similar` to the tile at `start`.
+fn flood_fill(
+    map: &GameMap,
+    start: Hex,
+    similar: impl Fn(&TileData, &TileData) -> bool,
+) -> Vec<Hex> {
+    let Some(start_tile) = map.tiles.get(&start) else {
+        return Vec::new();
+    };
+
+    let mut result = vec![start];
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(hex) = queue.pop_front() {
+        for neighbor in hex.all_neighbors() {
+            if !visited.insert(neighbor) {
+                continue;
+            }
+
+            if map
+                .tiles
+                .get(&neighbor)
+                .is_some_and(|tile| similar(start_tile, tile))
+            {
+                result.push(neighbor);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    result
+}
+
+pub fn change_brush_size(input_state: Res<ActionState<MapEditorAction>>, mut brush: ResMut<Brush>) {
+    if input_state.just_pressed(&MapEditorAction::IncreaseBrushSize) {
+        brush.radius = (brush.radius + 1).min(MAX_BRUSH_RADIUS);
+    }
+    if input_state.just_pressed(&MapEditorAction::DecreaseBrushSize) {
+        brush.radius = brush.radius.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::map_editor::brush::{Brush, BrushShape};
+    use crate::map_editor::map_editor_tool::MapEditorTool;
+    use game_common::game_map::{GameMap, TileSurface};
+    use hexx::Hex;
+
+    #[test]
+    fn fill_should_stop_at_different_surfaces() {
+        let mut map = GameMap::new(2);
+        for hex in Hex::ZERO.ring(1) {
+            map.tiles.get_mut(&hex).unwrap().surface = TileSurface::Stone;
+        }
+
+        let brush = Brush {
+            radius: 0,
+            shape: BrushShape::Fill,
+        };
+        let tool = MapEditorTool::PaintSurface(TileSurface::Sand);
+        assert_eq!(
+            vec![Hex::ZERO],
+            brush.affected_hexes(Hex::ZERO, None, &tool, &map)
+        );
+        assert_eq!(
+            12,
+            brush
+                .affected_hexes(Hex::new(2, 0), None, &tool, &map)
+                .len()
+        );
+    }
+
+    #[test]
+    fn shapes_should_be_limited_to_the_map() {
+        let map = GameMap::new(2);
+        let tool = MapEditorTool::RaiseTiles;
+        let mut brush = Brush {
+            radius: 1,
+            shape: BrushShape::Area,
+        };
+        assert_eq!(
+            4,
+            brush
+                .affected_hexes(Hex::new(2, 0), None, &tool, &map)
+                .len()
+        );
+
+        brush.shape = BrushShape::Ring;
+        assert_eq!(6, brush.affected_hexes(Hex::ZERO, None, &tool, &map).len());
+
+        brush.shape = BrushShape::Line;
+        let line = brush.affected_hexes(Hex::new(2, 0), Some(Hex::new(-2, 0)), &tool, &map);
+        assert_eq!(5, line.len());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::map::{MapState, TileChangeEvent};
+use crate::map_editor::map_editor_action::MapEditorAction;
+use crate::map_editor::tools::events::{AddSpawnMarkerEvent, RemoveSpawnMarkerEvent};
+use crate::ApplicationState;
+use bevy::app::{App, Plugin, Update};
+use bevy::prelude::{in_state, EventWriter, IntoSystemConfigs, OnEnter, Res, ResMut, Resource};
+use game_common::game_map::{GameMap, TileData};
+use hexx::Hex;
+use leafwing_input_manager::action_state::ActionState;
+
+/// Older strokes get forgotten once the history grows beyond this.
+const MAX_HISTORY_LENGTH: usize = 100;
+
+pub struct EditHistoryPlugin;
+impl Plugin for EditHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditHistory>();
+        app.add_systems(
+            Update,
+            undo_or_redo
+                .run_if(in_state(ApplicationState::MapEditor))
+                .run_if(in_state(MapState::Ready)),
+        );
+        app.add_systems(
+            OnEnter(MapState::Ready),
+            clear_history.run_if(in_state(ApplicationState::MapEditor)),
+        );
+    }
+}
+
+/// A single tile which got changed by a tool.
+#[derive(Debug, Clone)]
+struct TileEdit {
+    hex: Hex,
+    before: TileData,
+    after: TileData,
+}
+
+/// Every tile which got changed during one click or mouse drag, so they can be reverted all at once.
+#[derive(Debug, Default)]
+struct EditStroke {
+    edits: Vec<TileEdit>,
+}
+
+/// Keeps track of all changes made in the map editor.
+#[derive(Resource, Debug, Default)]
+pub struct EditHistory {
+    undo_stack: Vec<EditStroke>,
+    redo_stack: Vec<EditStroke>,
+    is_stroke_in_progress: bool,
+}
+
+impl EditHistory {
+    /// Every following change will be grouped into a new stroke, until this is called again.
+    pub fn begin_stroke(&mut self) {
+        self.is_stroke_in_progress = false;
+    }
+
+    pub fn record(&mut self, hex: Hex, before: TileData, after: TileData) {
+        if !self.is_stroke_in_progress {
+            self.is_stroke_in_progress = true;
+            self.redo_stack.clear();
+            self.undo_stack.push(EditStroke::default());
+            if self.undo_stack.len() > MAX_HISTORY_LENGTH {
+                self.undo_stack.remove(0);
+            }
+        }
+
+        if let Some(stroke) = self.undo_stack.last_mut() {
+            stroke.edits.push(TileEdit { hex, before, after });
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.is_stroke_in_progress = false;
+    }
+
+    /// Reverts the most recent stroke. Returns the hex and previous data of every tile which got changed.
+    fn undo(&mut self, map: &mut GameMap) -> Vec<(Hex, TileData)> {
+        let Some(stroke) = self.undo_stack.pop() else {
+            return Vec::new();
+        };
+
+        self.is_stroke_in_progress = false;
+        let result = stroke
+            .edits
+            .iter()
+            .rev()
+            .filter_map(|edit| replace_tile(map, edit.hex, edit.before.clone()))
+            .collect();
+        self.redo_stack.push(stroke);
+        result
+    }
+
+    /// Repeats the most recently reverted stroke. Returns the hex and previous data of every tile which got changed.
+    fn redo(&mut self, map: &mut GameMap) -> Vec<(Hex, TileData)> {
+        let Some(stroke) = self.redo_stack.pop() else {
+            return Vec::new();
+        };
+
+        self.is_stroke_in_progress = false;
+        let result = stroke
+            .edits
+            .iter()
+            .filter_map(|edit| replace_tile(map, edit.hex, edit.after.clone()))
+            .collect();
+        self.undo_stack.push(stroke);
+        result
+    }
+}
+
+fn replace_tile(map: &mut GameMap, hex: Hex, data: TileData) -> Option<(Hex, TileData)> {
+    let tile = map.tiles.get_mut(&hex)?;
+    Some((hex, std::mem::replace(tile, data)))
+}
+
+fn undo_or_redo(
+    mut map: ResMut<GameMap>,
+    mut history: ResMut<EditHistory>,
+    input_state: Res<ActionState<MapEditorAction>>,
+    mut tile_change_event: EventWriter<TileChangeEvent>,
+    mut add_spawn_marker_event: EventWriter<AddSpawnMarkerEvent>,
+    mut remove_spawn_marker_event: EventWriter<RemoveSpawnMarkerEvent>,
+) {
+    let changes = if input_state.just_pressed(&MapEditorAction::Undo) {
+        history.undo(&mut map)
+    } else if input_state.just_pressed(&MapEditorAction::Redo) {
+        history.redo(&mut map)
+    } else {
+        return;
+    };
+
+    for (hex, old_data) in changes {
+        let new_spawn_zone = map.tiles[&hex].spawn_zone;
+        match (old_data.spawn_zone, new_spawn_zone) {
+            (None, Some(team)) => {
+                add_spawn_marker_event.send(AddSpawnMarkerEvent { hex, team });
+            }
+            (Some(_), None) => {
+                remove_spawn_marker_event.send(RemoveSpawnMarkerEvent { hex });
+            }
+            _ => {}
+        }
+
+        tile_change_event.send(TileChangeEvent { hex, old_data });
+    }
+}
+
+fn clear_history(mut history: ResMut<EditHistory>) {
+    history.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::map_editor::edit_history::EditHistory;
+    use game_common::game_map::GameMap;
+    use hexx::Hex;
+
+    fn raise(map: &mut GameMap, history: &mut EditHistory, hex: Hex) {
+        let tile = map.tiles.get_mut(&hex).unwrap();
+        let before = tile.clone();
+        tile.height += 1;
+        history.record(hex, before, tile.clone());
+    }
+
+    #[test]
+    fn strokes_should_be_undone_and_redone_as_a_whole() {
+        let mut map = GameMap::new(2);
+        let mut history = EditHistory::default();
+        let original = map.clone();
+
+        history.begin_stroke();
+        raise(&mut map, &mut history, Hex::new(0, 0));
+        raise(&mut map, &mut history, Hex::new(1, 0));
+        let after_first_stroke = map.clone();
+
+        history.begin_stroke();
+        raise(&mut map, &mut history, Hex::new(0, 0));
+        let after_second_stroke = map.clone();
+
+        assert_eq!(1, history.undo(&mut map).len());
+        assert_eq!(after_first_stroke, map);
+        assert_eq!(2, history.undo(&mut map).len());
+        assert_eq!(original, map);
+        assert!(history.undo(&mut map).is_empty());
+
+        history.redo(&mut map);
+        history.redo(&mut map);
+        assert_eq!(after_second_stroke, map);
+    }
+
+    #[test]
+    fn new_changes_should_clear_redo_stack() {
+        let mut map = GameMap::new(2);
+        let mut history = EditHistory::default();
+
+        history.begin_stroke();
+        raise(&mut map, &mut history, Hex::new(0, 0));
+        history.undo(&mut map);
+
+        history.begin_stroke();
+        raise(&mut map, &mut history, Hex::new(1, 0));
+        assert!(history.redo(&mut map).is_empty());
+    }
+}
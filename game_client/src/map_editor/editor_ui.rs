@@ -1,4 +1,6 @@
 use crate::map::SpawnMapCommand;
+use crate::map_editor::brush::{Brush, BrushShape, MAX_BRUSH_RADIUS};
+use crate::map_editor::file_menu::{file_menu, reset_file_state};
 use crate::map_editor::map_editor_action::{MapEditorAction, ACTION_TO_TOOL};
 use crate::map_editor::map_editor_tool::MapEditorTool;
 use crate::ApplicationState;
@@ -6,8 +8,10 @@ use bevy::app::{App, Plugin};
 use bevy::prelude::*;
 use bevy_egui::egui::{Align2, Pos2};
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
-use game_common::game_map::GameMap;
-use game_common::TEST_MAP_NAME;
+use game_common::game_data::GameData;
+use game_common::game_map::map_generator::{generate_map, MapGeneratorSettings};
+use game_common::game_map::map_validation::{validate_map, MapIssueSeverity, MapValidationReport};
+use game_common::game_map::{GameMap, MapMetadata, MAX_HEIGHT};
 
 pub struct MapEditorUiPlugin;
 impl Plugin for MapEditorUiPlugin {
@@ -16,14 +20,27 @@ impl Plugin for MapEditorUiPlugin {
             app.add_plugins(EguiPlugin);
         }
 
+        app.add_systems(OnEnter(ApplicationState::MapEditor), reset_file_state);
         app.add_systems(
             Update,
-            (tool_view, menu_buttons).run_if(in_state(ApplicationState::MapEditor)),
+            (
+                tool_view,
+                file_menu,
+                metadata_view,
+                generator_view,
+                validation_view,
+            )
+                .run_if(in_state(ApplicationState::MapEditor)),
         );
     }
 }
 
-fn tool_view(mut egui: EguiContexts, mut current_tool: ResMut<MapEditorTool>) {
+fn tool_view(
+    mut egui: EguiContexts,
+    mut current_tool: ResMut<MapEditorTool>,
+    mut brush: ResMut<Brush>,
+    game_data: Res<GameData>,
+) {
     egui::Window::new("Editor Buttons")
         .title_bar(false)
         .collapsible(false)
@@ -41,46 +58,163 @@ fn tool_view(mut egui: EguiContexts, mut current_tool: ResMut<MapEditorTool>) {
                 }
             }
 
+            ui.separator();
+            let mut props: Vec<_> = game_data.props.values().collect();
+            props.sort_by_key(|prop| prop.id);
+            for prop in props {
+                if ui.button(format!("Spawn {}", prop.name)).clicked() {
+                    *current_tool = MapEditorTool::SpawnProp(prop.id);
+                }
+            }
+
+            ui.separator();
+            ui.add(egui::Slider::new(&mut brush.radius, 0..=MAX_BRUSH_RADIUS).text("Brush Radius"));
+            ui.horizontal(|ui| {
+                for shape in [
+                    BrushShape::Area,
+                    BrushShape::Ring,
+                    BrushShape::Line,
+                    BrushShape::Fill,
+                ] {
+                    ui.selectable_value(&mut brush.shape, shape, shape.to_string());
+                }
+            });
+
             ui.label(format!("Current: {}", *current_tool));
         });
 }
 
-fn menu_buttons(
+fn metadata_view(mut egui: EguiContexts, mut map: ResMut<GameMap>) {
+    egui::Window::new("Map Metadata")
+        .collapsible(true)
+        .resizable(false)
+        .anchor(Align2::RIGHT_BOTTOM, egui::Vec2::new(-5.0, -5.0))
+        .show(egui.ctx_mut(), |ui| {
+            // Editing metadata shouldn't cause the map to be considered changed
+            let metadata = &mut map.bypass_change_detection().metadata;
+            egui::Grid::new("metadata_grid").show(ui, |ui| {
+                ui.label("Name");
+                ui.text_edit_singleline(&mut metadata.name);
+                ui.end_row();
+                ui.label("Author");
+                ui.text_edit_singleline(&mut metadata.author);
+                ui.end_row();
+                ui.label("Teams");
+                ui.add(egui::DragValue::new(&mut metadata.team_count).clamp_range(1..=8));
+                ui.end_row();
+                ui.label("Units per Team");
+                ui.add(
+                    egui::DragValue::new(&mut metadata.recommended_unit_count).clamp_range(1..=20),
+                );
+                ui.end_row();
+                ui.label("Min Players");
+                ui.add(egui::DragValue::new(&mut metadata.min_players).clamp_range(1..=8));
+                ui.end_row();
+                ui.label("Max Players");
+                ui.add(
+                    egui::DragValue::new(&mut metadata.max_players)
+                        .clamp_range(metadata.min_players..=8),
+                );
+                ui.end_row();
+            });
+        });
+}
+
+fn generator_view(
     mut commands: Commands,
     mut egui: EguiContexts,
-    map: Res<GameMap>,
-    mut next_application_state: ResMut<NextState<ApplicationState>>,
+    mut seed: Local<u64>,
+    mut settings: Local<MapGeneratorSettings>,
     mut spawn_new_world_command: EventWriter<SpawnMapCommand>,
 ) {
-    egui::Window::new("Save & Load")
-        .title_bar(false)
-        .collapsible(false)
+    egui::Window::new("Map Generator")
+        .default_open(false)
         .resizable(false)
-        .anchor(Align2::RIGHT_TOP, egui::Vec2::ZERO)
-        .fixed_pos(Pos2::new(-5.0, -5.0))
+        .anchor(Align2::LEFT_TOP, egui::Vec2::new(5.0, 5.0))
         .show(egui.ctx_mut(), |ui| {
-            ui.vertical_centered(|ui| {
-                if ui.button("New").clicked() {
-                    commands.insert_resource(GameMap::new(10));
-                    spawn_new_world_command.send(SpawnMapCommand {});
-                }
-                if ui.button("Save").clicked() {
-                    map.write_to_disk(TEST_MAP_NAME);
-                }
-                if ui.button("Load").clicked() {
-                    match GameMap::load_from_file(TEST_MAP_NAME) {
-                        Ok(map) => {
-                            commands.insert_resource(map);
-                            spawn_new_world_command.send(SpawnMapCommand {});
-                        }
-                        Err(_) => {
-                            // TODO: Error response. Low priority.
-                        }
+            egui::Grid::new("generator_grid").show(ui, |ui| {
+                ui.label("Seed");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut *seed));
+                    if ui.button("Random").clicked() {
+                        *seed = rand::random();
                     }
+                });
+                ui.end_row();
+                ui.label("Radius");
+                ui.add(egui::DragValue::new(&mut settings.radius).clamp_range(3..=40));
+                ui.end_row();
+                ui.label("Teams");
+                ui.add(egui::DragValue::new(&mut settings.team_count).clamp_range(1..=6));
+                ui.end_row();
+                ui.label("Spawn Zone Size");
+                ui.add(egui::DragValue::new(&mut settings.spawn_zone_size).clamp_range(1..=20));
+                ui.end_row();
+                ui.label("Max Height");
+                ui.add(egui::DragValue::new(&mut settings.max_height).clamp_range(1..=MAX_HEIGHT));
+                ui.end_row();
+                ui.label("Hills");
+                ui.add(egui::DragValue::new(&mut settings.hill_count).clamp_range(0..=20));
+                ui.end_row();
+                ui.label("Lakes");
+                ui.add(egui::DragValue::new(&mut settings.lake_count).clamp_range(0..=10));
+                ui.end_row();
+            });
+
+            if ui.button("Generate").clicked() {
+                match generate_map(*seed, &settings) {
+                    Ok(map) => {
+                        commands.insert_resource(map);
+                        spawn_new_world_command.send(SpawnMapCommand {});
+                    }
+                    Err(e) => error!("{e}"),
                 }
-                if ui.button("Back To Menu").clicked() {
-                    next_application_state.set(ApplicationState::MainMenu);
+            }
+        });
+}
+
+/// Lists everything which would stop the server from using the current map.
+fn validation_view(
+    mut egui: EguiContexts,
+    map: Res<GameMap>,
+    mut cache: Local<Option<(MapMetadata, MapValidationReport)>>,
+) {
+    // Metadata edits bypass change detection, so they need to be compared manually
+    let is_outdated = match cache.as_ref() {
+        Some((metadata, _)) => map.is_changed() || metadata != &map.metadata,
+        None => true,
+    };
+    if is_outdated {
+        *cache = Some((map.metadata.clone(), validate_map(&map)));
+    }
+
+    let Some((_, report)) = cache.as_ref() else {
+        return;
+    };
+
+    egui::Window::new("Map Validation")
+        .collapsible(true)
+        .resizable(false)
+        .anchor(Align2::RIGHT_CENTER, egui::Vec2::new(-5.0, 0.0))
+        .show(egui.ctx_mut(), |ui| {
+            if report.issues.is_empty() {
+                ui.label("No issues found.");
+            }
+
+            for issue in &report.issues {
+                let color = match issue.severity() {
+                    MapIssueSeverity::Warning => egui::Color32::YELLOW,
+                    MapIssueSeverity::Error => egui::Color32::RED,
+                };
+                let label = ui.colored_label(color, issue.to_string());
+                if !issue.hexes().is_empty() {
+                    let hexes: Vec<String> = issue
+                        .hexes()
+                        .iter()
+                        .map(|hex| format!("({}, {})", hex.x, hex.y))
+                        .collect();
+                    label.on_hover_text(hexes.join(", "));
                 }
-            })
+            }
         });
 }
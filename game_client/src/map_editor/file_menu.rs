@@ -0,0 +1,234 @@
+use crate::map::SpawnMapCommand;
+use crate::ApplicationState;
+use bevy::prelude::*;
+use bevy_egui::egui::{Align2, Pos2};
+use bevy_egui::{egui, EguiContexts};
+use game_common::game_map::{GameMap, MapFormat};
+use game_common::{MAP_DIRECTORY, MAP_FILE_EXTENSION};
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_RADIUS: u32 = 10;
+const MAX_RADIUS: u32 = 40;
+
+/// Keeps track of the file the current map belongs to, as well as any open dialog.
+#[derive(Resource, Default)]
+pub struct MapFileState {
+    file_name: Option<String>,
+    dialog: Option<FileDialog>,
+    error: Option<String>,
+}
+
+enum FileDialog {
+    New { radius: u32 },
+    Open { files: Vec<String> },
+    SaveAs { name: String },
+    Resize { radius: u32 },
+}
+
+pub fn reset_file_state(mut commands: Commands) {
+    commands.insert_resource(MapFileState::default());
+}
+
+fn map_path(name: &str) -> PathBuf {
+    PathBuf::from(MAP_DIRECTORY).join(format!("{}.{}", name, MAP_FILE_EXTENSION))
+}
+
+/// Names of all map files inside the map directory, without their extension.
+fn list_map_files() -> Result<Vec<String>, String> {
+    let entries = match fs::read_dir(MAP_DIRECTORY) {
+        Ok(entries) => entries,
+        Err(e) => return Err(format!("Was unable to read {}: {}", MAP_DIRECTORY, e)),
+    };
+
+    let mut result: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|x| x == MAP_FILE_EXTENSION))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .collect();
+    result.sort();
+    Ok(result)
+}
+
+fn validate_file_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Please enter a name.".into());
+    }
+
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == ' ')
+    {
+        return Err(
+            "Names may only contain letters, numbers, spaces, dashes and underscores.".into(),
+        );
+    }
+
+    Ok(())
+}
+
+fn save_map(map: &GameMap, name: &str) -> Result<(), String> {
+    validate_file_name(name)?;
+    fs::create_dir_all(MAP_DIRECTORY)
+        .map_err(|e| format!("Was unable to create {}: {}", MAP_DIRECTORY, e))?;
+
+    map.write_to_disk_as(&map_path(name).to_string_lossy(), MapFormat::Ron)
+}
+
+pub fn file_menu(
+    mut commands: Commands,
+    mut egui: EguiContexts,
+    map: Res<GameMap>,
+    mut state: ResMut<MapFileState>,
+    mut next_application_state: ResMut<NextState<ApplicationState>>,
+    mut spawn_new_world_command: EventWriter<SpawnMapCommand>,
+) {
+    let state = state.as_mut();
+    egui::Window::new("Save & Load")
+        .title_bar(false)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::RIGHT_TOP, egui::Vec2::ZERO)
+        .fixed_pos(Pos2::new(-5.0, -5.0))
+        .show(egui.ctx_mut(), |ui| {
+            ui.vertical_centered(|ui| {
+                ui.label(state.file_name.as_deref().unwrap_or("Unsaved Map"));
+                if ui.button("New...").clicked() {
+                    state.dialog = Some(FileDialog::New {
+                        radius: DEFAULT_RADIUS,
+                    });
+                }
+                if ui.button("Open...").clicked() {
+                    match list_map_files() {
+                        Ok(files) => state.dialog = Some(FileDialog::Open { files }),
+                        Err(e) => state.error = Some(e),
+                    }
+                }
+                if ui.button("Save").clicked() {
+                    match &state.file_name {
+                        Some(name) => {
+                            if let Err(e) = save_map(&map, name) {
+                                state.error = Some(e);
+                            }
+                        }
+                        None => {
+                            state.dialog = Some(FileDialog::SaveAs {
+                                name: String::new(),
+                            })
+                        }
+                    }
+                }
+                if ui.button("Save As...").clicked() {
+                    state.dialog = Some(FileDialog::SaveAs {
+                        name: state.file_name.clone().unwrap_or_default(),
+                    });
+                }
+                if ui.button("Resize...").clicked() {
+                    state.dialog = Some(FileDialog::Resize { radius: map.radius });
+                }
+                if ui.button("Back To Menu").clicked() {
+                    next_application_state.set(ApplicationState::MainMenu);
+                }
+            })
+        });
+
+    let mut close_dialog = false;
+    let mut new_map = None;
+    if let Some(dialog) = &mut state.dialog {
+        egui::Window::new("Map File")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(egui.ctx_mut(), |ui| match dialog {
+                FileDialog::New { radius } => {
+                    ui.heading("New Map");
+                    ui.add(egui::Slider::new(radius, 1..=MAX_RADIUS).text("Radius"));
+                    ui.horizontal(|ui| {
+                        if ui.button("Create").clicked() {
+                            new_map = Some((GameMap::new(*radius), None));
+                        }
+                        close_dialog |= ui.button("Cancel").clicked();
+                    });
+                }
+                FileDialog::Open { files } => {
+                    ui.heading("Open Map");
+                    if files.is_empty() {
+                        ui.label(format!("There are no maps inside {}.", MAP_DIRECTORY));
+                    }
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            for file in files.iter() {
+                                if ui.button(file).clicked() {
+                                    match GameMap::load_from_file(&map_path(file).to_string_lossy())
+                                    {
+                                        Ok(map) => new_map = Some((map, Some(file.clone()))),
+                                        Err(e) => state.error = Some(e),
+                                    }
+                                }
+                            }
+                        });
+                    close_dialog |= ui.button("Cancel").clicked();
+                }
+                FileDialog::SaveAs { name } => {
+                    ui.heading("Save Map As");
+                    ui.text_edit_singleline(name);
+                    ui.horizontal(|ui| {
+                        if ui.button("Save").clicked() {
+                            match save_map(&map, name) {
+                                Ok(_) => {
+                                    state.file_name = Some(name.clone());
+                                    close_dialog = true;
+                                }
+                                Err(e) => state.error = Some(e),
+                            }
+                        }
+                        close_dialog |= ui.button("Cancel").clicked();
+                    });
+                }
+                FileDialog::Resize { radius } => {
+                    ui.heading("Resize Map");
+                    ui.add(egui::Slider::new(radius, 1..=MAX_RADIUS).text("Radius"));
+                    if *radius < map.radius {
+                        ui.label("Tiles outside of the new radius will be removed!");
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Resize").clicked() {
+                            let mut resized_map = map.clone();
+                            resized_map.resize(*radius);
+                            new_map = Some((resized_map, state.file_name.clone()));
+                        }
+                        close_dialog |= ui.button("Cancel").clicked();
+                    });
+                }
+            });
+    }
+
+    if let Some((map, file_name)) = new_map {
+        commands.insert_resource(map);
+        spawn_new_world_command.send(SpawnMapCommand {});
+        state.file_name = file_name;
+        close_dialog = true;
+    }
+
+    if close_dialog {
+        state.dialog = None;
+    }
+
+    if let Some(error) = &state.error {
+        let mut acknowledged = false;
+        egui::Window::new("Error")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_TOP, egui::Vec2::new(0.0, 20.0))
+            .show(egui.ctx_mut(), |ui| {
+                ui.label(error);
+                acknowledged = ui.button("OK").clicked();
+            });
+
+        if acknowledged {
+            state.error = None;
+        }
+    }
+}
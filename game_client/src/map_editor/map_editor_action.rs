@@ -4,7 +4,7 @@ use bevy::prelude::{KeyCode, MouseButton, Reflect};
 use game_common::game_map::{FluidKind, TileSurface};
 use leafwing_input_manager::axislike::DualAxis;
 use leafwing_input_manager::input_map::InputMap;
-use leafwing_input_manager::prelude::InputKind;
+use leafwing_input_manager::prelude::{InputKind, UserInput};
 use leafwing_input_manager::Actionlike;
 use std::fmt::Formatter;
 
@@ -13,16 +13,25 @@ pub enum MapEditorAction {
     UseTool,
     RaiseTiles,
     LowerTiles,
+    SmoothHeight,
+    FlattenHeight,
     PaintGrass,
     PaintStone,
     PaintSand,
     PaintEarth,
     RaiseWater,
+    RaiseLava,
+    RaiseSlime,
     LowerFluid,
     MouseMotion,
     MarkSpawnTile,
     RemoveSpawnTile,
     SpawnCubeProp,
+    RemoveProps,
+    Undo,
+    Redo,
+    IncreaseBrushSize,
+    DecreaseBrushSize,
 }
 
 impl std::fmt::Display for MapEditorAction {
@@ -31,15 +40,24 @@ impl std::fmt::Display for MapEditorAction {
             MapEditorAction::UseTool => write!(f, "Use Tool"),
             MapEditorAction::RaiseTiles => write!(f, "Raise Tiles"),
             MapEditorAction::LowerTiles => write!(f, "Lower Tiles"),
+            MapEditorAction::SmoothHeight => write!(f, "Smooth Height"),
+            MapEditorAction::FlattenHeight => write!(f, "Flatten Height"),
             MapEditorAction::PaintGrass => write!(f, "Paint Grass"),
             MapEditorAction::PaintStone => write!(f, "Paint Stone"),
             MapEditorAction::PaintSand => write!(f, "Paint Sand"),
             MapEditorAction::PaintEarth => write!(f, "Paint Earth"),
             MapEditorAction::RaiseWater => write!(f, "Raise Water"),
-            MapEditorAction::LowerFluid => write!(f, "Lower Water"),
+            MapEditorAction::RaiseLava => write!(f, "Raise Lava"),
+            MapEditorAction::RaiseSlime => write!(f, "Raise Slime"),
+            MapEditorAction::LowerFluid => write!(f, "Lower Fluid"),
             MapEditorAction::MarkSpawnTile => write!(f, "Mark Spawn Tile"),
             MapEditorAction::RemoveSpawnTile => write!(f, "Remove Spawn Tile"),
             MapEditorAction::SpawnCubeProp => write!(f, "Spawn Cube Prop"),
+            MapEditorAction::RemoveProps => write!(f, "Remove Props"),
+            MapEditorAction::Undo => write!(f, "Undo"),
+            MapEditorAction::Redo => write!(f, "Redo"),
+            MapEditorAction::IncreaseBrushSize => write!(f, "Increase Brush Size"),
+            MapEditorAction::DecreaseBrushSize => write!(f, "Decrease Brush Size"),
             MapEditorAction::MouseMotion => {
                 warn!(
                     "MapEditorAction::MouseMotion::Display was called. This should never happen?"
@@ -56,32 +74,48 @@ impl MapEditorAction {
         input_map.insert(Self::UseTool, InputKind::Mouse(MouseButton::Left));
         input_map.insert(Self::RaiseTiles, KeyCode::KeyR);
         input_map.insert(Self::LowerTiles, KeyCode::KeyF);
+        input_map.insert(Self::SmoothHeight, KeyCode::KeyT);
+        input_map.insert(Self::FlattenHeight, KeyCode::KeyG);
         input_map.insert(Self::PaintGrass, KeyCode::Digit1);
         input_map.insert(Self::PaintStone, KeyCode::Digit2);
         input_map.insert(Self::PaintSand, KeyCode::Digit3);
         input_map.insert(Self::PaintEarth, KeyCode::Digit4);
         input_map.insert(Self::RaiseWater, KeyCode::Digit5);
         input_map.insert(Self::LowerFluid, KeyCode::Digit6);
+        input_map.insert(Self::RaiseLava, KeyCode::Digit7);
+        input_map.insert(Self::RaiseSlime, KeyCode::Digit8);
         input_map.insert(Self::MarkSpawnTile, KeyCode::KeyZ);
         input_map.insert(Self::RemoveSpawnTile, KeyCode::KeyX);
         input_map.insert(Self::SpawnCubeProp, KeyCode::KeyC);
+        input_map.insert(Self::RemoveProps, KeyCode::KeyV);
+        input_map.insert(Self::IncreaseBrushSize, KeyCode::BracketRight);
+        input_map.insert(Self::DecreaseBrushSize, KeyCode::BracketLeft);
         input_map.insert(Self::MouseMotion, DualAxis::mouse_motion());
+        for control in [KeyCode::ControlLeft, KeyCode::ControlRight] {
+            input_map.insert(Self::Undo, UserInput::chord([control, KeyCode::KeyZ]));
+            input_map.insert(Self::Redo, UserInput::chord([control, KeyCode::KeyY]));
+        }
 
         input_map
     }
 }
 
 #[rustfmt::skip]
-pub const ACTION_TO_TOOL: [(MapEditorAction, MapEditorTool); 11] = [
+pub const ACTION_TO_TOOL: [(MapEditorAction, MapEditorTool); 16] = [
     (MapEditorAction::RaiseTiles, MapEditorTool::RaiseTiles),
     (MapEditorAction::LowerTiles, MapEditorTool::LowerTiles),
+    (MapEditorAction::SmoothHeight, MapEditorTool::SmoothHeight),
+    (MapEditorAction::FlattenHeight, MapEditorTool::FlattenHeight),
     (MapEditorAction::PaintGrass, MapEditorTool::PaintSurface(TileSurface::Grass)),
     (MapEditorAction::PaintStone, MapEditorTool::PaintSurface(TileSurface::Stone)),
     (MapEditorAction::PaintSand,  MapEditorTool::PaintSurface(TileSurface::Sand)),
     (MapEditorAction::PaintEarth, MapEditorTool::PaintSurface(TileSurface::Earth)),
     (MapEditorAction::RaiseWater, MapEditorTool::RaiseFluid(FluidKind::Water)),
+    (MapEditorAction::RaiseLava,  MapEditorTool::RaiseFluid(FluidKind::Lava)),
+    (MapEditorAction::RaiseSlime, MapEditorTool::RaiseFluid(FluidKind::Slime)),
     (MapEditorAction::LowerFluid, MapEditorTool::LowerFluid),
     (MapEditorAction::MarkSpawnTile, MapEditorTool::MarkSpawnTile(1)),
     (MapEditorAction::RemoveSpawnTile, MapEditorTool::RemoveSpawnTile),
     (MapEditorAction::SpawnCubeProp, MapEditorTool::SpawnProp(1)),
+    (MapEditorAction::RemoveProps, MapEditorTool::RemoveProps),
 ];
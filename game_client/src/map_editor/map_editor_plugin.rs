@@ -8,6 +8,8 @@ use leafwing_input_manager::plugin::InputManagerPlugin;
 use game_common::game_map::GameMap;
 
 use crate::map::{DespawnMapCommand, MapState, SpawnMapCommand};
+use crate::map_editor::brush::{change_brush_size, Brush};
+use crate::map_editor::edit_history::EditHistoryPlugin;
 use crate::map_editor::editor_ui::MapEditorUiPlugin;
 use crate::map_editor::map_editor_action::{MapEditorAction, ACTION_TO_TOOL};
 use crate::map_editor::map_editor_tool::{use_tool, MapEditorTool};
@@ -21,13 +23,14 @@ impl Plugin for MapEditorPlugin {
         app.add_plugins(InputManagerPlugin::<MapEditorAction>::default());
         app.add_plugins(MapEditorUiPlugin);
         app.add_plugins(MapEditorToolsPlugin);
+        app.add_plugins(EditHistoryPlugin);
         app.init_resource::<ActionState<MapEditorAction>>();
         app.insert_resource(MapEditorAction::default_input_map());
         app.add_systems(OnEnter(ApplicationState::MapEditor), setup_map_editor);
         app.add_systems(OnExit(ApplicationState::MapEditor), exit_map_editor);
         app.add_systems(
             PreUpdate,
-            switch_tool_on_button_press
+            (switch_tool_on_button_press, change_brush_size)
                 .run_if(in_state(ApplicationState::MapEditor))
                 .run_if(in_state(MapState::Ready)),
         );
@@ -43,6 +46,7 @@ impl Plugin for MapEditorPlugin {
 
 fn setup_map_editor(mut commands: Commands, mut spawn_map_command: EventWriter<SpawnMapCommand>) {
     commands.insert_resource(MapEditorTool::default());
+    commands.insert_resource(Brush::default());
     let default_radius = 10;
     let map = GameMap::new(default_radius);
     commands.insert_resource(map);
@@ -5,27 +5,37 @@ use std::ops::DerefMut;
 use bevy::log::error;
 use bevy::prelude::{EventWriter, Local, Res, ResMut, Resource};
 use game_common::game_data::prop::PropId;
+use game_common::game_data::GameData;
 use hexx::Hex;
 use leafwing_input_manager::action_state::ActionState;
 
-use game_common::game_map::{Fluid, FluidKind, GameMap, TileData, TileSurface, MAX_HEIGHT};
+use game_common::game_map::{
+    Fluid, FluidKind, GameMap, PropInstance, TileData, TileSurface, MAX_HEIGHT,
+};
 
 use crate::map::{CursorOnTile, TileChangeEvent};
+use crate::map_editor::brush::{Brush, BrushShape};
+use crate::map_editor::edit_history::EditHistory;
 use crate::map_editor::map_editor_action::MapEditorAction;
 use crate::map_editor::multiselect_data::MultiselectData;
 use crate::map_editor::tools::events::{AddSpawnMarkerEvent, RemoveSpawnMarkerEvent};
 
-#[derive(Resource, Debug, Default)]
+#[derive(Resource, Debug, Default, Copy, Clone)]
 pub enum MapEditorTool {
     #[default]
     RaiseTiles,
     LowerTiles,
+    /// Moves tiles towards the average height of their neighbors.
+    SmoothHeight,
+    /// Sets tiles to the height of the tile where the stroke started.
+    FlattenHeight,
     PaintSurface(TileSurface),
     RaiseFluid(FluidKind),
     LowerFluid,
     MarkSpawnTile(u8),
     RemoveSpawnTile,
     SpawnProp(PropId),
+    RemoveProps,
 }
 
 impl std::fmt::Display for MapEditorTool {
@@ -33,12 +43,15 @@ impl std::fmt::Display for MapEditorTool {
         match self {
             MapEditorTool::RaiseTiles => write!(f, "Raise Tiles"),
             MapEditorTool::LowerTiles => write!(f, "Lower Tiles"),
+            MapEditorTool::SmoothHeight => write!(f, "Smooth Height"),
+            MapEditorTool::FlattenHeight => write!(f, "Flatten Height"),
             MapEditorTool::PaintSurface(surface) => write!(f, "Paint {}", surface),
             MapEditorTool::RaiseFluid(fluid) => write!(f, "Fill with {}", fluid),
             MapEditorTool::LowerFluid => write!(f, "Lower Fluid"),
             MapEditorTool::MarkSpawnTile(team) => write!(f, "Mark Spawn Tiles for Team {team}"),
             MapEditorTool::RemoveSpawnTile => write!(f, "Remove Spawn Tiles"),
             MapEditorTool::SpawnProp(prop) => write!(f, "Spawn Prop {}", prop),
+            MapEditorTool::RemoveProps => write!(f, "Remove Props"),
         }
     }
 }
@@ -51,10 +64,13 @@ pub struct EventWriters<'w> {
 
 pub fn use_tool(
     map: ResMut<GameMap>,
+    game_data: Res<GameData>,
     active_tool: Res<MapEditorTool>,
+    brush: Res<Brush>,
     cursor: Option<Res<CursorOnTile>>,
     input_state: Res<ActionState<MapEditorAction>>,
     mut multiselect_data: Local<MultiselectData>,
+    mut history: ResMut<EditHistory>,
     mut tile_change_event: EventWriter<TileChangeEvent>,
     mut event_writers: EventWriters,
 ) {
@@ -62,17 +78,47 @@ pub fn use_tool(
         return;
     };
 
+    // Lines would turn into a fan if they were applied while the cursor is still moving
+    if brush.shape == BrushShape::Line {
+        if input_state.just_pressed(&MapEditorAction::UseTool) {
+            multiselect_data.deref_mut().clear();
+            multiselect_data.origin = Some(cursor.hex);
+        } else if input_state.just_released(&MapEditorAction::UseTool)
+            && multiselect_data.origin.is_some()
+        {
+            history.begin_stroke();
+            create_tool_events_for_tile(
+                map,
+                &game_data,
+                &active_tool,
+                &brush,
+                &cursor,
+                multiselect_data,
+                &mut history,
+                &mut tile_change_event,
+                &mut event_writers,
+            );
+        }
+
+        return;
+    }
+
     if !input_state.pressed(&MapEditorAction::UseTool) {
         return;
     }
 
     if input_state.just_pressed(&MapEditorAction::UseTool) {
         multiselect_data.deref_mut().clear();
+        multiselect_data.origin = Some(cursor.hex);
+        history.begin_stroke();
         create_tool_events_for_tile(
             map,
+            &game_data,
             &active_tool,
+            &brush,
             &cursor,
             multiselect_data,
+            &mut history,
             &mut tile_change_event,
             &mut event_writers,
         );
@@ -85,9 +131,12 @@ pub fn use_tool(
         if multiselect_data.total_mouse_delta > 35.0 {
             create_tool_events_for_tile(
                 map,
+                &game_data,
                 &active_tool,
+                &brush,
                 &cursor,
                 multiselect_data,
+                &mut history,
                 &mut tile_change_event,
                 &mut event_writers,
             );
@@ -97,45 +146,96 @@ pub fn use_tool(
 
 fn create_tool_events_for_tile(
     mut map: ResMut<GameMap>,
-    active_tool: &Res<MapEditorTool>,
+    game_data: &GameData,
+    active_tool: &MapEditorTool,
+    brush: &Brush,
     cursor: &CursorOnTile,
     mut multiselect_data: Local<MultiselectData>,
+    history: &mut EditHistory,
     tile_change_event: &mut EventWriter<TileChangeEvent>,
     event_writers: &mut EventWriters,
 ) {
-    let cursor = std::iter::once(cursor);
-    tile_change_event.send_batch(cursor.filter_map(|cursor| {
-        if multiselect_data
-            .previously_selected_tiles
-            .contains(&cursor.hex)
-        {
-            None
-        } else {
-            multiselect_data.previously_selected_tiles.push(cursor.hex);
-            if let Some(tile) = map.tiles.get_mut(&cursor.hex) {
-                if can_tool_be_used_on_tile(&active_tool, tile) {
-                    let old_data = tile.clone();
-                    use_tool_on_tile(&active_tool, &cursor.hex, tile, event_writers);
-                    Some(TileChangeEvent {
-                        hex: cursor.hex,
-                        old_data,
-                    })
-                } else {
-                    None
-                }
+    // Tools depending on surrounding tiles need to be resolved before anything gets changed
+    let tools: Vec<(Hex, ResolvedTool)> = brush
+        .affected_hexes(cursor.hex, multiselect_data.origin, active_tool, &map)
+        .into_iter()
+        .filter(|hex| !multiselect_data.previously_selected_tiles.contains(hex))
+        .map(|hex| {
+            (
+                hex,
+                resolve_tool(active_tool, &hex, &map, &multiselect_data),
+            )
+        })
+        .collect();
+
+    tile_change_event.send_batch(tools.into_iter().filter_map(|(hex, tool)| {
+        multiselect_data.previously_selected_tiles.push(hex);
+        if let Some(tile) = map.tiles.get_mut(&hex) {
+            if can_tool_be_used_on_tile(&tool, tile, game_data) {
+                let old_data = tile.clone();
+                use_tool_on_tile(&tool, &hex, tile, game_data, event_writers);
+                history.record(hex, old_data.clone(), tile.clone());
+                Some(TileChangeEvent { hex, old_data })
             } else {
-                error!("Was unable to find hex tile_data at {:?} in map!", cursor);
                 None
             }
+        } else {
+            error!("Was unable to find hex tile_data at {:?} in map!", hex);
+            None
         }
     }));
 }
 
+/// A [MapEditorTool] which can be applied to a single tile without looking at its surroundings.
+#[derive(Debug, Copy, Clone)]
+enum ResolvedTool {
+    /// Smoothing and flattening, once the target height for a tile is known.
+    SetHeight(u8),
+    Unchanged(MapEditorTool),
+}
+
+/// Turns tools which depend on the surrounding tiles into a tool which can be applied to `hex` on its own.
 #[must_use]
-fn can_tool_be_used_on_tile(tool: &MapEditorTool, tile: &TileData) -> bool {
+fn resolve_tool(
+    tool: &MapEditorTool,
+    hex: &Hex,
+    map: &GameMap,
+    multiselect_data: &MultiselectData,
+) -> ResolvedTool {
+    match tool {
+        MapEditorTool::SmoothHeight => {
+            let heights: Vec<u32> = std::iter::once(*hex)
+                .chain(hex.all_neighbors())
+                .filter_map(|hex| map.tiles.get(&hex))
+                .map(|tile| tile.height as u32)
+                .collect();
+            let average = heights.iter().sum::<u32>() as f32 / heights.len().max(1) as f32;
+            ResolvedTool::SetHeight(average.round() as u8)
+        }
+        MapEditorTool::FlattenHeight => {
+            let origin = multiselect_data.origin.unwrap_or(*hex);
+            match map.tiles.get(&origin) {
+                Some(tile) => ResolvedTool::SetHeight(tile.height),
+                None => ResolvedTool::Unchanged(*tool),
+            }
+        }
+        _ => ResolvedTool::Unchanged(*tool),
+    }
+}
+
+#[must_use]
+fn can_tool_be_used_on_tile(tool: &ResolvedTool, tile: &TileData, game_data: &GameData) -> bool {
+    let tool = match tool {
+        ResolvedTool::SetHeight(height) => {
+            return tile.height != *height && *height <= MAX_HEIGHT;
+        }
+        ResolvedTool::Unchanged(tool) => tool,
+    };
+
     match tool {
         MapEditorTool::RaiseTiles => tile.height < MAX_HEIGHT,
         MapEditorTool::LowerTiles => tile.height > 0,
+        MapEditorTool::SmoothHeight | MapEditorTool::FlattenHeight => false,
         MapEditorTool::PaintSurface(_) => true,
         MapEditorTool::RaiseFluid(_) => true, // TODO: Check tile.height + fluid.height against MAX_HEIGHT
         MapEditorTool::LowerFluid => tile.fluid.is_some(),
@@ -143,16 +243,44 @@ fn can_tool_be_used_on_tile(tool: &MapEditorTool, tile: &TileData) -> bool {
             tile.height > 0 && !tile.spawn_zone.is_some_and(|x| &x == team)
         }
         MapEditorTool::RemoveSpawnTile => tile.spawn_zone.is_some(),
-        MapEditorTool::SpawnProp(_) => true,
+        MapEditorTool::SpawnProp(id) => {
+            let Some(definition) = game_data.props.get(id) else {
+                return false;
+            };
+
+            tile.height > 0
+                && tile
+                    .surface_height()
+                    .checked_add(definition.components.extra_height)
+                    .is_some_and(|height| height <= MAX_HEIGHT)
+        }
+        MapEditorTool::RemoveProps => !tile.props.is_empty(),
     }
 }
 
 fn use_tool_on_tile(
-    tool: &MapEditorTool,
+    tool: &ResolvedTool,
     hex: &Hex,
     tile: &mut TileData,
+    game_data: &GameData,
     event_writers: &mut EventWriters,
 ) {
+    let tool = match tool {
+        ResolvedTool::SetHeight(height) => {
+            let difference = *height as f32 - tile.height as f32;
+            tile.height = *height;
+            if let Some(ref mut fluid) = tile.fluid {
+                fluid.height -= difference;
+
+                if fluid.height <= 0.0 {
+                    tile.fluid = None;
+                }
+            }
+            return;
+        }
+        ResolvedTool::Unchanged(tool) => tool,
+    };
+
     match tool {
         MapEditorTool::RaiseTiles => {
             tile.height += 1;
@@ -170,6 +298,9 @@ fn use_tool_on_tile(
                 fluid.height += 1.0;
             }
         }
+        MapEditorTool::SmoothHeight | MapEditorTool::FlattenHeight => {
+            error!("{} should have been resolved into a different tool!", tool);
+        }
         MapEditorTool::PaintSurface(surface) => tile.surface = surface.clone(),
         MapEditorTool::RaiseFluid(kind) => {
             if let Some(ref mut fluid) = tile.fluid {
@@ -209,7 +340,10 @@ fn use_tool_on_tile(
                 .send(RemoveSpawnMarkerEvent { hex: hex.clone() });
         }
         MapEditorTool::SpawnProp(id) => {
-            // TODO
+            // Existence has already been checked in can_tool_be_used_on_tile
+            tile.props
+                .push(PropInstance::from_definition(&game_data.props[id]));
         }
+        MapEditorTool::RemoveProps => tile.props.clear(),
     }
 }
@@ -1,4 +1,7 @@
+mod brush;
+mod edit_history;
 mod editor_ui;
+mod file_menu;
 mod map_editor_action;
 mod map_editor_plugin;
 pub mod map_editor_tool;
@@ -4,11 +4,14 @@ use hexx::Hex;
 pub struct MultiselectData {
     pub total_mouse_delta: f32,
     pub previously_selected_tiles: Vec<Hex>,
+    /// The hex where the current stroke started.
+    pub origin: Option<Hex>,
 }
 
 impl MultiselectData {
     pub fn clear(&mut self) {
         self.total_mouse_delta = 0.0;
         self.previously_selected_tiles.clear();
+        self.origin = None;
     }
 }
@@ -94,8 +94,14 @@ fn remove_all_spawn_spot_markers(mut commands: Commands) {
 
 pub fn on_add_spawn_marker(
     mut events: EventReader<AddSpawnMarkerEvent>,
-    mut existing_highlights: ResMut<RangeHighlights>,
+    existing_highlights: Option<ResMut<RangeHighlights>>,
 ) {
+    // Markers are only visible while a spawn tool is selected, but undo and redo may change spawn tiles at any time
+    let Some(mut existing_highlights) = existing_highlights else {
+        events.clear();
+        return;
+    };
+
     for event in events.read() {
         existing_highlights.tiles.push(event.hex);
     }
@@ -103,8 +109,14 @@ pub fn on_add_spawn_marker(
 
 pub fn on_remove_spawn_marker(
     mut events: EventReader<RemoveSpawnMarkerEvent>,
-    mut existing_highlights: ResMut<RangeHighlights>,
+    existing_highlights: Option<ResMut<RangeHighlights>>,
 ) {
+    // Markers are only visible while a spawn tool is selected, but undo and redo may change spawn tiles at any time
+    let Some(mut existing_highlights) = existing_highlights else {
+        events.clear();
+        return;
+    };
+
     for event in events.read() {
         if let Some(pos) = existing_highlights
             .tiles
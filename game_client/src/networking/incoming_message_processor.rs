@@ -1,5 +1,8 @@
-use crate::networking::network_plugin::{NetworkState, ServerConnection};
+use crate::networking::network_plugin::{
+    check_for_connection_updates, NetworkState, ServerConnection,
+};
 use bevy::app::{App, Plugin, PreUpdate};
+use bevy::ecs::system::SystemParam;
 use bevy::log::{debug, error};
 use bevy::prelude::{in_state, Condition, EventWriter, IntoSystemConfigs, Local, ResMut};
 use game_common::network_events::server_to_client::ServerToClientMessage;
@@ -8,60 +11,102 @@ use game_common::network_events::{server_to_client, NetworkMessage};
 pub struct IncomingMessageProcessorPlugin;
 impl Plugin for IncomingMessageProcessorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<server_to_client::LoadMap>()
+        app.add_event::<server_to_client::ConnectionRefused>()
+            .add_event::<server_to_client::LoadMap>()
+            .add_event::<server_to_client::MapData>()
             .add_event::<server_to_client::UpdateReadyStateForPlayer>()
             .add_event::<server_to_client::PlayerTurnToPlaceUnit>()
             .add_event::<server_to_client::PlaceUnit>()
             .add_event::<server_to_client::StartUnitTurn>()
             .add_event::<server_to_client::MoveUnit>()
             .add_event::<server_to_client::YouConnected>()
-            .add_event::<server_to_client::OtherPlayerConnected>()
+            .add_event::<server_to_client::PlayerDisconnected>()
+            .add_event::<server_to_client::PlayerReconnected>()
+            .add_event::<server_to_client::ResumeGame>()
+            .add_event::<server_to_client::StateChecksum>()
             .add_event::<server_to_client::UseSkill>()
+            .add_event::<server_to_client::ReactionTriggered>()
+            .add_event::<server_to_client::LobbyList>()
+            .add_event::<server_to_client::JoinedLobby>()
+            .add_event::<server_to_client::LeftLobby>()
+            .add_event::<server_to_client::PlayerJoinedLobby>()
+            .add_event::<server_to_client::PlayerLeftLobby>()
             .add_event::<server_to_client::StartGame>()
             .add_event::<server_to_client::ChooseBetweenUnits>()
             .add_event::<server_to_client::AddUnit>()
             .add_event::<server_to_client::CombatFinished>()
             .add_systems(
                 PreUpdate,
-                receive_updates.run_if(
-                    in_state(NetworkState::Connected)
-                        .or_else(in_state(NetworkState::Authenticating)),
-                ),
+                receive_updates
+                    .run_if(
+                        in_state(NetworkState::Connected)
+                            .or_else(in_state(NetworkState::Authenticating)),
+                    )
+                    // Frames received right before the connection got dropped still need to be processed
+                    .before(check_for_connection_updates),
             );
     }
 }
 
+// Systems are limited to 16 parameters
+#[derive(SystemParam)]
+struct ConnectionEventWriters<'w> {
+    connection_refused: EventWriter<'w, server_to_client::ConnectionRefused>,
+    you_connected: EventWriter<'w, server_to_client::YouConnected>,
+    player_disconnected: EventWriter<'w, server_to_client::PlayerDisconnected>,
+    player_reconnected: EventWriter<'w, server_to_client::PlayerReconnected>,
+    resume_game: EventWriter<'w, server_to_client::ResumeGame>,
+    state_checksum: EventWriter<'w, server_to_client::StateChecksum>,
+}
+
+#[derive(SystemParam)]
+struct LobbyEventWriters<'w> {
+    lobby_list: EventWriter<'w, server_to_client::LobbyList>,
+    joined_lobby: EventWriter<'w, server_to_client::JoinedLobby>,
+    left_lobby: EventWriter<'w, server_to_client::LeftLobby>,
+    player_joined_lobby: EventWriter<'w, server_to_client::PlayerJoinedLobby>,
+    player_left_lobby: EventWriter<'w, server_to_client::PlayerLeftLobby>,
+    start_game: EventWriter<'w, server_to_client::StartGame>,
+}
+
+#[derive(SystemParam)]
+struct MapEventWriters<'w> {
+    load_map: EventWriter<'w, server_to_client::LoadMap>,
+    map_data: EventWriter<'w, server_to_client::MapData>,
+}
+
 fn receive_updates(
     mut connection: ResMut<ServerConnection>,
     mut event_queue: Local<IncomingNetworkEventQueue>,
-    mut load_map_event_from_server: EventWriter<server_to_client::LoadMap>,
+    mut connection_events: ConnectionEventWriters,
+    mut lobby_events: LobbyEventWriters,
+    mut map_events: MapEventWriters,
     mut player_is_ready: EventWriter<server_to_client::UpdateReadyStateForPlayer>,
     mut player_turn_to_place_unit: EventWriter<server_to_client::PlayerTurnToPlaceUnit>,
     mut place_unit: EventWriter<server_to_client::PlaceUnit>,
     mut start_unit_turn: EventWriter<server_to_client::StartUnitTurn>,
     mut move_unit: EventWriter<server_to_client::MoveUnit>,
-    mut you_connected: EventWriter<server_to_client::YouConnected>,
-    mut other_player_connected: EventWriter<server_to_client::OtherPlayerConnected>,
     mut use_skill: EventWriter<server_to_client::UseSkill>,
-    mut start_game: EventWriter<server_to_client::StartGame>,
+    mut reaction_triggered: EventWriter<server_to_client::ReactionTriggered>,
     mut choose_between_units: EventWriter<server_to_client::ChooseBetweenUnits>,
     mut add_unit: EventWriter<server_to_client::AddUnit>,
     mut combat_finished: EventWriter<server_to_client::CombatFinished>,
 ) {
-    if let Ok(bytes) = connection.message_receiver.try_recv() {
-        match ServerToClientMessage::deserialize(&bytes) {
-            Ok(messages) => {
-                debug!("Received {} bytes: {:?}", bytes.len(), messages);
-                for message in messages {
-                    event_queue.push(message);
-                }
+    while let Ok(frame) = connection.message_receiver.try_recv() {
+        match ServerToClientMessage::deserialize(&frame) {
+            Ok(ServerToClientMessage::ConnectionRefused(event)) => {
+                // The connection is about to be dropped, so there won't be another frame to process this in
+                connection_events.connection_refused.send(event);
+            }
+            Ok(message) => {
+                debug!("Received {} bytes: {:?}", frame.len(), message);
+                event_queue.push(message);
             }
             Err(e) => {
                 error!(
-                    "Failed deserializing NetworkMessage! Error: {:?} Bytes: {:?}",
-                    e, bytes
+                    "Failed deserializing NetworkMessage! Error: {} Bytes: {:?}",
+                    e, frame
                 );
-                return;
             }
         };
     }
@@ -73,15 +118,49 @@ fn receive_updates(
                 error!("Server responded with an error: {:?}", e);
             }
 
+            ServerToClientMessage::ConnectionRefused(event) => {
+                connection_events.connection_refused.send(event);
+            }
             ServerToClientMessage::YouConnected(event) => {
-                you_connected.send(event);
+                connection_events.you_connected.send(event);
+            }
+            ServerToClientMessage::PlayerDisconnected(event) => {
+                connection_events.player_disconnected.send(event);
             }
-            ServerToClientMessage::OtherPlayerConnected(event) => {
-                other_player_connected.send(event);
+            ServerToClientMessage::PlayerReconnected(event) => {
+                connection_events.player_reconnected.send(event);
+            }
+            ServerToClientMessage::ResumeGame(event) => {
+                connection_events.resume_game.send(event);
+            }
+            ServerToClientMessage::StateChecksum(event) => {
+                connection_events.state_checksum.send(event);
+            }
+
+            ServerToClientMessage::LobbyList(event) => {
+                lobby_events.lobby_list.send(event);
+            }
+            ServerToClientMessage::JoinedLobby(event) => {
+                lobby_events.joined_lobby.send(event);
+            }
+            ServerToClientMessage::LeftLobby(event) => {
+                lobby_events.left_lobby.send(event);
+            }
+            ServerToClientMessage::PlayerJoinedLobby(event) => {
+                lobby_events.player_joined_lobby.send(event);
+            }
+            ServerToClientMessage::PlayerLeftLobby(event) => {
+                lobby_events.player_left_lobby.send(event);
+            }
+            ServerToClientMessage::StartGame(event) => {
+                lobby_events.start_game.send(event);
             }
 
             ServerToClientMessage::LoadMap(event) => {
-                load_map_event_from_server.send(event);
+                map_events.load_map.send(event);
+            }
+            ServerToClientMessage::MapData(event) => {
+                map_events.map_data.send(event);
             }
             ServerToClientMessage::PlayerIsReady(event) => {
                 player_is_ready.send(event);
@@ -101,13 +180,13 @@ fn receive_updates(
             ServerToClientMessage::UseSkill(event) => {
                 use_skill.send(event);
             }
+            ServerToClientMessage::ReactionTriggered(event) => {
+                reaction_triggered.send(event);
+            }
             ServerToClientMessage::CombatFinished(event) => {
                 combat_finished.send(event);
             }
 
-            ServerToClientMessage::StartGame(event) => {
-                start_game.send(event);
-            }
             ServerToClientMessage::ChooseBetweenUnits(event) => {
                 choose_between_units.send(event);
             }
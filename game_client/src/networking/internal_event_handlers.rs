@@ -1,14 +1,51 @@
-use bevy::log::{error, info};
-use bevy::prelude::{Commands, EventReader, NextState, ResMut};
+use bevy::log::{error, info, warn};
+use bevy::prelude::{Commands, EventReader, EventWriter, NextState, Res, ResMut};
 use bevy::utils::HashMap;
 use game_common::combat_unit::ActorId;
+use game_common::game_data::GameData;
 
+use game_common::network_events::client_to_server::{ClientToServerMessage, Hello};
 use game_common::network_events::server_to_client::{
-    OtherPlayerConnected, UpdateReadyStateForPlayer, YouConnected,
+    ConnectionRefused, JoinedLobby, LeftLobby, PlayerDisconnected, PlayerJoinedLobby,
+    PlayerLeftLobby, PlayerReconnected, UpdateReadyStateForPlayer, YouConnected,
 };
+use game_common::network_events::PROTOCOL_VERSION;
 use game_common::DESYNC_TODO_MESSAGE;
 
-use crate::networking::{ClientIsLobbyHost, ConnectedPlayers, LocalPlayerId, NetworkState};
+use crate::networking::network_plugin::ServerConnection;
+use crate::networking::{
+    AbsentPlayers, ClientIsLobbyHost, ConnectedPlayers, ConnectionRefusedReason, CurrentLobby,
+    LocalPlayerId, NetworkState, ResumableSession,
+};
+
+pub fn send_hello(
+    mut commands: Commands,
+    game_data: Res<GameData>,
+    session: Option<Res<ResumableSession>>,
+    mut messages: EventWriter<ClientToServerMessage>,
+) {
+    commands.remove_resource::<ConnectionRefusedReason>();
+    messages.send(ClientToServerMessage::Hello(Hello {
+        protocol_version: PROTOCOL_VERSION,
+        game_data_hash: game_data.content_hash(),
+        session_token: session.map(|x| x.token),
+    }));
+}
+
+pub fn on_connection_refused(
+    mut commands: Commands,
+    mut events: EventReader<ConnectionRefused>,
+    mut next_network_state: ResMut<NextState<NetworkState>>,
+) {
+    for x in events.read() {
+        warn!("Server refused connection: {}", x.reason);
+        commands.insert_resource(ConnectionRefusedReason {
+            reason: x.reason.clone(),
+        });
+        commands.remove_resource::<ServerConnection>();
+        next_network_state.set(NetworkState::Disconnected);
+    }
+}
 
 pub fn on_you_connected(
     mut commands: Commands,
@@ -16,38 +53,102 @@ pub fn on_you_connected(
     mut next_network_state: ResMut<NextState<NetworkState>>,
 ) {
     for x in events.read() {
-        let mut all_players = HashMap::new();
-        for x in x.connected_players.iter() {
-            all_players.insert(x.id, x.clone());
-        }
-
         commands.insert_resource(LocalPlayerId {
             id: x.player_id,
             actor: ActorId::Player(x.player_id),
         });
+        commands.insert_resource(ConnectedPlayers {
+            players: HashMap::new(),
+        });
+        commands.insert_resource(ResumableSession {
+            token: x.session_token,
+        });
+        commands.insert_resource(AbsentPlayers::default());
+        commands.remove_resource::<CurrentLobby>();
+        commands.remove_resource::<ClientIsLobbyHost>();
+
+        next_network_state.set(NetworkState::Connected);
+        info!("Authentication Successful, Networking setup complete.")
+    }
+}
+
+pub fn on_joined_lobby(mut commands: Commands, mut events: EventReader<JoinedLobby>) {
+    for x in events.read() {
+        let mut all_players = HashMap::new();
+        for x in x.players.iter() {
+            all_players.insert(x.id, x.clone());
+        }
+
         commands.insert_resource(ConnectedPlayers {
             players: all_players,
         });
+        commands.insert_resource(CurrentLobby {
+            info: x.lobby.clone(),
+        });
 
-        if x.connected_players.len() == 1 {
+        if x.players.len() == 1 {
             commands.insert_resource(ClientIsLobbyHost)
+        } else {
+            commands.remove_resource::<ClientIsLobbyHost>();
         }
 
-        next_network_state.set(NetworkState::Connected);
-        info!("Authentication Successful, Networking setup complete.")
+        info!("Joined lobby {:?}", x.lobby);
+    }
+}
+
+pub fn on_left_lobby(mut commands: Commands, mut events: EventReader<LeftLobby>) {
+    for _ in events.read() {
+        commands.insert_resource(ConnectedPlayers {
+            players: HashMap::new(),
+        });
+        commands.remove_resource::<CurrentLobby>();
+        commands.remove_resource::<ClientIsLobbyHost>();
+
+        info!("Left lobby.");
     }
 }
 
-pub fn on_other_player_connected(
+pub fn on_player_joined_lobby(
     mut connected_players: ResMut<ConnectedPlayers>,
-    mut events: EventReader<OtherPlayerConnected>,
+    mut events: EventReader<PlayerJoinedLobby>,
 ) {
     for x in events.read() {
         connected_players
             .players
             .insert(x.player.id, x.player.clone());
 
-        info!("Other player connected: {:?}", x.player);
+        info!("Player joined the lobby: {:?}", x.player);
+    }
+}
+
+pub fn on_player_left_lobby(
+    mut connected_players: ResMut<ConnectedPlayers>,
+    mut events: EventReader<PlayerLeftLobby>,
+) {
+    for x in events.read() {
+        connected_players.players.remove(&x.player_id);
+        info!("Player {} left the lobby.", x.player_id);
+    }
+}
+
+pub fn on_player_disconnected(
+    mut absent_players: ResMut<AbsentPlayers>,
+    mut events: EventReader<PlayerDisconnected>,
+) {
+    for x in events.read() {
+        // The server keeps their seat around in case they come back
+        absent_players.players.insert(x.player_id);
+        info!("Player {} disconnected.", x.player_id);
+    }
+}
+
+pub fn on_player_reconnected(
+    mut absent_players: ResMut<AbsentPlayers>,
+    mut events: EventReader<PlayerReconnected>,
+) {
+    for x in events.read() {
+        absent_players.players.remove(&x.player_id);
+        info!("Player {} reconnected.", x.player_id);
     }
 }
 
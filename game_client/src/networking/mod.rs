@@ -4,9 +4,10 @@ mod network;
 mod network_plugin;
 
 use bevy::prelude::Resource;
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
 use game_common::combat_unit::ActorId;
-use game_common::player::{Player, PlayerId};
+use game_common::lobby::LobbyInfo;
+use game_common::player::{Player, PlayerId, SessionToken};
 pub use network::Network; // TODO: Should probably not be public and instead communicate via events
 pub use network_plugin::{NetworkPlugin, NetworkState};
 
@@ -23,3 +24,27 @@ pub struct ConnectedPlayers {
 
 #[derive(Resource)]
 pub struct ClientIsLobbyHost;
+
+/// The lobby we are currently in. Players without one get to pick one from the lobby list.
+#[derive(Resource)]
+pub struct CurrentLobby {
+    pub info: LobbyInfo,
+}
+
+/// Players whose connection to the server got lost during the current game.
+#[derive(Resource, Default)]
+pub struct AbsentPlayers {
+    pub players: HashSet<PlayerId>,
+}
+
+/// Lets us resume our session with the server in case the connection gets lost.
+#[derive(Resource)]
+pub struct ResumableSession {
+    pub token: SessionToken,
+}
+
+/// Why the server refused the most recent connection attempt, so it can be shown to the player.
+#[derive(Resource)]
+pub struct ConnectionRefusedReason {
+    pub reason: String,
+}
@@ -1,8 +1,8 @@
 use crate::networking::network_plugin::{ServerConnection, ServerConnectionUpdate};
-use bevy::log::{error, warn};
+use bevy::log::{error, info};
 use bevy::prelude::Resource;
 use game_common::network_events::NETWORK_IDLE_TIMEOUT;
-use game_common::network_helpers;
+use game_common::network_helpers::FrameBuffer;
 use tokio::io::AsyncReadExt;
 use tokio::runtime::{Handle, Runtime};
 use tokio::sync::mpsc;
@@ -15,6 +15,7 @@ pub struct Network {
 
     pub(in crate::networking) connection_receiver: mpsc::UnboundedReceiver<ServerConnectionUpdate>,
     connection_sender: mpsc::UnboundedSender<ServerConnectionUpdate>,
+    close_sender: Option<mpsc::UnboundedSender<()>>,
 }
 
 impl Default for Network {
@@ -27,6 +28,7 @@ impl Default for Network {
             tokio_handle,
             connection_sender: tx,
             connection_receiver: rx,
+            close_sender: None,
         }
     }
 }
@@ -36,6 +38,8 @@ impl Network {
         let _guard = self.tokio_handle.enter();
 
         let connection_tx = self.connection_sender.clone();
+        let (close_tx, mut close_rx) = mpsc::unbounded_channel();
+        self.close_sender = Some(close_tx);
 
         tokio::spawn(async move {
             let config = ClientConfig::builder()
@@ -52,7 +56,7 @@ impl Network {
                 .await
             {
                 Ok(connection) => {
-                    let mut buffer = network_helpers::create_buffer();
+                    let mut frame_buffer = FrameBuffer::default();
                     match connection.open_bi().await.unwrap().await {
                         Ok((mut send_stream, mut receive_stream)) => {
                             let (tx_rx, rx_rx) = mpsc::unbounded_channel();
@@ -70,20 +74,36 @@ impl Network {
                                 return;
                             }
 
-                            loop {
+                            'connection: loop {
                                 tokio::select! {
+                                    _ = close_rx.recv() => {
+                                        let _ = send_stream.finish().await;
+                                        let _ = connection_tx.send(ServerConnectionUpdate::ConnectionDropped);
+                                        break;
+                                    }
                                     Some(bytes) = rx_tx.recv() => {
                                         let _ = send_stream.write_all(&bytes).await;
                                     }
-                                    result = receive_stream.read_buf(&mut buffer) => match result {
+                                    result = receive_stream.read_buf(frame_buffer.read_buffer()) => match result {
                                         Ok(bytes) => {
                                             if bytes == 0 {
                                                 error!("Bytes was 0!");
                                                 let _ = connection_tx.send(ServerConnectionUpdate::ConnectionDropped);
                                                 break;
                                             }
-                                            let _ = tx_rx.send(buffer.split().freeze());
-                                            network_helpers::reclaim_buffer_capacity_if_necessary(&mut buffer);
+                                            loop {
+                                                match frame_buffer.next_frame() {
+                                                    Ok(Some(frame)) => {
+                                                        let _ = tx_rx.send(frame);
+                                                    }
+                                                    Ok(None) => break,
+                                                    Err(e) => {
+                                                        error!("Error when receiving data from server: {}", e);
+                                                        let _ = connection_tx.send(ServerConnectionUpdate::ConnectionDropped);
+                                                        break 'connection;
+                                                    }
+                                                }
+                                            }
                                         }
                                         Err(e) => {
                                             error!("Error when receiving data from server: {:?}", e);
@@ -109,7 +129,10 @@ impl Network {
     }
 
     pub fn disconnect(&mut self) {
-        // TODO
-        warn!("Disconnecting isn't yet implemented. You are forever trapped here! :^)")
+        if let Some(close_sender) = self.close_sender.take() {
+            if close_sender.send(()).is_ok() {
+                info!("Disconnecting from server.");
+            }
+        }
     }
 }
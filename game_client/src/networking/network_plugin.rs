@@ -3,12 +3,13 @@ use crate::networking::internal_event_handlers;
 use crate::networking::network::Network;
 use bevy::prelude::{
     error, in_state, info, on_event, App, Commands, Condition, EventReader, IntoSystemConfigs,
-    NextState, Plugin, PostUpdate, PreUpdate, ResMut, Resource, States,
+    NextState, OnEnter, Plugin, PostUpdate, PreUpdate, ResMut, Resource, States,
 };
 use bytes::Bytes;
 use game_common::network_events::client_to_server::ClientToServerMessage;
 use game_common::network_events::server_to_client::{
-    OtherPlayerConnected, UpdateReadyStateForPlayer, YouConnected,
+    ConnectionRefused, JoinedLobby, LeftLobby, PlayerDisconnected, PlayerJoinedLobby,
+    PlayerLeftLobby, PlayerReconnected, UpdateReadyStateForPlayer, YouConnected,
 };
 use game_common::network_events::NetworkMessage;
 use tokio::sync::mpsc;
@@ -29,17 +30,38 @@ impl Plugin for NetworkPlugin {
                                 .or_else(in_state(NetworkState::Connected)),
                         ),
                     ),
+                    internal_event_handlers::on_connection_refused
+                        .run_if(on_event::<ConnectionRefused>()),
                     internal_event_handlers::on_you_connected
                         .run_if(in_state(NetworkState::Authenticating))
                         .run_if(on_event::<YouConnected>()),
-                    internal_event_handlers::on_other_player_connected
+                    internal_event_handlers::on_joined_lobby
                         .run_if(in_state(NetworkState::Connected))
-                        .run_if(on_event::<OtherPlayerConnected>()),
+                        .run_if(on_event::<JoinedLobby>()),
+                    internal_event_handlers::on_left_lobby
+                        .run_if(in_state(NetworkState::Connected))
+                        .run_if(on_event::<LeftLobby>()),
+                    internal_event_handlers::on_player_joined_lobby
+                        .run_if(in_state(NetworkState::Connected))
+                        .run_if(on_event::<PlayerJoinedLobby>()),
+                    internal_event_handlers::on_player_left_lobby
+                        .run_if(in_state(NetworkState::Connected))
+                        .run_if(on_event::<PlayerLeftLobby>()),
+                    internal_event_handlers::on_player_disconnected
+                        .run_if(in_state(NetworkState::Connected))
+                        .run_if(on_event::<PlayerDisconnected>()),
+                    internal_event_handlers::on_player_reconnected
+                        .run_if(in_state(NetworkState::Connected))
+                        .run_if(on_event::<PlayerReconnected>()),
                     internal_event_handlers::on_update_ready_state_for_player
                         .run_if(in_state(NetworkState::Connected))
                         .run_if(on_event::<UpdateReadyStateForPlayer>()),
                 ),
             )
+            .add_systems(
+                OnEnter(NetworkState::Authenticating),
+                internal_event_handlers::send_hello,
+            )
             .add_systems(
                 PostUpdate,
                 (event_processor.run_if(on_event::<ClientToServerMessage>())).run_if(
@@ -70,7 +92,7 @@ pub enum NetworkState {
     Connected,
 }
 
-fn check_for_connection_updates(
+pub(in crate::networking) fn check_for_connection_updates(
     mut commands: Commands,
     mut network: ResMut<Network>,
     mut next_network_state: ResMut<NextState<NetworkState>>,
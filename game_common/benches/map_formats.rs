@@ -0,0 +1,42 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use game_common::game_map::{Fluid, FluidKind, GameMap, MapFormat, TileSurface};
+
+const RADIUS: u32 = 30;
+
+/// A map big enough for the format differences to matter, with some variety so compression isn't trivial.
+fn create_map() -> GameMap {
+    let mut map = GameMap::new(RADIUS);
+    for (hex, tile) in map.tiles.iter_mut() {
+        tile.height = (hex.x * 7 + hex.y * 13).rem_euclid(6) as u8 + 1;
+        tile.surface = match (hex.x - hex.y).rem_euclid(4) {
+            0 => TileSurface::Grass,
+            1 => TileSurface::Stone,
+            2 => TileSurface::Sand,
+            _ => TileSurface::Earth,
+        };
+        if hex.length() % 9 == 0 {
+            tile.fluid = Some(Fluid {
+                height: 0.5,
+                kind: FluidKind::Water,
+            });
+        }
+    }
+
+    map
+}
+
+fn map_formats(c: &mut Criterion) {
+    let map = create_map();
+    for format in [MapFormat::Ron, MapFormat::Binary] {
+        let bytes = map.encode(format).unwrap();
+        c.bench_function(&format!("encode {:?}", format), |b| {
+            b.iter(|| map.encode(black_box(format)).unwrap())
+        });
+        c.bench_function(&format!("decode {:?}", format), |b| {
+            b.iter(|| GameMap::decode(black_box(&bytes)).unwrap())
+        });
+    }
+}
+
+criterion_group!(benches, map_formats);
+criterion_main!(benches);
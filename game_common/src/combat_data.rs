@@ -1,12 +1,14 @@
 use crate::combat_turn::CombatTurn;
-use crate::combat_unit::{ActorId, CombatUnit, UnitId};
+use crate::combat_unit::{CombatUnit, UnitId};
+use crate::content_hash::content_hash;
 use crate::game_map::GameMap;
 use bevy::prelude::{error, Resource};
 use bevy::utils::HashMap;
 use hexx::Hex;
+use serde::{Deserialize, Serialize};
 
 /// Combat Data shared between client and server. Things in here should always be kept in sync.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ecs", derive(Resource))]
 pub struct CombatData {
     pub units: HashMap<UnitId, CombatUnit>,
@@ -15,11 +17,34 @@ pub struct CombatData {
 }
 
 impl CombatData {
+    /// Hashes everything in here, sorted first so the result doesn't depend on HashMap ordering.
+    /// Sent by the server after every update, so clients can tell when they are out of sync.
+    #[must_use]
+    pub fn content_hash(&self) -> u64 {
+        let mut units: Vec<_> = self.units.values().collect();
+        units.sort_by_key(|unit| unit.id);
+        let mut unit_positions: Vec<_> = self.unit_positions.iter().collect();
+        unit_positions.sort_by_key(|(hex, _)| (hex.x, hex.y));
+
+        content_hash(&(units, unit_positions, &self.current_turn))
+    }
+
     pub fn start_unit_turn(&mut self, unit_id: UnitId) {
         let unit = &self.units[&unit_id];
         self.current_turn = CombatTurn::start_unit_turn(unit);
     }
 
+    /// Ticks the status effects of the currently active unit, if there is one, and recharges its reaction.
+    /// Needs to be called before [Self::get_unit_for_next_turn], as status effects might change its speed.
+    pub fn end_unit_turn(&mut self) {
+        if let CombatTurn::UnitTurn(turn) = &self.current_turn {
+            if let Some(unit) = self.units.get_mut(&turn.unit_id) {
+                unit.tick_status_effects();
+                unit.is_reaction_ready = true;
+            }
+        }
+    }
+
     /// Returns a reference to the currently active unit.
     /// # Panics
     /// In case turn data is invalid or there is no active unit.
@@ -55,11 +80,6 @@ impl CombatData {
                     continue;
                 }
 
-                if x.owner == ActorId::AI {
-                    // TODO: Don't skip poor monsters. Poor creatures!
-                    continue;
-                }
-
                 x.turn_counter += x.stats_after_buffs.speed;
                 if x.turn_counter >= highest_counter {
                     if x.turn_counter > highest_counter {
@@ -144,6 +164,23 @@ mod tests {
     use crate::combat_turn::{CombatTurn, UnitTurn};
     use crate::combat_unit::CombatUnit;
     use crate::unit_stats::UnitStats;
+    use hexx::Hex;
+
+    #[test]
+    fn content_hash_should_only_depend_on_content() {
+        let units = vec![
+            CombatUnit::create_mock(1, 1).with_position(Hex::new(0, 0)),
+            CombatUnit::create_mock(2, 1).with_position(Hex::new(1, 0)),
+            CombatUnit::create_mock(3, 2).with_position(Hex::new(0, 1)),
+        ];
+        let combat_data = CombatData::create_mock().with_units(units.clone());
+        let reversed = CombatData::create_mock().with_units(units.into_iter().rev().collect());
+        assert_eq!(combat_data.content_hash(), reversed.content_hash());
+
+        let mut changed = reversed;
+        changed.units.get_mut(&2).unwrap().hp -= 1;
+        assert_ne!(combat_data.content_hash(), changed.content_hash());
+    }
 
     #[test]
     fn test_start_unit_turn() {
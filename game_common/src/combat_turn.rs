@@ -1,8 +1,9 @@
 use crate::combat_unit::{CombatUnit, UnitId};
 use crate::player::PlayerId;
 use enum_as_inner::EnumAsInner;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq, EnumAsInner)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumAsInner, Serialize, Deserialize)]
 pub enum CombatTurn {
     Undefined,
     PlaceUnit(PlaceUnit),
@@ -21,12 +22,12 @@ impl CombatTurn {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PlaceUnit {
     pub player_id: PlayerId,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UnitTurn {
     pub unit_id: UnitId,
     pub remaining_movement: u8,
@@ -36,6 +37,14 @@ pub struct UnitTurn {
 impl UnitTurn {
     #[must_use]
     fn start(unit: &CombatUnit) -> UnitTurn {
+        if unit.is_stunned() {
+            return UnitTurn {
+                unit_id: unit.id,
+                remaining_movement: 0,
+                remaining_actions: 0,
+            };
+        }
+
         UnitTurn {
             unit_id: unit.id,
             remaining_movement: unit.stats_after_buffs.movement,
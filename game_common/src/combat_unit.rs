@@ -1,13 +1,17 @@
+use crate::game_data::element::Resistances;
 use crate::game_data::equipment::WeaponId;
 use crate::game_data::equipment::{AccessoryId, ArmorId};
 use crate::game_data::monster::{MonsterDefinition, MonsterId};
+use crate::game_data::movement_type::MovementType;
 use crate::game_data::race::{RaceId, DEBUG_RACE_ID};
+use crate::game_data::reaction::ReactionId;
 use crate::game_data::skill::SkillId;
 use crate::game_data::unit_definition::UnitDefinition;
 use crate::game_data::GameData;
 use crate::player::PlayerId;
+use crate::status_effect::{calculate_stats_after_buffs, StatusEffect, StatusEffectKind};
 use crate::unit_stats::UnitStats;
-use hexx::Hex;
+use hexx::{EdgeDirection, Hex};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::sync::atomic::{AtomicU32, Ordering};
@@ -62,10 +66,18 @@ pub struct CombatUnit {
     pub owner: ActorId,
     pub name: String,
     pub position: Hex,
+    /// Attacks from the side or back of a unit deal more damage.
+    pub facing: EdgeDirection,
     pub hp: u32,
     pub mp: u32,
     pub base_stats: UnitStats,
     pub stats_after_buffs: UnitStats,
+    pub resistances: Resistances,
+    pub movement_type: MovementType,
+    pub status_effects: Vec<StatusEffect>,
+    pub reaction: Option<ReactionId>,
+    /// Reactions can only trigger once until they recharge at the end of the unit's turn.
+    pub is_reaction_ready: bool,
     pub turn_counter: u32,
     pub turn_tiebreaker: u32,
 }
@@ -84,10 +96,31 @@ impl Display for CombatUnit {
 
 impl From<&MonsterDefinition> for CombatUnit {
     fn from(monster: &MonsterDefinition) -> Self {
-        let mut result = Self::create_debug_unit(get_unique_unit_id(), ActorId::AI);
-        result.kind = CombatUnitKind::Monster(MonsterData {
-            monster_id: monster.id,
-        });
+        let id = get_unique_unit_id();
+        let stats: UnitStats = monster.stats.into();
+
+        let mut result = CombatUnit {
+            id,
+            owner: ActorId::AI,
+            kind: CombatUnitKind::Monster(MonsterData {
+                monster_id: monster.id,
+            }),
+            name: monster.name.clone(),
+            position: Hex::ZERO,
+            facing: EdgeDirection::default(),
+            hp: stats.max_health,
+            mp: stats.max_mana,
+            base_stats: stats.clone(),
+            stats_after_buffs: stats,
+            resistances: monster.resistances,
+            movement_type: monster.movement_type,
+            status_effects: Vec::new(),
+            reaction: None,
+            is_reaction_ready: true,
+            turn_counter: 0,
+            turn_tiebreaker: 0,
+        };
+        result.init_tiebreaker();
         result
     }
 }
@@ -103,10 +136,16 @@ impl CombatUnit {
             kind,
             name: unit.name.clone(),
             position: Hex::ZERO,
+            facing: EdgeDirection::default(),
             hp: stats.max_health,
             mp: stats.max_mana,
             base_stats: stats.clone(),
             stats_after_buffs: stats,
+            resistances: unit.calculate_resistances(game_data),
+            movement_type: unit.calculate_movement_type(game_data),
+            status_effects: Vec::new(),
+            reaction: unit.reaction,
+            is_reaction_ready: true,
             turn_counter: 0,
             turn_tiebreaker: 0,
         };
@@ -130,6 +169,7 @@ impl CombatUnit {
             }),
             name: format!("Unit {id}"),
             position: Hex::ZERO,
+            facing: EdgeDirection::default(),
             hp: max_hp,
             mp: max_mp,
             base_stats: UnitStats {
@@ -148,6 +188,11 @@ impl CombatUnit {
                 strength: 10,
                 speed: 50,
             },
+            resistances: Resistances::default(),
+            movement_type: MovementType::Walker,
+            status_effects: Vec::new(),
+            reaction: None,
+            is_reaction_ready: true,
             turn_counter: 0,
             turn_tiebreaker: 0,
         };
@@ -159,6 +204,64 @@ impl CombatUnit {
         return self.hp == 0;
     }
 
+    /// Turns the unit to face `target`. Nothing happens if it is already standing there.
+    pub fn turn_towards(&mut self, target: Hex) {
+        if target != self.position {
+            self.facing = self.position.main_direction_to(target);
+        }
+    }
+
+    /// Turns the unit into the direction of the last step in `path`.
+    pub fn face_along_path(&mut self, path: &[Hex]) {
+        if let [.., previous, last] = path {
+            if previous != last {
+                self.facing = previous.main_direction_to(*last);
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn is_stunned(&self) -> bool {
+        self.status_effects
+            .iter()
+            .any(|x| x.kind == StatusEffectKind::Stun)
+    }
+
+    /// Adds `status` to this unit. Applying an effect which is already active only refreshes its duration.
+    pub fn apply_status_effect(&mut self, status: StatusEffect) {
+        match self
+            .status_effects
+            .iter_mut()
+            .find(|x| x.kind == status.kind)
+        {
+            Some(existing) => {
+                existing.remaining_turns = existing.remaining_turns.max(status.remaining_turns)
+            }
+            None => self.status_effects.push(status),
+        }
+
+        self.recalculate_stats_after_buffs();
+    }
+
+    /// Should be called at the end of this unit's turn.
+    /// Deals poison damage and removes every effect which just ran out.
+    pub fn tick_status_effects(&mut self) {
+        for effect in &mut self.status_effects {
+            if let StatusEffectKind::Poison { damage_per_turn } = effect.kind {
+                self.hp = self.hp.saturating_sub(damage_per_turn);
+            }
+            effect.remaining_turns = effect.remaining_turns.saturating_sub(1);
+        }
+
+        self.status_effects.retain(|x| x.remaining_turns > 0);
+        self.recalculate_stats_after_buffs();
+    }
+
+    pub fn recalculate_stats_after_buffs(&mut self) {
+        self.stats_after_buffs =
+            calculate_stats_after_buffs(&self.base_stats, &self.status_effects);
+    }
+
     fn init_tiebreaker(&mut self) {
         self.turn_tiebreaker = self.base_stats.speed * 1000 + self.id;
     }
@@ -179,7 +282,7 @@ impl CombatUnit {
 
                 result
             }
-            CombatUnitKind::Monster(_) => Vec::new(),
+            CombatUnitKind::Monster(data) => game_data.monsters[&data.monster_id].skills.clone(),
         }
     }
 }
@@ -187,10 +290,15 @@ impl CombatUnit {
 #[cfg(feature = "test_helpers")]
 pub mod test_helpers {
     use crate::combat_unit::{ActorId, CombatUnit, CombatUnitKind, HumanoidData, UnitId};
+    use crate::game_data::element::Resistances;
+    use crate::game_data::equipment::WeaponId;
+    use crate::game_data::movement_type::MovementType;
     use crate::game_data::race::DEBUG_RACE_ID;
+    use crate::game_data::reaction::ReactionId;
     use crate::player::PlayerId;
+    use crate::status_effect::StatusEffect;
     use crate::unit_stats::UnitStats;
-    use hexx::Hex;
+    use hexx::{EdgeDirection, Hex};
 
     impl CombatUnit {
         /// Create a mock Unit with sensible defaults.
@@ -207,11 +315,16 @@ pub mod test_helpers {
                 }),
                 name: format!("Test Unit #{id}"),
                 position: Hex::ZERO,
+                facing: EdgeDirection::default(),
                 hp: 10,
                 mp: 10,
-                exp: 0,
                 base_stats: UnitStats::create_mock(),
                 stats_after_buffs: UnitStats::create_mock(),
+                resistances: Resistances::default(),
+                movement_type: MovementType::Walker,
+                status_effects: Vec::new(),
+                reaction: None,
+                is_reaction_ready: true,
                 turn_counter: 0,
                 turn_tiebreaker: 0,
             };
@@ -225,9 +338,46 @@ pub mod test_helpers {
             self
         }
 
+        pub fn with_facing(mut self, facing: EdgeDirection) -> Self {
+            self.facing = facing;
+            self
+        }
+
+        pub fn with_owner(mut self, owner: ActorId) -> Self {
+            self.owner = owner;
+            self
+        }
+
+        pub fn with_reaction(mut self, reaction: ReactionId) -> Self {
+            self.reaction = Some(reaction);
+            self
+        }
+
+        pub fn with_weapon(mut self, weapon: WeaponId) -> Self {
+            if let CombatUnitKind::Humanoid(data) = &mut self.kind {
+                data.weapon = Some(weapon);
+            }
+            self
+        }
+
+        pub fn with_status_effect(mut self, status: StatusEffect) -> Self {
+            self.apply_status_effect(status);
+            self
+        }
+
+        pub fn with_movement_type(mut self, movement_type: MovementType) -> Self {
+            self.movement_type = movement_type;
+            self
+        }
+
+        pub fn with_resistances(mut self, resistances: Resistances) -> Self {
+            self.resistances = resistances;
+            self
+        }
+
         pub fn with_stats(mut self, stats: UnitStats) -> Self {
-            self.base_stats = stats.clone();
-            self.stats_after_buffs = stats;
+            self.base_stats = stats;
+            self.recalculate_stats_after_buffs();
             self.init_tiebreaker();
             self
         }
@@ -0,0 +1,63 @@
+use bevy::utils::HashMap;
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::io::Write;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hashes the serialized representation of `value` with 64-bit FNV-1a.
+/// Unlike the std hashers, the result is guaranteed to be the same across platforms and compiler versions,
+/// so it can be used to compare data between clients and the server.
+/// Make sure `value` serializes in a stable order, which isn't the case for HashMaps.
+#[must_use]
+pub fn content_hash<T: Serialize>(value: &T) -> u64 {
+    let mut writer = FnvWriter {
+        hash: FNV_OFFSET_BASIS,
+    };
+    bincode::serde::encode_into_std_write(value, &mut writer, bincode::config::standard())
+        .expect("Plain data should always be serializable!");
+
+    writer.hash
+}
+
+/// Serializes `map` sorted by its keys, so [content_hash] stays deterministic for types containing HashMaps.
+/// Use it with `#[serde(serialize_with = "crate::content_hash::serialize_sorted")]`.
+pub fn serialize_sorted<S: Serializer, K: Ord + Serialize, V: Serialize>(
+    map: &HashMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
+struct FnvWriter {
+    hash: u64,
+}
+
+impl Write for FnvWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for byte in buf {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::content_hash::content_hash;
+
+    #[test]
+    fn hashes_should_be_stable() {
+        // Values are fixed on purpose, as clients and servers from different builds need to agree on them
+        assert_eq!(0xaf63_bd4c_8601_b7df, content_hash(&0u8));
+        assert_eq!(content_hash(&(1u32, "a")), content_hash(&(1u32, "a")));
+        assert_ne!(content_hash(&(1u32, "a")), content_hash(&(1u32, "b")));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::combat_data::CombatData;
+use crate::combat_unit::{CombatUnit, UnitId};
+use crate::content_hash::content_hash;
+use crate::game_data::unit_definition::UnitDefinition;
+use crate::player_resources::PlayerResources;
+use bevy::utils::HashSet;
+use hexx::Hex;
+use serde::Serialize;
+use std::fmt::{Debug, Formatter};
+
+/// A single field in which the client's state differs from the server's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDifference {
+    pub path: String,
+    pub local: String,
+    pub remote: String,
+}
+
+impl std::fmt::Display for StateDifference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} (local) != {} (server)",
+            self.path, self.local, self.remote
+        )
+    }
+}
+
+/// Compares each field of the given values and records the ones which differ.
+macro_rules! diff_fields {
+    ($differences:expr, $path:expr, $local:expr, $remote:expr, [$($field:ident),* $(,)?]) => {
+        $(
+            diff_field(
+                $differences,
+                format!("{}.{}", $path, stringify!($field)),
+                &$local.$field,
+                &$remote.$field,
+            );
+        )*
+    };
+}
+
+/// Lists every field in which `local` differs from `remote`, to help figure out what caused a desync.
+#[must_use]
+pub fn diff_combat_data(local: &CombatData, remote: &CombatData) -> Vec<StateDifference> {
+    let mut differences = Vec::new();
+    diff_field(
+        &mut differences,
+        "current_turn".into(),
+        &local.current_turn,
+        &remote.current_turn,
+    );
+
+    let mut unit_ids: Vec<UnitId> = local
+        .units
+        .keys()
+        .chain(remote.units.keys())
+        .copied()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    unit_ids.sort();
+    for id in unit_ids {
+        let path = format!("units[{id}]");
+        match (local.units.get(&id), remote.units.get(&id)) {
+            (Some(local), Some(remote)) => diff_combat_unit(&mut differences, &path, local, remote),
+            (local, remote) => diff_field(
+                &mut differences,
+                path,
+                &local.map(CombatUnit::to_string),
+                &remote.map(CombatUnit::to_string),
+            ),
+        }
+    }
+
+    let mut hexes: Vec<Hex> = local
+        .unit_positions
+        .keys()
+        .chain(remote.unit_positions.keys())
+        .copied()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    hexes.sort_by_key(|hex| (hex.x, hex.y));
+    for hex in hexes {
+        diff_field(
+            &mut differences,
+            format!("unit_positions[{}, {}]", hex.x, hex.y),
+            &local.unit_positions.get(&hex),
+            &remote.unit_positions.get(&hex),
+        );
+    }
+
+    differences
+}
+
+/// Lists every field in which `local` differs from `remote`, to help figure out what caused a desync.
+#[must_use]
+pub fn diff_player_resources(
+    local: &PlayerResources,
+    remote: &PlayerResources,
+) -> Vec<StateDifference> {
+    let mut differences = Vec::new();
+    for index in 0..local.units.len().max(remote.units.len()) {
+        let path = format!("units[{index}]");
+        match (local.units.get(index), remote.units.get(index)) {
+            (Some(local), Some(remote)) => {
+                diff_unit_definition(&mut differences, &path, local, remote);
+            }
+            (local, remote) => diff_field(
+                &mut differences,
+                path,
+                &local.map(|x| &x.name),
+                &remote.map(|x| &x.name),
+            ),
+        }
+    }
+
+    differences
+}
+
+fn diff_combat_unit(
+    differences: &mut Vec<StateDifference>,
+    path: &str,
+    local: &CombatUnit,
+    remote: &CombatUnit,
+) {
+    diff_fields!(
+        differences,
+        path,
+        local,
+        remote,
+        [
+            kind,
+            owner,
+            name,
+            position,
+            facing,
+            hp,
+            mp,
+            base_stats,
+            stats_after_buffs,
+            resistances,
+            movement_type,
+            status_effects,
+            reaction,
+            is_reaction_ready,
+            turn_counter,
+            turn_tiebreaker,
+        ]
+    );
+}
+
+fn diff_unit_definition(
+    differences: &mut Vec<StateDifference>,
+    path: &str,
+    local: &UnitDefinition,
+    remote: &UnitDefinition,
+) {
+    diff_fields!(
+        differences,
+        path,
+        local,
+        remote,
+        [
+            id,
+            owner,
+            name,
+            active_class,
+            race,
+            levels,
+            permanently_unlocked_skills,
+            weapon,
+            armor,
+            accessory,
+            reaction,
+        ]
+    );
+}
+
+/// Values are compared by their [content_hash], as not all of them implement PartialEq in a useful way.
+fn diff_field<T: Serialize + Debug>(
+    differences: &mut Vec<StateDifference>,
+    path: String,
+    local: &T,
+    remote: &T,
+) {
+    if content_hash(local) != content_hash(remote) {
+        differences.push(StateDifference {
+            path,
+            local: format!("{local:?}"),
+            remote: format!("{remote:?}"),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::combat_data::CombatData;
+    use crate::combat_unit::CombatUnit;
+    use crate::desync::{diff_combat_data, StateDifference};
+    use hexx::Hex;
+
+    #[test]
+    fn identical_states_should_have_no_differences() {
+        let combat_data = CombatData::create_mock()
+            .with_units(vec![CombatUnit::create_mock(1, 1)])
+            .with_unit_turn(1);
+
+        assert!(diff_combat_data(&combat_data, &combat_data.clone()).is_empty());
+    }
+
+    #[test]
+    fn differences_should_point_to_the_changed_fields() {
+        let local = CombatData::create_mock().with_units(vec![
+            CombatUnit::create_mock(1, 1).with_position(Hex::new(0, 0)),
+            CombatUnit::create_mock(2, 1).with_position(Hex::new(1, 0)),
+        ]);
+        let mut remote = local.clone();
+        remote.units.get_mut(&2).unwrap().hp -= 5;
+        remote.units.remove(&1);
+
+        let differences = diff_combat_data(&local, &remote);
+        let paths: Vec<&str> = differences.iter().map(|x| x.path.as_str()).collect();
+        assert_eq!(vec!["units[1]", "units[2].hp"], paths);
+        assert_eq!(
+            StateDifference {
+                path: "units[2].hp".into(),
+                local: local.units[&2].hp.to_string(),
+                remote: remote.units[&2].hp.to_string(),
+            },
+            differences[1]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::unit_stats::UnitStats;
+use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct BaseStats {
     pub hp: i32,
     pub mp: i32,
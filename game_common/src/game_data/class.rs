@@ -1,8 +1,10 @@
 use crate::game_data::base_stats::BaseStats;
 use crate::game_data::skill::SkillId;
-use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
 
 pub type ClassId = usize;
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ClassDefinition {
     pub id: ClassId,
     pub name: String,
@@ -13,44 +15,53 @@ pub struct ClassDefinition {
 pub const DEBUG_CLASS_FIGHTER: ClassId = 1;
 pub const DEBUG_CLASS_MAGE: ClassId = 2;
 
-impl ClassDefinition {
-    pub fn mock_data() -> HashMap<ClassId, ClassDefinition> {
-        let mut result = HashMap::new();
-
-        result.insert(
-            DEBUG_CLASS_FIGHTER,
-            ClassDefinition {
-                id: DEBUG_CLASS_FIGHTER,
-                name: "Fighter".to_string(),
-                stats_per_level: BaseStats {
-                    hp: 5,
-                    mp: 0,
-                    movement: 0,
-                    jump: 0,
-                    strength: 5,
-                    speed: 3,
+#[cfg(feature = "test_helpers")]
+pub mod test_helpers {
+    use crate::game_data::base_stats::BaseStats;
+    use crate::game_data::class::{
+        ClassDefinition, ClassId, DEBUG_CLASS_FIGHTER, DEBUG_CLASS_MAGE,
+    };
+    use bevy::utils::HashMap;
+
+    impl ClassDefinition {
+        pub fn mock_data() -> HashMap<ClassId, ClassDefinition> {
+            let mut result = HashMap::new();
+
+            result.insert(
+                DEBUG_CLASS_FIGHTER,
+                ClassDefinition {
+                    id: DEBUG_CLASS_FIGHTER,
+                    name: "Fighter".to_string(),
+                    stats_per_level: BaseStats {
+                        hp: 5,
+                        mp: 0,
+                        movement: 0,
+                        jump: 0,
+                        strength: 5,
+                        speed: 3,
+                    },
+                    learnable_skills: vec![],
                 },
-                learnable_skills: vec![],
-            },
-        );
-
-        result.insert(
-            DEBUG_CLASS_MAGE,
-            ClassDefinition {
-                id: DEBUG_CLASS_MAGE,
-                name: "Mage".to_string(),
-                stats_per_level: BaseStats {
-                    hp: 0,
-                    mp: 5,
-                    movement: 0,
-                    jump: 0,
-                    strength: 0,
-                    speed: 2,
+            );
+
+            result.insert(
+                DEBUG_CLASS_MAGE,
+                ClassDefinition {
+                    id: DEBUG_CLASS_MAGE,
+                    name: "Mage".to_string(),
+                    stats_per_level: BaseStats {
+                        hp: 0,
+                        mp: 5,
+                        movement: 0,
+                        jump: 0,
+                        strength: 0,
+                        speed: 2,
+                    },
+                    learnable_skills: vec![],
                 },
-                learnable_skills: vec![],
-            },
-        );
+            );
 
-        result
+            result
+        }
     }
 }
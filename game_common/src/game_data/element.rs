@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Elemental damage types. Skills without an element deal neutral damage which can't be resisted.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Element {
+    Fire,
+    Ice,
+    Lightning,
+    Earth,
+}
+
+impl Display for Element {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Element::Fire => write!(f, "Fire"),
+            Element::Ice => write!(f, "Ice"),
+            Element::Lightning => write!(f, "Lightning"),
+            Element::Earth => write!(f, "Earth"),
+        }
+    }
+}
+
+/// Percentage by which incoming damage of each element is reduced. Negative values represent weaknesses.
+/// Values which are left out in data files default to 0.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Resistances {
+    pub fire: i32,
+    pub ice: i32,
+    pub lightning: i32,
+    pub earth: i32,
+}
+
+impl Resistances {
+    #[must_use]
+    pub fn get(&self, element: Element) -> i32 {
+        match element {
+            Element::Fire => self.fire,
+            Element::Ice => self.ice,
+            Element::Lightning => self.lightning,
+            Element::Earth => self.earth,
+        }
+    }
+
+    /// Reduces `amount` by the resistance against `element`. Resistances above 100% nullify all damage.
+    #[must_use]
+    pub fn apply(&self, element: Element, amount: u32) -> u32 {
+        let percentage = (100 - self.get(element)).max(0) as u32;
+        amount * percentage / 100
+    }
+}
+
+impl std::ops::AddAssign for Resistances {
+    fn add_assign(&mut self, rhs: Self) {
+        self.fire += rhs.fire;
+        self.ice += rhs.ice;
+        self.lightning += rhs.lightning;
+        self.earth += rhs.earth;
+    }
+}
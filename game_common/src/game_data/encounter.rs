@@ -0,0 +1,52 @@
+use crate::game_data::monster::MonsterId;
+use serde::{Deserialize, Serialize};
+
+pub type EncounterId = u32;
+
+/// A group of monsters the players can fight against.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncounterDefinition {
+    pub id: EncounterId,
+    pub name: String,
+    pub monsters: Vec<EncounterMonster>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncounterMonster {
+    pub monster_id: MonsterId,
+    pub count: u8,
+    /// The team id of the map's spawn zone inside which these monsters should be placed.
+    pub spawn_zone: u8,
+}
+
+pub const DEBUG_ENCOUNTER_ID: EncounterId = 1;
+
+#[cfg(feature = "test_helpers")]
+pub mod test_helpers {
+    use crate::game_data::encounter::{
+        EncounterDefinition, EncounterId, EncounterMonster, DEBUG_ENCOUNTER_ID,
+    };
+    use crate::game_data::monster::DEBUG_MONSTER_ID;
+    use bevy::utils::HashMap;
+
+    impl EncounterDefinition {
+        pub(in crate::game_data) fn mock_data() -> HashMap<EncounterId, EncounterDefinition> {
+            let mut result = HashMap::new();
+
+            result.insert(
+                DEBUG_ENCOUNTER_ID,
+                Self {
+                    id: DEBUG_ENCOUNTER_ID,
+                    name: "Slime Pit".into(),
+                    monsters: vec![EncounterMonster {
+                        monster_id: DEBUG_MONSTER_ID,
+                        count: 2,
+                        spawn_zone: 2,
+                    }],
+                },
+            );
+
+            result
+        }
+    }
+}
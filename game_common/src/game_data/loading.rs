@@ -0,0 +1,140 @@
+use bevy::utils::HashMap;
+use ron::de::from_reader;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display, Formatter};
+use std::fs::File;
+use std::hash::Hash;
+use std::path::Path;
+
+/// Enum to keep track of different data file versions. Every data file contains a list of definitions
+/// of a single kind, e.g. `V1([(id: 1, name: "Hooman", ...)])`.
+#[derive(Serialize, Deserialize)]
+pub enum VersionedDataFile<T> {
+    V1(Vec<T>),
+}
+
+/// A single problem which was encountered while loading GameData.
+#[derive(Debug, PartialEq)]
+pub enum GameDataError {
+    UnableToOpenFile { path: String, reason: String },
+    UnableToParseFile { path: String, reason: String },
+    DuplicateId { path: String, id: String },
+    UnknownReference { source: String, reference: String },
+}
+
+impl Display for GameDataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameDataError::UnableToOpenFile { path, reason } => {
+                write!(f, "Unable to open {path}: {reason}")
+            }
+            GameDataError::UnableToParseFile { path, reason } => {
+                write!(f, "Unable to parse {path}: {reason}")
+            }
+            GameDataError::DuplicateId { path, id } => {
+                write!(f, "{path} contains id {id} more than once")
+            }
+            GameDataError::UnknownReference { source, reference } => {
+                write!(f, "{source} references unknown {reference}")
+            }
+        }
+    }
+}
+
+/// Contains every problem which was encountered while loading GameData.
+#[derive(Debug)]
+pub struct GameDataLoadError {
+    pub errors: Vec<GameDataError>,
+}
+
+impl Display for GameDataLoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Encountered {} error(s) while loading game data:",
+            self.errors.len()
+        )?;
+        for error in &self.errors {
+            write!(f, "\n - {error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for GameDataLoadError {}
+
+/// Parses the definitions inside `directory/file_name`, storing them by their id.
+/// Problems are pushed into `errors`, in which case the returned map might be incomplete.
+pub(in crate::game_data) fn load_definitions<Id, T>(
+    directory: &Path,
+    file_name: &str,
+    get_id: impl Fn(&T) -> Id,
+    errors: &mut Vec<GameDataError>,
+) -> HashMap<Id, T>
+where
+    Id: Eq + Hash + Display,
+    T: DeserializeOwned,
+{
+    let path = directory.join(file_name);
+    let path_string = path.display().to_string();
+
+    let file = match File::open(&path) {
+        Ok(file) => file,
+        Err(e) => {
+            errors.push(GameDataError::UnableToOpenFile {
+                path: path_string,
+                reason: e.to_string(),
+            });
+            return HashMap::new();
+        }
+    };
+
+    let definitions = match from_reader::<File, VersionedDataFile<T>>(file) {
+        Ok(VersionedDataFile::V1(definitions)) => definitions,
+        // Older versions would call a .migrate method on them which would upgrade them to VX+1.
+        Err(e) => {
+            errors.push(GameDataError::UnableToParseFile {
+                path: path_string,
+                reason: e.to_string(),
+            });
+            return HashMap::new();
+        }
+    };
+
+    let mut result = HashMap::new();
+    for definition in definitions {
+        let id = get_id(&definition);
+        if result.contains_key(&id) {
+            errors.push(GameDataError::DuplicateId {
+                path: path_string.clone(),
+                id: id.to_string(),
+            });
+        } else {
+            result.insert(id, definition);
+        }
+    }
+
+    result
+}
+
+/// Pushes an `UnknownReference` error for every id in `references` which can't be found in `target`.
+pub(in crate::game_data) fn validate_references<Id, T>(
+    source: impl Fn() -> String,
+    reference_kind: &str,
+    references: &[Id],
+    target: &HashMap<Id, T>,
+    errors: &mut Vec<GameDataError>,
+) where
+    Id: Eq + Hash + Display,
+{
+    for id in references {
+        if !target.contains_key(id) {
+            errors.push(GameDataError::UnknownReference {
+                source: source(),
+                reference: format!("{reference_kind} {id}"),
+            });
+        }
+    }
+}
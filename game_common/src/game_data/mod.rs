@@ -1,20 +1,31 @@
+use crate::content_hash::content_hash;
+use crate::game_data::encounter::{EncounterDefinition, EncounterId};
 use crate::game_data::equipment::{AccessoryDefinition, AccessoryId, ArmorDefinition, ArmorId};
-use crate::game_data::prop::{PropDefinition, PropId};
+use crate::game_data::prop::{Interaction, PropDefinition, PropId};
+use crate::game_data::reaction::{ReactionDefinition, ReactionId};
 use crate::game_data::skill::{SkillDefinition, SkillId};
+use crate::GAME_DATA_DIRECTORY;
 use bevy::prelude::Resource;
 use bevy::utils::HashMap;
 use class::{ClassDefinition, ClassId};
 use equipment::{WeaponDefinition, WeaponId};
+use loading::{load_definitions, validate_references, GameDataLoadError};
 use monster::{MonsterDefinition, MonsterId};
 use race::{RaceDefinition, RaceId};
+use std::path::Path;
 
 pub mod base_stats;
 pub mod class;
+pub mod element;
+pub mod encounter;
 pub mod equipment;
 pub mod level;
+pub mod loading;
 pub mod monster;
+pub mod movement_type;
 pub mod prop;
 pub mod race;
+pub mod reaction;
 pub mod skill;
 pub mod unit_definition;
 
@@ -24,30 +35,189 @@ pub struct GameData {
     pub races: HashMap<RaceId, RaceDefinition>,
     pub classes: HashMap<ClassId, ClassDefinition>,
     pub skills: HashMap<SkillId, SkillDefinition>,
+    pub reactions: HashMap<ReactionId, ReactionDefinition>,
     pub weapons: HashMap<WeaponId, WeaponDefinition>,
     pub armor: HashMap<ArmorId, ArmorDefinition>,
     pub accessories: HashMap<AccessoryId, AccessoryDefinition>,
     pub monsters: HashMap<MonsterId, MonsterDefinition>,
+    pub encounters: HashMap<EncounterId, EncounterDefinition>,
     pub props: HashMap<PropId, PropDefinition>,
 }
 
 impl GameData {
-    pub fn load() -> Self {
-        GameData {
-            races: RaceDefinition::mock_data(),
-            classes: ClassDefinition::mock_data(),
-            skills: SkillDefinition::mock_data(),
-            weapons: WeaponDefinition::mock_weapons(),
-            armor: ArmorDefinition::mock_armor(),
-            accessories: AccessoryDefinition::mock_accessories(),
-            monsters: MonsterDefinition::mock_data(),
-            props: PropDefinition::mock_data(),
+    /// Loads all data files from [`GAME_DATA_DIRECTORY`].
+    pub fn load() -> Result<Self, GameDataLoadError> {
+        Self::load_from_directory(GAME_DATA_DIRECTORY)
+    }
+
+    /// Loads all data files inside `directory` and validates references between them.
+    /// Instead of bailing at the first problem, every encountered error will be collected and returned.
+    pub fn load_from_directory<P: AsRef<Path>>(directory: P) -> Result<Self, GameDataLoadError> {
+        let directory = directory.as_ref();
+        let mut errors = Vec::new();
+
+        let data = GameData {
+            races: load_definitions(
+                directory,
+                "races.ron",
+                |x: &RaceDefinition| x.id,
+                &mut errors,
+            ),
+            classes: load_definitions(
+                directory,
+                "classes.ron",
+                |x: &ClassDefinition| x.id,
+                &mut errors,
+            ),
+            skills: load_definitions(
+                directory,
+                "skills.ron",
+                |x: &SkillDefinition| x.id,
+                &mut errors,
+            ),
+            reactions: load_definitions(
+                directory,
+                "reactions.ron",
+                |x: &ReactionDefinition| x.id,
+                &mut errors,
+            ),
+            weapons: load_definitions(
+                directory,
+                "weapons.ron",
+                |x: &WeaponDefinition| x.id,
+                &mut errors,
+            ),
+            armor: load_definitions(
+                directory,
+                "armor.ron",
+                |x: &ArmorDefinition| x.id,
+                &mut errors,
+            ),
+            accessories: load_definitions(
+                directory,
+                "accessories.ron",
+                |x: &AccessoryDefinition| x.id,
+                &mut errors,
+            ),
+            monsters: load_definitions(
+                directory,
+                "monsters.ron",
+                |x: &MonsterDefinition| x.id,
+                &mut errors,
+            ),
+            encounters: load_definitions(
+                directory,
+                "encounters.ron",
+                |x: &EncounterDefinition| x.id,
+                &mut errors,
+            ),
+            props: load_definitions(
+                directory,
+                "props.ron",
+                |x: &PropDefinition| x.id,
+                &mut errors,
+            ),
+        };
+
+        data.validate_references(&mut errors);
+
+        if errors.is_empty() {
+            Ok(data)
+        } else {
+            Err(GameDataLoadError { errors })
+        }
+    }
+
+    fn validate_references(&self, errors: &mut Vec<loading::GameDataError>) {
+        for race in self.races.values() {
+            let source = || format!("Race {} ({})", race.id, race.name);
+            validate_references(source, "Skill", &race.extra_skills, &self.skills, errors);
+        }
+        for class in self.classes.values() {
+            let source = || format!("Class {} ({})", class.id, class.name);
+            validate_references(
+                source,
+                "Skill",
+                &class.learnable_skills,
+                &self.skills,
+                errors,
+            );
+        }
+        for reaction in self.reactions.values() {
+            let source = || format!("Reaction {} ({})", reaction.id, reaction.name);
+            validate_references(source, "Skill", &[reaction.skill], &self.skills, errors);
+        }
+        for weapon in self.weapons.values() {
+            let source = || format!("Weapon {} ({})", weapon.id, weapon.name);
+            validate_references(source, "Skill", &weapon.skills, &self.skills, errors);
+        }
+        for armor in self.armor.values() {
+            let source = || format!("Armor {} ({})", armor.id, armor.name);
+            validate_references(source, "Skill", &armor.skills, &self.skills, errors);
+        }
+        for accessory in self.accessories.values() {
+            let source = || format!("Accessory {} ({})", accessory.id, accessory.name);
+            validate_references(source, "Skill", &accessory.skills, &self.skills, errors);
+        }
+        for monster in self.monsters.values() {
+            let source = || format!("Monster {} ({})", monster.id, monster.name);
+            validate_references(source, "Skill", &monster.skills, &self.skills, errors);
+        }
+        for encounter in self.encounters.values() {
+            let source = || format!("Encounter {} ({})", encounter.id, encounter.name);
+            let monsters: Vec<MonsterId> =
+                encounter.monsters.iter().map(|x| x.monster_id).collect();
+            validate_references(source, "Monster", &monsters, &self.monsters, errors);
+        }
+        for prop in self.props.values() {
+            let source = || format!("Prop {} ({})", prop.id, prop.name);
+            let replacements: Vec<PropId> = prop
+                .components
+                .interactable
+                .iter()
+                .map(|Interaction::ReplaceWith(id)| *id)
+                .collect();
+            validate_references(source, "Prop", &replacements, &self.props, errors);
         }
     }
+
+    /// Hashes every definition. Entries are sorted by their id first, so the result doesn't depend on HashMap ordering.
+    /// Clients and servers compare this to make sure they are using the same data.
+    #[must_use]
+    pub fn content_hash(&self) -> u64 {
+        content_hash(&(
+            sorted_by_id(&self.races),
+            sorted_by_id(&self.classes),
+            sorted_by_id(&self.skills),
+            sorted_by_id(&self.reactions),
+            sorted_by_id(&self.weapons),
+            sorted_by_id(&self.armor),
+            sorted_by_id(&self.accessories),
+            sorted_by_id(&self.monsters),
+            sorted_by_id(&self.encounters),
+            sorted_by_id(&self.props),
+        ))
+    }
+}
+
+fn sorted_by_id<Id: Ord, Definition>(definitions: &HashMap<Id, Definition>) -> Vec<&Definition> {
+    let mut entries: Vec<_> = definitions.iter().collect();
+    entries.sort_by_key(|(id, _)| *id);
+    entries
+        .into_iter()
+        .map(|(_, definition)| definition)
+        .collect()
 }
 
 #[cfg(feature = "test_helpers")]
 pub mod test_helpers {
+    use crate::game_data::class::ClassDefinition;
+    use crate::game_data::encounter::EncounterDefinition;
+    use crate::game_data::equipment::{AccessoryDefinition, ArmorDefinition, WeaponDefinition};
+    use crate::game_data::monster::MonsterDefinition;
+    use crate::game_data::prop::PropDefinition;
+    use crate::game_data::race::RaceDefinition;
+    use crate::game_data::reaction::ReactionDefinition;
     use crate::game_data::skill::SkillDefinition;
     use crate::game_data::GameData;
 
@@ -59,10 +229,12 @@ pub mod test_helpers {
                 races: Default::default(),
                 classes: Default::default(),
                 skills: Default::default(),
+                reactions: Default::default(),
                 weapons: Default::default(),
                 armor: Default::default(),
                 accessories: Default::default(),
                 monsters: Default::default(),
+                encounters: Default::default(),
                 props: Default::default(),
             }
         }
@@ -71,5 +243,135 @@ pub mod test_helpers {
             self.skills = SkillDefinition::mock_data();
             self
         }
+
+        pub fn with_all_mock_reactions(mut self) -> Self {
+            self.reactions = ReactionDefinition::mock_data();
+            self
+        }
+
+        pub fn with_all_mock_races(mut self) -> Self {
+            self.races = RaceDefinition::mock_data();
+            self
+        }
+
+        pub fn with_all_mock_classes(mut self) -> Self {
+            self.classes = ClassDefinition::mock_data();
+            self
+        }
+
+        pub fn with_all_mock_equipment(mut self) -> Self {
+            self.weapons = WeaponDefinition::mock_weapons();
+            self.armor = ArmorDefinition::mock_armor();
+            self.accessories = AccessoryDefinition::mock_accessories();
+            self
+        }
+
+        pub fn with_all_mock_monsters(mut self) -> Self {
+            self.monsters = MonsterDefinition::mock_data();
+            self
+        }
+
+        pub fn with_all_mock_encounters(mut self) -> Self {
+            self.encounters = EncounterDefinition::mock_data();
+            self
+        }
+
+        pub fn with_all_mock_props(mut self) -> Self {
+            self.props = PropDefinition::mock_data();
+            self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_data::loading::GameDataError;
+    use crate::game_data::GameData;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const SHIPPED_DATA_DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../data");
+
+    #[test]
+    fn shipped_data_should_load_without_errors() {
+        let result = GameData::load_from_directory(SHIPPED_DATA_DIRECTORY);
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+    }
+
+    #[test]
+    fn content_hash_should_not_depend_on_load_order() {
+        let first = GameData::load_from_directory(SHIPPED_DATA_DIRECTORY).unwrap();
+        let second = GameData::load_from_directory(SHIPPED_DATA_DIRECTORY).unwrap();
+        assert_eq!(first.content_hash(), second.content_hash());
+
+        let mut changed = second;
+        let skill = changed.skills.values_mut().next().unwrap();
+        skill.name.push('!');
+        assert_ne!(first.content_hash(), changed.content_hash());
+    }
+
+    #[test]
+    fn missing_directory_should_list_every_file() {
+        let dir = TempDir::new().unwrap();
+        let errors = GameData::load_from_directory(dir.path())
+            .err()
+            .unwrap()
+            .errors;
+
+        assert_eq!(10, errors.len());
+        assert!(errors
+            .iter()
+            .all(|x| matches!(x, GameDataError::UnableToOpenFile { .. })));
+    }
+
+    #[test]
+    fn unknown_references_and_duplicates_should_be_reported() {
+        let dir = TempDir::new().unwrap();
+        for entry in fs::read_dir(SHIPPED_DATA_DIRECTORY).unwrap() {
+            let entry = entry.unwrap();
+            fs::copy(entry.path(), dir.path().join(entry.file_name())).unwrap();
+        }
+
+        fs::write(
+            dir.path().join("weapons.ron"),
+            r#"V1([
+                (id: 1, name: "Broken", description: "", stats: (hp: 0, mp: 0, movement: 0, jump: 0, strength: 0, speed: 0), resistances: (), skills: [42, 1337]),
+                (id: 1, name: "Twin", description: "", stats: (hp: 0, mp: 0, movement: 0, jump: 0, strength: 0, speed: 0), resistances: (), skills: []),
+            ])"#,
+        )
+        .unwrap();
+
+        let errors = GameData::load_from_directory(dir.path())
+            .err()
+            .unwrap()
+            .errors;
+
+        assert_eq!(3, errors.len(), "{:?}", errors);
+        assert!(errors.contains(&GameDataError::UnknownReference {
+            source: "Weapon 1 (Broken)".into(),
+            reference: "Skill 42".into(),
+        }));
+        assert!(errors.contains(&GameDataError::UnknownReference {
+            source: "Weapon 1 (Broken)".into(),
+            reference: "Skill 1337".into(),
+        }));
+        assert!(errors
+            .iter()
+            .any(|x| matches!(x, GameDataError::DuplicateId { id, .. } if id == "1")));
+    }
+
+    #[test]
+    fn invalid_syntax_should_be_reported() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("races.ron"), "V1([(id: ").unwrap();
+
+        let errors = GameData::load_from_directory(dir.path())
+            .err()
+            .unwrap()
+            .errors;
+
+        assert!(errors
+            .iter()
+            .any(|x| matches!(x, GameDataError::UnableToParseFile { path, .. } if path.ends_with("races.ron"))));
     }
 }
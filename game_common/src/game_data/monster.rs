@@ -1,39 +1,60 @@
 use crate::game_data::base_stats::BaseStats;
+use crate::game_data::element::Resistances;
+use crate::game_data::movement_type::MovementType;
 use crate::game_data::skill::SkillId;
-use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
 
 pub type MonsterId = u32;
 
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MonsterDefinition {
     pub id: MonsterId,
     pub name: String,
     pub stats: BaseStats,
+    pub resistances: Resistances,
+    #[serde(default)]
+    pub movement_type: MovementType,
     pub skills: Vec<SkillId>,
 }
 
 pub const DEBUG_MONSTER_ID: MonsterId = 1;
 
-impl MonsterDefinition {
-    pub(in crate::game_data) fn mock_data() -> HashMap<MonsterId, MonsterDefinition> {
-        let mut result = HashMap::new();
+#[cfg(feature = "test_helpers")]
+pub mod test_helpers {
+    use crate::game_data::base_stats::BaseStats;
+    use crate::game_data::element::Resistances;
+    use crate::game_data::monster::{MonsterDefinition, MonsterId, DEBUG_MONSTER_ID};
+    use crate::game_data::movement_type::MovementType;
+    use crate::game_data::skill::DEBUG_SINGLE_TARGET_ATTACK_ID;
+    use bevy::utils::HashMap;
 
-        result.insert(
-            DEBUG_MONSTER_ID,
-            Self {
-                id: DEBUG_MONSTER_ID,
-                name: "Slime".into(),
-                skills: Vec::new(),
-                stats: BaseStats {
-                    hp: 5,
-                    mp: 5,
-                    movement: 3,
-                    jump: 3,
-                    strength: 5,
-                    speed: 40,
+    impl MonsterDefinition {
+        pub(in crate::game_data) fn mock_data() -> HashMap<MonsterId, MonsterDefinition> {
+            let mut result = HashMap::new();
+
+            result.insert(
+                DEBUG_MONSTER_ID,
+                Self {
+                    id: DEBUG_MONSTER_ID,
+                    name: "Slime".into(),
+                    skills: vec![DEBUG_SINGLE_TARGET_ATTACK_ID],
+                    stats: BaseStats {
+                        hp: 5,
+                        mp: 5,
+                        movement: 3,
+                        jump: 3,
+                        strength: 5,
+                        speed: 40,
+                    },
+                    resistances: Resistances {
+                        fire: -50,
+                        ..Default::default()
+                    },
+                    movement_type: MovementType::Walker,
                 },
-            },
-        );
+            );
 
-        result
+            result
+        }
     }
 }
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Decides which tiles a unit can move through and where it may end its movement.
+/// Variants are ordered by how capable they are, so units with multiple sources use the best one.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum MovementType {
+    /// Stays on solid ground and needs to jump up or down walls.
+    #[default]
+    Walker,
+    /// Like a walker, but can also cross and stay in deep fluids.
+    Swimmer,
+    /// Ignores walls, terrain costs and fluids, and may fly across the void, but can't land there.
+    Flyer,
+    /// Disappears and reappears anywhere within its movement range, as long as it can stand there.
+    Teleporter,
+}
+
+impl Display for MovementType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MovementType::Walker => write!(f, "Walker"),
+            MovementType::Swimmer => write!(f, "Swimmer"),
+            MovementType::Flyer => write!(f, "Flyer"),
+            MovementType::Teleporter => write!(f, "Teleporter"),
+        }
+    }
+}
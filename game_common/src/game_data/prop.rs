@@ -1,25 +1,91 @@
-use bevy::utils::HashMap;
+use crate::game_data::element::Resistances;
+use serde::{Deserialize, Serialize};
 
 pub type PropId = usize;
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PropDefinition {
     pub id: PropId,
     pub name: String,
+    /// Defaults for every placed instance of this prop. These can be adjusted per instance.
+    #[serde(default)]
+    pub components: PropComponents,
+}
+
+/// Everything that decides how a prop looks and interacts with units.
+/// Components which are left out in data files are disabled.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PropComponents {
+    /// Shifts the prop away from the center of its tile. Purely visual.
+    pub offset: [f32; 3],
+    /// Units can neither pass nor stand on tiles with blocking props.
+    pub blocking: bool,
+    /// Raises the surface units stand on, as well as what blocks line of sight. Stacks with other props on the same tile.
+    pub extra_height: u8,
+    pub destructible: Option<Destructible>,
+    pub interactable: Option<Interaction>,
+}
+
+/// Props with this component can be damaged by skills and will be removed once their hp reach zero.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Destructible {
+    pub hp: u32,
+    #[serde(default)]
+    pub resistances: Resistances,
+}
+
+/// What happens when a unit interacts with the prop.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Interaction {
+    /// Swaps the prop for another one, e.g. to open or close a door.
+    ReplaceWith(PropId),
 }
 
 pub const DEBUG_PROP_CUBE: PropId = 1;
+pub const DEBUG_PROP_CRATE: PropId = 2;
+
+#[cfg(feature = "test_helpers")]
+pub mod test_helpers {
+    use crate::game_data::prop::{
+        Destructible, PropComponents, PropDefinition, PropId, DEBUG_PROP_CRATE, DEBUG_PROP_CUBE,
+    };
+    use bevy::utils::HashMap;
+
+    impl PropDefinition {
+        pub fn mock_data() -> HashMap<PropId, PropDefinition> {
+            let mut result = HashMap::new();
 
-impl PropDefinition {
-    pub fn mock_data() -> HashMap<PropId, PropDefinition> {
-        let mut result = HashMap::new();
+            result.insert(
+                DEBUG_PROP_CUBE,
+                PropDefinition {
+                    id: DEBUG_PROP_CUBE,
+                    name: "Cube".to_string(),
+                    components: PropComponents {
+                        blocking: true,
+                        extra_height: 2,
+                        ..Default::default()
+                    },
+                },
+            );
 
-        result.insert(
-            DEBUG_PROP_CUBE,
-            PropDefinition {
-                id: DEBUG_PROP_CUBE,
-                name: "Cube".to_string(),
-            },
-        );
+            result.insert(
+                DEBUG_PROP_CRATE,
+                PropDefinition {
+                    id: DEBUG_PROP_CRATE,
+                    name: "Crate".to_string(),
+                    components: PropComponents {
+                        extra_height: 1,
+                        destructible: Some(Destructible {
+                            hp: 5,
+                            resistances: Default::default(),
+                        }),
+                        ..Default::default()
+                    },
+                },
+            );
 
-        result
+            result
+        }
     }
 }
@@ -1,39 +1,56 @@
 use crate::game_data::base_stats::BaseStats;
+use crate::game_data::element::Resistances;
+use crate::game_data::movement_type::MovementType;
 use crate::game_data::skill::SkillId;
-use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
 
 pub type RaceId = usize;
 
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RaceDefinition {
     pub id: RaceId,
     pub name: String,
     pub base_stats: BaseStats,
+    pub resistances: Resistances,
+    #[serde(default)]
+    pub movement_type: MovementType,
     pub extra_skills: Vec<SkillId>,
 }
 
 pub const DEBUG_RACE_ID: RaceId = 1;
 
-impl RaceDefinition {
-    pub(in crate::game_data) fn mock_data() -> HashMap<RaceId, RaceDefinition> {
-        let mut result = HashMap::new();
+#[cfg(feature = "test_helpers")]
+pub mod test_helpers {
+    use crate::game_data::base_stats::BaseStats;
+    use crate::game_data::element::Resistances;
+    use crate::game_data::movement_type::MovementType;
+    use crate::game_data::race::{RaceDefinition, RaceId, DEBUG_RACE_ID};
+    use bevy::utils::HashMap;
 
-        result.insert(
-            DEBUG_RACE_ID,
-            Self {
-                id: DEBUG_RACE_ID,
-                name: "Hooman".into(),
-                extra_skills: Vec::new(),
-                base_stats: BaseStats {
-                    hp: 10,
-                    mp: 10,
-                    movement: 3,
-                    jump: 3,
-                    strength: 5,
-                    speed: 50,
+    impl RaceDefinition {
+        pub(in crate::game_data) fn mock_data() -> HashMap<RaceId, RaceDefinition> {
+            let mut result = HashMap::new();
+
+            result.insert(
+                DEBUG_RACE_ID,
+                Self {
+                    id: DEBUG_RACE_ID,
+                    name: "Hooman".into(),
+                    extra_skills: Vec::new(),
+                    base_stats: BaseStats {
+                        hp: 10,
+                        mp: 10,
+                        movement: 3,
+                        jump: 3,
+                        strength: 5,
+                        speed: 50,
+                    },
+                    resistances: Resistances::default(),
+                    movement_type: MovementType::Walker,
                 },
-            },
-        );
+            );
 
-        result
+            result
+        }
     }
 }
@@ -0,0 +1,93 @@
+use crate::game_data::skill::SkillId;
+use serde::{Deserialize, Serialize};
+
+pub type ReactionId = u32;
+
+/// A skill which is used automatically whenever its trigger condition is met.
+/// Every reaction can only be used once, until it recharges at the end of its owner's turn.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReactionDefinition {
+    pub id: ReactionId,
+    pub name: String,
+    pub description: String,
+    pub trigger: ReactionTrigger,
+    pub skill: SkillId,
+    pub target: ReactionTarget,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ReactionTrigger {
+    /// An enemy hit the owner with physical damage.
+    PhysicalDamageTaken,
+    /// An enemy hit the owner with magical damage.
+    MagicalDamageTaken,
+    /// The owner received any kind of damage.
+    DamageTaken,
+    /// An enemy unit moved out of the owner's skill range.
+    EnemyLeftRange,
+}
+
+/// Where the reaction's skill is aimed at.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ReactionTarget {
+    /// The unit which caused the reaction to trigger.
+    TriggeringUnit,
+    /// The owner of the reaction.
+    User,
+}
+
+pub const DEBUG_RETALIATE_ID: ReactionId = 1;
+pub const DEBUG_COUNTERSPELL_ID: ReactionId = 2;
+pub const DEBUG_OPPORTUNITY_ATTACK_ID: ReactionId = 3;
+
+#[cfg(feature = "test_helpers")]
+pub mod test_helpers {
+    use crate::game_data::reaction::{
+        ReactionDefinition, ReactionId, ReactionTarget, ReactionTrigger, DEBUG_COUNTERSPELL_ID,
+        DEBUG_OPPORTUNITY_ATTACK_ID, DEBUG_RETALIATE_ID,
+    };
+    use crate::game_data::skill::{DEBUG_AOE_TARGET_ATTACK_ID, DEBUG_SINGLE_TARGET_ATTACK_ID};
+    use bevy::utils::HashMap;
+
+    impl ReactionDefinition {
+        pub(in crate::game_data) fn mock_data() -> HashMap<ReactionId, ReactionDefinition> {
+            let mut result = HashMap::new();
+
+            result.insert(
+                DEBUG_RETALIATE_ID,
+                Self {
+                    id: DEBUG_RETALIATE_ID,
+                    name: "Retaliate".into(),
+                    description: "Strike back when hit.".into(),
+                    trigger: ReactionTrigger::PhysicalDamageTaken,
+                    skill: DEBUG_SINGLE_TARGET_ATTACK_ID,
+                    target: ReactionTarget::TriggeringUnit,
+                },
+            );
+            result.insert(
+                DEBUG_COUNTERSPELL_ID,
+                Self {
+                    id: DEBUG_COUNTERSPELL_ID,
+                    name: "Counterspell".into(),
+                    description: "Answer spells with spells.".into(),
+                    trigger: ReactionTrigger::MagicalDamageTaken,
+                    skill: DEBUG_AOE_TARGET_ATTACK_ID,
+                    target: ReactionTarget::TriggeringUnit,
+                },
+            );
+            result.insert(
+                DEBUG_OPPORTUNITY_ATTACK_ID,
+                Self {
+                    id: DEBUG_OPPORTUNITY_ATTACK_ID,
+                    name: "Opportunity Attack".into(),
+                    description: "Punish enemies who try to run away.".into(),
+                    trigger: ReactionTrigger::EnemyLeftRange,
+                    skill: DEBUG_SINGLE_TARGET_ATTACK_ID,
+                    target: ReactionTarget::TriggeringUnit,
+                },
+            );
+
+            result
+        }
+    }
+}
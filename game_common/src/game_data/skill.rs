@@ -1,5 +1,10 @@
+use crate::combat_data::CombatData;
 use crate::combat_unit::{CombatUnit, UnitId};
-use crate::game_map::GameMap;
+use crate::game_data::element::Element;
+use crate::game_map::{Fluid, GameMap, TileData, TileSurface, MAX_HEIGHT};
+use crate::positioning;
+use crate::skill_effect::{SkillEffect, SkillInvocationResult};
+use crate::status_effect::StatusEffect;
 use bevy::utils::HashMap;
 use hexx::Hex;
 use serde::{Deserialize, Serialize};
@@ -10,27 +15,176 @@ pub const DEBUG_SINGLE_TARGET_ATTACK_ID: SkillId = 1;
 pub const DEBUG_AOE_TARGET_ATTACK_ID: SkillId = 2;
 pub const DEBUG_AOE_T_SHAPED: SkillId = 3;
 
-///
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillDefinition {
     pub id: SkillId,
     pub name: String,
-    pub base_power: u32,
     pub mp_costs: u32,
     pub targeting: SkillTargeting,
     pub shape: SkillShape,
+    /// Elemental damage is affected by resistances and fluids. Skills without an element deal neutral damage.
+    pub element: Option<Element>,
+    /// Applied in order to every unit and tile within the skill's shape.
+    pub effects: Vec<SkillEffectDefinition>,
 }
 
 impl SkillDefinition {
-    pub fn calculate_damage(
+    /// Figures out everything that would happen if `user` used this skill at `cursor_position`.
+    /// Nothing is modified yet, use [crate::skill_effect::apply_skill_effects] on the result for that.
+    #[must_use]
+    pub fn invoke(
         &self,
         user: &CombatUnit,
-        target: &CombatUnit,
+        cursor_position: Hex,
+        combat_data: &CombatData,
+        map: &GameMap,
     ) -> SkillInvocationResult {
-        SkillInvocationResult {
-            physical_damage: user.stats_after_buffs.strength + self.base_power,
-            target_unit_id: target.id,
+        let mut target_hexagons =
+            self.get_valid_target_hexagons(cursor_position, user.position, map);
+        target_hexagons.sort_by_key(|hex| (user.position.unsigned_distance_to(*hex), hex.x, hex.y));
+        target_hexagons.dedup();
+
+        let targets: Vec<&CombatUnit> = target_hexagons
+            .iter()
+            .filter_map(|hex| combat_data.unit_positions.get(hex))
+            .map(|unit_id| &combat_data.units[unit_id])
+            .filter(|unit| !unit.is_dead())
+            .collect();
+
+        // Units might be moved around by previous effects
+        let mut unit_positions = combat_data.unit_positions.clone();
+
+        let mut effects = Vec::new();
+        for target in targets {
+            for effect in &self.effects {
+                match effect {
+                    SkillEffectDefinition::PhysicalDamage { power } => {
+                        effects.push(SkillEffect::PhysicalDamage {
+                            target: target.id,
+                            amount: self.calculate_damage(
+                                user.stats_after_buffs.strength + power,
+                                user,
+                                target,
+                                map,
+                            ),
+                        });
+                    }
+                    SkillEffectDefinition::MagicalDamage { power } => {
+                        effects.push(SkillEffect::MagicalDamage {
+                            target: target.id,
+                            amount: self.calculate_damage(*power, user, target, map),
+                        });
+                    }
+                    SkillEffectDefinition::Heal { power } => {
+                        effects.push(SkillEffect::Heal {
+                            target: target.id,
+                            amount: *power,
+                        });
+                    }
+                    SkillEffectDefinition::DrainMp { power } => {
+                        effects.push(SkillEffect::DrainMp {
+                            user: user.id,
+                            target: target.id,
+                            amount: (*power).min(target.mp),
+                        });
+                    }
+                    SkillEffectDefinition::Push { distance } => {
+                        if let Some(path) = calculate_push_path(
+                            user.position,
+                            target,
+                            *distance,
+                            true,
+                            &unit_positions,
+                            map,
+                        ) {
+                            unit_positions.remove(&path[0]);
+                            unit_positions.insert(*path.last().unwrap(), target.id);
+                            effects.push(SkillEffect::Move {
+                                target: target.id,
+                                path,
+                            });
+                        }
+                    }
+                    SkillEffectDefinition::Pull { distance } => {
+                        if let Some(path) = calculate_push_path(
+                            user.position,
+                            target,
+                            *distance,
+                            false,
+                            &unit_positions,
+                            map,
+                        ) {
+                            unit_positions.remove(&path[0]);
+                            unit_positions.insert(*path.last().unwrap(), target.id);
+                            effects.push(SkillEffect::Move {
+                                target: target.id,
+                                path,
+                            });
+                        }
+                    }
+                    SkillEffectDefinition::ApplyStatus(status) => {
+                        effects.push(SkillEffect::ApplyStatus {
+                            target: target.id,
+                            status: status.clone(),
+                        });
+                    }
+                    SkillEffectDefinition::ChangeTerrain(_) => {}
+                }
+            }
+        }
+
+        for hex in &target_hexagons {
+            let Some(original_tile) = map.tiles.get(hex) else {
+                continue;
+            };
+
+            // Props don't have a facing or stats, so they only care about raw damage and their resistances
+            let mut tile = original_tile.clone();
+            for effect in &self.effects {
+                match effect {
+                    SkillEffectDefinition::PhysicalDamage { power } => {
+                        tile.damage_props(self.element, user.stats_after_buffs.strength + power);
+                    }
+                    SkillEffectDefinition::MagicalDamage { power } => {
+                        tile.damage_props(self.element, *power);
+                    }
+                    SkillEffectDefinition::ChangeTerrain(change) => tile = change.apply(&tile),
+                    _ => {}
+                }
+            }
+
+            if &tile != original_tile {
+                effects.push(SkillEffect::ChangeTerrain { hex: *hex, tile });
+            }
         }
+
+        SkillInvocationResult { effects }
+    }
+
+    /// Applies positional and elemental modifiers to `amount`.
+    /// Height, attack direction and the fluids on the user's and the target's tile stack,
+    /// and the target's resistances are applied last.
+    #[must_use]
+    pub fn calculate_damage(
+        &self,
+        amount: u32,
+        user: &CombatUnit,
+        target: &CombatUnit,
+        map: &GameMap,
+    ) -> u32 {
+        let positional_modifier = positioning::positional_damage_modifier(user, target, map);
+        let Some(element) = self.element else {
+            return amount * (100 + positional_modifier).max(0) as u32 / 100;
+        };
+
+        let fluid_modifier: i32 = [user.position, target.position]
+            .iter()
+            .filter_map(|hex| map.tiles.get(hex)?.fluid.as_ref())
+            .map(|fluid| fluid.kind.damage_modifier(element))
+            .sum();
+        let amount = amount * (100 + positional_modifier + fluid_modifier).max(0) as u32 / 100;
+
+        target.resistances.apply(element, amount)
     }
 
     pub fn get_valid_target_hexagons(
@@ -81,96 +235,231 @@ impl SkillDefinition {
 
         tile.height > 0
     }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SkillRange {
+    pub min: u32,
+    pub max: u32,
+    /// How far above or below the user's tile the targeted tile may be. Unlimited if left out.
+    #[serde(default)]
+    pub max_height_difference: Option<u8>,
+    /// Whether tiles rising between the user and the target block the skill.
+    #[serde(default)]
+    pub requires_line_of_sight: bool,
+}
 
-    pub(in crate::game_data) fn mock_data() -> HashMap<SkillId, SkillDefinition> {
-        let mut result = HashMap::new();
+/// A single building block describing what a skill does.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SkillEffectDefinition {
+    /// Damage which scales with the user's strength.
+    PhysicalDamage {
+        power: u32,
+    },
+    MagicalDamage {
+        power: u32,
+    },
+    Heal {
+        power: u32,
+    },
+    /// Moves MP from the target to the user.
+    DrainMp {
+        power: u32,
+    },
+    /// Pushes targets away from the user, until they are blocked by a unit, wall or void tile.
+    Push {
+        distance: u32,
+    },
+    /// Pulls targets towards the user, until they are blocked by a unit, wall or void tile.
+    Pull {
+        distance: u32,
+    },
+    ApplyStatus(StatusEffect),
+    ChangeTerrain(TerrainChange),
+}
+
+/// Modifies every tile within a skill's shape.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TerrainChange {
+    Raise(u8),
+    /// Tiles will never be lowered into the void by skills.
+    Lower(u8),
+    SetSurface(TileSurface),
+    SetFluid(Option<Fluid>),
+}
 
-        result.insert(
-            DEBUG_SINGLE_TARGET_ATTACK_ID,
-            Self::debug_attack_single_target(),
-        );
-        result.insert(DEBUG_AOE_T_SHAPED, Self::debug_attack_t_shaped());
-        result.insert(DEBUG_AOE_TARGET_ATTACK_ID, Self::debug_attack_aoe());
+impl TerrainChange {
+    #[must_use]
+    pub fn apply(&self, tile: &TileData) -> TileData {
+        let mut result = tile.clone();
+        match self {
+            TerrainChange::Raise(amount) => {
+                result.height = tile.height.saturating_add(*amount).min(MAX_HEIGHT);
+            }
+            TerrainChange::Lower(amount) => {
+                result.height = tile.height.saturating_sub(*amount).max(1);
+            }
+            TerrainChange::SetSurface(surface) => result.surface = *surface,
+            TerrainChange::SetFluid(fluid) => result.fluid = fluid.clone(),
+        }
 
         result
     }
+}
 
-    fn debug_attack_single_target() -> SkillDefinition {
-        SkillDefinition {
-            id: DEBUG_SINGLE_TARGET_ATTACK_ID,
-            name: "Slash".into(),
-            base_power: 5,
-            mp_costs: 0,
-            targeting: SkillTargeting::MouseCursor(SkillRange { min: 1, max: 1 }),
-            shape: SkillShape::SingleTile,
-        }
+/// Computes the path along which `target` would be pushed away from (or pulled towards) `origin`.
+/// Returns `None` if the target can't be moved at all.
+fn calculate_push_path(
+    origin: Hex,
+    target: &CombatUnit,
+    distance: u32,
+    away_from_origin: bool,
+    unit_positions: &HashMap<Hex, UnitId>,
+    map: &GameMap,
+) -> Option<Vec<Hex>> {
+    if origin == target.position {
+        return None;
     }
 
-    fn debug_attack_aoe() -> SkillDefinition {
-        SkillDefinition {
-            id: DEBUG_AOE_TARGET_ATTACK_ID,
-            name: "Fireball".into(),
-            base_power: 5,
-            mp_costs: 0,
-            targeting: SkillTargeting::MouseCursor(SkillRange { min: 2, max: 5 }),
-            shape: SkillShape::Circle(CircleShapeData { radius: 1 }),
+    let direction = if away_from_origin {
+        origin.main_direction_to(target.position)
+    } else {
+        target.position.main_direction_to(origin)
+    };
+
+    let mut path = vec![target.position];
+    for _ in 0..distance {
+        let current = *path.last().unwrap();
+        let next = current.neighbor(direction);
+        if next == origin || unit_positions.contains_key(&next) {
+            break;
         }
-    }
 
-    fn debug_attack_t_shaped() -> SkillDefinition {
-        SkillDefinition {
-            id: DEBUG_AOE_T_SHAPED,
-            name: "Stinky Breath".into(),
-            base_power: 5,
-            mp_costs: 0,
-            targeting: SkillTargeting::UserPosition,
-            shape: SkillShape::Custom(CustomShapeData {
-                can_rotate: true,
-                tiles: vec![
-                    Hex::new(1, 0),
-                    Hex::new(2, 0),
-                    Hex::new(3, 0),
-                    Hex::new(2, 1),
-                    Hex::new(3, -1),
-                ],
-            }),
+        let Some(next_tile) = map.tiles.get(&next) else {
+            break;
+        };
+
+        // Units can't be pushed into the void, up a wall or into blocking props
+        if next_tile.height == 0
+            || next_tile.is_blocked_by_props()
+            || next_tile.surface_height() > map.tiles[&current].surface_height()
+        {
+            break;
         }
-    }
-}
 
-#[derive(Debug, Clone, Eq, PartialEq)]
-pub struct SkillRange {
-    pub min: u32,
-    pub max: u32,
-}
+        path.push(next);
+    }
 
-#[derive(Default, Serialize, Deserialize, PartialEq, Debug)]
-pub struct SkillInvocationResult {
-    pub target_unit_id: UnitId,
-    pub physical_damage: u32,
+    if path.len() > 1 {
+        Some(path)
+    } else {
+        None
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SkillShape {
     SingleTile,
     Circle(CircleShapeData),
     Custom(CustomShapeData),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CircleShapeData {
     pub radius: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomShapeData {
     pub tiles: Vec<Hex>,
     /// Whether the shape will be rotated to "face" the cursor position.
     pub can_rotate: bool,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum SkillTargeting {
     UserPosition,
     MouseCursor(SkillRange),
 }
+
+#[cfg(feature = "test_helpers")]
+pub mod test_helpers {
+    use crate::game_data::element::Element;
+    use crate::game_data::skill::{
+        CircleShapeData, CustomShapeData, SkillDefinition, SkillEffectDefinition, SkillId,
+        SkillRange, SkillShape, SkillTargeting, DEBUG_AOE_TARGET_ATTACK_ID, DEBUG_AOE_T_SHAPED,
+        DEBUG_SINGLE_TARGET_ATTACK_ID,
+    };
+    use bevy::utils::HashMap;
+    use hexx::Hex;
+
+    impl SkillDefinition {
+        pub(in crate::game_data) fn mock_data() -> HashMap<SkillId, SkillDefinition> {
+            let mut result = HashMap::new();
+
+            result.insert(
+                DEBUG_SINGLE_TARGET_ATTACK_ID,
+                Self::debug_attack_single_target(),
+            );
+            result.insert(DEBUG_AOE_T_SHAPED, Self::debug_attack_t_shaped());
+            result.insert(DEBUG_AOE_TARGET_ATTACK_ID, Self::debug_attack_aoe());
+
+            result
+        }
+
+        fn debug_attack_single_target() -> SkillDefinition {
+            SkillDefinition {
+                id: DEBUG_SINGLE_TARGET_ATTACK_ID,
+                name: "Slash".into(),
+                mp_costs: 0,
+                targeting: SkillTargeting::MouseCursor(SkillRange {
+                    min: 1,
+                    max: 1,
+                    max_height_difference: Some(2),
+                    requires_line_of_sight: false,
+                }),
+                shape: SkillShape::SingleTile,
+                element: None,
+                effects: vec![SkillEffectDefinition::PhysicalDamage { power: 5 }],
+            }
+        }
+
+        fn debug_attack_aoe() -> SkillDefinition {
+            SkillDefinition {
+                id: DEBUG_AOE_TARGET_ATTACK_ID,
+                name: "Fireball".into(),
+                mp_costs: 0,
+                targeting: SkillTargeting::MouseCursor(SkillRange {
+                    min: 2,
+                    max: 5,
+                    max_height_difference: None,
+                    requires_line_of_sight: true,
+                }),
+                shape: SkillShape::Circle(CircleShapeData { radius: 1 }),
+                element: Some(Element::Fire),
+                effects: vec![SkillEffectDefinition::MagicalDamage { power: 7 }],
+            }
+        }
+
+        fn debug_attack_t_shaped() -> SkillDefinition {
+            SkillDefinition {
+                id: DEBUG_AOE_T_SHAPED,
+                name: "Stinky Breath".into(),
+                mp_costs: 0,
+                targeting: SkillTargeting::UserPosition,
+                shape: SkillShape::Custom(CustomShapeData {
+                    can_rotate: true,
+                    tiles: vec![
+                        Hex::new(1, 0),
+                        Hex::new(2, 0),
+                        Hex::new(3, 0),
+                        Hex::new(2, 1),
+                        Hex::new(3, -1),
+                    ],
+                }),
+                element: None,
+                effects: vec![SkillEffectDefinition::PhysicalDamage { power: 5 }],
+            }
+        }
+    }
+}
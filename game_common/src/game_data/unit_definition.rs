@@ -1,8 +1,11 @@
 use crate::combat_unit::UnitId;
 use crate::game_data::class::ClassId;
+use crate::game_data::element::Resistances;
 use crate::game_data::equipment::WeaponId;
 use crate::game_data::level::{Level, LevelUp};
+use crate::game_data::movement_type::MovementType;
 use crate::game_data::race::RaceId;
+use crate::game_data::reaction::ReactionId;
 use crate::game_data::skill::SkillId;
 use crate::game_data::{AccessoryId, ArmorId, GameData};
 use crate::player::PlayerId;
@@ -19,11 +22,13 @@ pub struct UnitDefinition {
     pub active_class: ClassId,
 
     pub race: RaceId,
+    #[serde(serialize_with = "crate::content_hash::serialize_sorted")]
     pub levels: HashMap<ClassId, Level>,
     pub permanently_unlocked_skills: Vec<SkillId>,
     pub weapon: Option<WeaponId>,
     pub armor: Option<ArmorId>,
     pub accessory: Option<AccessoryId>,
+    pub reaction: Option<ReactionId>,
 }
 
 impl PartialEq for UnitDefinition {
@@ -53,6 +58,41 @@ impl UnitDefinition {
         result.into()
     }
 
+    pub fn calculate_resistances(&self, data: &GameData) -> Resistances {
+        let mut result = data.races[&self.race].resistances;
+        if let Some(weapon) = &self.weapon {
+            result += data.weapons[weapon].resistances;
+        }
+        if let Some(armor) = &self.armor {
+            result += data.armor[armor].resistances;
+        }
+        if let Some(accessory) = &self.accessory {
+            result += data.accessories[accessory].resistances;
+        }
+
+        result
+    }
+
+    /// The best movement type granted by the unit's race or any of its equipment.
+    pub fn calculate_movement_type(&self, data: &GameData) -> MovementType {
+        let mut result = data.races[&self.race].movement_type;
+        if let Some(weapon) = &self.weapon {
+            result = result.max(data.weapons[weapon].movement_type.unwrap_or_default());
+        }
+        if let Some(armor) = &self.armor {
+            result = result.max(data.armor[armor].movement_type.unwrap_or_default());
+        }
+        if let Some(accessory) = &self.accessory {
+            result = result.max(
+                data.accessories[accessory]
+                    .movement_type
+                    .unwrap_or_default(),
+            );
+        }
+
+        result
+    }
+
     pub fn all_available_skills(&self, data: &GameData) -> Vec<SkillId> {
         let mut result = self.permanently_unlocked_skills.clone();
         if let Some(weapon) = &self.weapon {
@@ -1,15 +1,52 @@
+use crate::game_data::element::Element;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
+/// Fire damage dealt to units whenever they enter a lava tile.
+pub const LAVA_CONTACT_DAMAGE: u32 = 8;
+
+/// How many turns units stay slimed after touching slime. Effects tick at the end of the unit's turn,
+/// so this covers the rest of the current turn and the entirety of the next one.
+pub const SLIME_DURATION: u8 = 2;
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum FluidKind {
     Water,
+    Lava,
+    Slime,
+}
+
+impl FluidKind {
+    /// Additional movement needed to wade through a shallow pool of this fluid.
+    #[must_use]
+    pub fn movement_cost(&self) -> u32 {
+        match self {
+            FluidKind::Water => 1,
+            FluidKind::Lava => 1,
+            FluidKind::Slime => 2,
+        }
+    }
+
+    /// Percentage by which damage of `element` changes when the user or the target stands in this fluid.
+    #[must_use]
+    pub fn damage_modifier(&self, element: Element) -> i32 {
+        match (self, element) {
+            (FluidKind::Water, Element::Lightning) => 50,
+            (FluidKind::Water, Element::Fire) => -50,
+            (FluidKind::Lava, Element::Fire) => 50,
+            (FluidKind::Lava, Element::Ice) => -50,
+            (FluidKind::Slime, Element::Fire) => 25,
+            _ => 0,
+        }
+    }
 }
 
 impl Display for FluidKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             FluidKind::Water => write!(f, "Water"),
+            FluidKind::Lava => write!(f, "Lava"),
+            FluidKind::Slime => write!(f, "Slime"),
         }
     }
 }
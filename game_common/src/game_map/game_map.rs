@@ -1,9 +1,16 @@
 use crate::combat_data::CombatData;
 use crate::combat_unit::CombatUnit;
+use crate::game_data::element::Element;
+use crate::game_data::movement_type::MovementType;
 use crate::game_map::field_of_movement_with_edge_detection::field_of_movement_with_edge_detection;
+use crate::game_map::fluid_kind::{FluidKind, LAVA_CONTACT_DAMAGE, SLIME_DURATION};
+use crate::game_map::map_format::MapFormat;
+use crate::game_map::map_metadata::MapMetadata;
 use crate::game_map::tile_data::TileData;
 use crate::game_map::tile_surface::TileSurface;
 use crate::game_map::versioned_map_data::VersionedMapData;
+use crate::game_map::HEIGHT_PER_CLIMBING_COST;
+use crate::status_effect::{StatusEffect, StatusEffectKind};
 use bevy::prelude::Resource;
 use bevy::utils::hashbrown::HashMap;
 use hexx::Hex;
@@ -11,6 +18,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Resource, Serialize, Deserialize, Clone, PartialEq)]
 pub struct GameMap {
+    pub metadata: MapMetadata,
     pub radius: u32,
     pub tiles: HashMap<Hex, TileData>,
 }
@@ -20,29 +28,56 @@ impl GameMap {
     pub fn new(radius: u32) -> Self {
         let mut tiles = HashMap::new();
         for hex in hexx::shapes::hexagon(Hex::ORIGIN, radius) {
-            tiles.insert(
-                hex,
-                TileData {
-                    height: 1,
-                    surface: TileSurface::Grass,
-                    fluid: None,
-                    spawn_zone: None,
-                },
-            );
+            tiles.insert(hex, Self::default_tile());
         }
 
-        GameMap { radius, tiles }
+        GameMap {
+            metadata: MapMetadata::default(),
+            radius,
+            tiles,
+        }
     }
 
     pub fn write_to_disk(&self, path: &str) {
-        // This clone is highly suboptimal.
-        let _ = VersionedMapData::V1(self.clone()).write_to_disk(path);
+        let _ = self.write_to_disk_as(path, MapFormat::Ron);
+    }
+
+    pub fn write_to_disk_as(&self, path: &str, format: MapFormat) -> Result<(), String> {
+        VersionedMapData::write_to_disk(self, path, format)
     }
 
-    pub fn load_from_file(path: &str) -> Result<Self, ()> {
+    pub fn load_from_file(path: &str) -> Result<Self, String> {
         VersionedMapData::load_from_file(path)
     }
 
+    pub fn encode(&self, format: MapFormat) -> Result<Vec<u8>, String> {
+        VersionedMapData::encode(self, format)
+    }
+
+    /// Decodes maps of any [MapFormat] and version.
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        VersionedMapData::decode(bytes)
+    }
+
+    /// Changes the radius of the map. Tiles within the new radius are kept, new ones are filled with grass.
+    pub fn resize(&mut self, radius: u32) {
+        self.tiles.retain(|hex, _| hex.ulength() <= radius);
+        for hex in hexx::shapes::hexagon(Hex::ORIGIN, radius) {
+            self.tiles.entry(hex).or_insert_with(Self::default_tile);
+        }
+        self.radius = radius;
+    }
+
+    fn default_tile() -> TileData {
+        TileData {
+            height: 1,
+            surface: TileSurface::Grass,
+            fluid: None,
+            spawn_zone: None,
+            props: Vec::new(),
+        }
+    }
+
     #[must_use]
     pub fn field_of_movement(&self, unit: &CombatUnit, combat_data: &CombatData) -> Vec<Hex> {
         let unit_turn = combat_data.current_turn.as_unit_turn().unwrap();
@@ -52,11 +87,36 @@ impl GameMap {
             |from, to| self.calculate_path_costs(unit, &combat_data, &from, &to),
         );
 
-        // Since we can walk through our own units, remove tiles which are already occupied
-        result.retain(|x| combat_data.unit_positions.get(x).is_none());
+        // Units may pass some tiles without being able to stop there, e.g. allies or the void
+        result.retain(|x| self.can_unit_stop_on(unit, combat_data, x));
         result
     }
 
+    /// Whether `unit` may end its movement on `hex`. Passing through a tile doesn't imply being able to stop there.
+    #[must_use]
+    pub fn can_unit_stop_on(&self, unit: &CombatUnit, combat_data: &CombatData, hex: &Hex) -> bool {
+        let Some(tile) = self.tiles.get(hex) else {
+            return false;
+        };
+
+        if tile.height == 0 || tile.is_blocked_by_props() {
+            return false;
+        }
+
+        if combat_data
+            .unit_positions
+            .get(hex)
+            .is_some_and(|id| *id != unit.id)
+        {
+            return false;
+        }
+
+        match unit.movement_type {
+            MovementType::Walker | MovementType::Teleporter => !tile.has_deep_fluid(),
+            MovementType::Swimmer | MovementType::Flyer => true,
+        }
+    }
+
     #[must_use]
     pub fn calculate_path_for_active_unit(
         &self,
@@ -65,13 +125,32 @@ impl GameMap {
     ) -> Option<Vec<Hex>> {
         let unit_turn = combat_data.current_turn.as_unit_turn().unwrap();
         let unit = combat_data.units.get(&unit_turn.unit_id).unwrap();
+        if !self.can_unit_stop_on(unit, combat_data, &coordinate) {
+            return None;
+        }
+
         hexx::algorithms::a_star(unit.position, coordinate, |from, to| {
             self.calculate_path_costs(unit, &combat_data, &from, &to)
         })
     }
 
+    /// The movement `unit` needs to walk along all of `path`, or `None` if any step isn't traversable.
     #[must_use]
-    pub(crate) fn calculate_path_costs(
+    pub fn calculate_total_path_cost(
+        &self,
+        unit: &CombatUnit,
+        combat_data: &CombatData,
+        path: &[Hex],
+    ) -> Option<u32> {
+        path.windows(2)
+            .map(|step| self.calculate_path_costs(unit, combat_data, &step[0], &step[1]))
+            .sum()
+    }
+
+    /// The movement `unit` needs to step from `from` to its neighbor `to`, or `None` if it can't go there.
+    /// This is the one cost function used for movement previews, pathfinding and validation.
+    #[must_use]
+    pub fn calculate_path_costs(
         &self,
         unit: &CombatUnit,
         combat_data: &CombatData,
@@ -81,6 +160,11 @@ impl GameMap {
         let from_tile = &self.tiles[from];
         let to_tile = self.tiles.get(to)?;
 
+        // Teleporters don't care about anything between their start and destination
+        if unit.movement_type == MovementType::Teleporter {
+            return Some(1);
+        }
+
         if let Some(unit_on_tile) = combat_data.unit_positions.get(to) {
             let unit_on_tile = &combat_data.units[unit_on_tile];
             if unit_on_tile.owner != unit.owner {
@@ -88,21 +172,68 @@ impl GameMap {
             }
         }
 
+        if to_tile.is_blocked_by_props() {
+            return None;
+        }
+
+        if unit.movement_type == MovementType::Flyer {
+            return Some(1);
+        }
+
         if to_tile.height == 0 {
             return None;
         }
 
-        if let Some(fluid) = &to_tile.fluid {
-            if fluid.height > 1.0 {
-                return None;
-            }
+        let is_swimming = unit.movement_type == MovementType::Swimmer && to_tile.fluid.is_some();
+        if to_tile.has_deep_fluid() && !is_swimming {
+            return None;
         }
 
-        if from_tile.height.abs_diff(to_tile.height) > unit.stats_after_buffs.jump {
+        let from_height = from_tile.surface_height();
+        let to_height = to_tile.surface_height();
+        if from_height.abs_diff(to_height) > unit.stats_after_buffs.jump {
             return None;
         }
 
-        Some(1)
+        let mut cost = to_tile.surface.movement_cost();
+        if let Some(fluid) = to_tile.fluid.as_ref().filter(|_| !is_swimming) {
+            cost += fluid.kind.movement_cost();
+        }
+
+        // Dropping down is free, climbing up gets harder the higher the step
+        let climb = to_height.saturating_sub(from_height);
+        cost += (climb / HEIGHT_PER_CLIMBING_COST) as u32;
+
+        Some(cost)
+    }
+
+    /// Applies the effects of every fluid `unit` enters along `entered_tiles`.
+    /// Lava burns the unit, whereas slime sticks to it and slows it down.
+    /// Flyers never touch the ground, and teleporters only touch the tile they end up on.
+    pub fn apply_fluid_contact(&self, unit: &mut CombatUnit, entered_tiles: &[Hex]) {
+        let entered_tiles = match unit.movement_type {
+            MovementType::Walker | MovementType::Swimmer => entered_tiles,
+            MovementType::Flyer => &[],
+            MovementType::Teleporter => &entered_tiles[entered_tiles.len().saturating_sub(1)..],
+        };
+
+        for hex in entered_tiles {
+            let Some(fluid) = self.tiles.get(hex).and_then(|tile| tile.fluid.as_ref()) else {
+                continue;
+            };
+
+            match fluid.kind {
+                FluidKind::Water => {}
+                FluidKind::Lava => {
+                    let damage = unit.resistances.apply(Element::Fire, LAVA_CONTACT_DAMAGE);
+                    unit.hp = unit.hp.saturating_sub(damage);
+                }
+                FluidKind::Slime => unit.apply_status_effect(StatusEffect {
+                    kind: StatusEffectKind::Slimed,
+                    remaining_turns: SLIME_DURATION,
+                }),
+            }
+        }
     }
 
     #[must_use]
@@ -131,6 +262,8 @@ mod tests {
 
     use crate::combat_data::CombatData;
     use crate::combat_unit::CombatUnit;
+    use crate::game_data::movement_type::MovementType;
+    use crate::game_data::prop::{PropDefinition, DEBUG_PROP_CRATE, DEBUG_PROP_CUBE};
     use crate::game_map::game_map::GameMap;
     use hexx::Hex;
     use std::fs;
@@ -171,6 +304,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn resizing_should_keep_existing_tiles() {
+        let mut map = GameMap::new(2);
+        map.tiles.get_mut(&Hex::new(1, 0)).unwrap().height = 5;
+        map.tiles.get_mut(&Hex::new(2, 0)).unwrap().height = 5;
+
+        map.resize(1);
+        assert_eq!(1, map.radius);
+        assert_eq!(7, map.tiles.len());
+        assert_eq!(5, map.tiles[&Hex::new(1, 0)].height);
+
+        map.resize(3);
+        assert_eq!(37, map.tiles.len());
+        assert_eq!(5, map.tiles[&Hex::new(1, 0)].height);
+        assert_eq!(1, map.tiles[&Hex::new(2, 0)].height);
+    }
+
     #[test]
     fn field_of_movement() {
         let mut map = GameMap::new(3);
@@ -211,4 +361,176 @@ mod tests {
         assert_not_contains!(result, &friendly_unit_pos);
         assert_not_contains!(result, &hostile_unit_pos);
     }
+
+    fn create_combat_data_for_movement_type(
+        position: Hex,
+        movement: u8,
+        movement_type: MovementType,
+    ) -> CombatData {
+        let mut combat_data =
+            CombatData::create_mock().with_units(vec![CombatUnit::create_mock(1, 1)
+                .with_position(position)
+                .with_movement_type(movement_type)
+                .with_stats(UnitStats::create_mock().with_movement(movement))]);
+        combat_data.start_unit_turn(1);
+        combat_data
+    }
+
+    #[test]
+    fn flyers_may_pass_but_not_stop_on_void() {
+        let mut map = GameMap::new(2);
+        for tile in map.tiles.iter_mut().filter(|(hex, _)| hex.x == 0) {
+            tile.1.height = 0;
+        }
+        let start = Hex::new(-1, 0);
+        let other_side = Hex::new(1, 0);
+
+        let walker = create_combat_data_for_movement_type(start, 2, MovementType::Walker);
+        let result = map.field_of_movement(&walker.units[&1], &walker);
+        assert_not_contains!(result, &other_side);
+
+        let flyer = create_combat_data_for_movement_type(start, 2, MovementType::Flyer);
+        let result = map.field_of_movement(&flyer.units[&1], &flyer);
+        assert_contains!(result, &other_side);
+        assert_not_contains!(result, &Hex::ZERO, "Flyers should not stop on void");
+        assert!(map
+            .calculate_path_for_active_unit(&flyer, Hex::ZERO)
+            .is_none());
+        assert!(map
+            .calculate_path_for_active_unit(&flyer, other_side)
+            .is_some());
+    }
+
+    #[test]
+    fn swimmers_may_enter_deep_fluids() {
+        let mut map = GameMap::new(2);
+        let lake = Hex::new(1, 0);
+        map.tiles.get_mut(&lake).unwrap().fluid = Some(Fluid {
+            kind: FluidKind::Water,
+            height: 2.0,
+        });
+
+        let walker = create_combat_data_for_movement_type(Hex::ZERO, 1, MovementType::Walker);
+        let result = map.field_of_movement(&walker.units[&1], &walker);
+        assert_not_contains!(result, &lake);
+
+        let swimmer = create_combat_data_for_movement_type(Hex::ZERO, 1, MovementType::Swimmer);
+        let result = map.field_of_movement(&swimmer.units[&1], &swimmer);
+        assert_contains!(result, &lake);
+    }
+
+    #[test]
+    fn teleporters_should_ignore_everything_in_between() {
+        let mut map = GameMap::new(3);
+        for hex in Hex::ZERO.all_neighbors() {
+            map.tiles.get_mut(&hex).unwrap().height = 10;
+        }
+        let lava = Hex::new(2, 0);
+        map.tiles.get_mut(&lava).unwrap().fluid = Some(Fluid {
+            kind: FluidKind::Lava,
+            height: 0.5,
+        });
+
+        let walker = create_combat_data_for_movement_type(Hex::ZERO, 2, MovementType::Walker);
+        assert!(map.field_of_movement(&walker.units[&1], &walker).is_empty());
+
+        let teleporter =
+            create_combat_data_for_movement_type(Hex::ZERO, 2, MovementType::Teleporter);
+        let result = map.field_of_movement(&teleporter.units[&1], &teleporter);
+        assert_eq!(18, result.len());
+        assert_contains!(result, &lava);
+
+        let mut unit = teleporter.units[&1].clone();
+        map.apply_fluid_contact(&mut unit, &[Hex::new(1, 0), Hex::new(2, -1)]);
+        assert_eq!(10, unit.hp, "Only the destination should be touched");
+        map.apply_fluid_contact(&mut unit, &[Hex::new(1, 0), lava]);
+        assert_eq!(10 - LAVA_CONTACT_DAMAGE, unit.hp);
+    }
+
+    #[test]
+    fn movement_costs_should_depend_on_terrain() {
+        let mut map = GameMap::new(3);
+        let sand = Hex::new(1, 0);
+        let water = Hex::new(2, 0);
+        let cliff = Hex::new(-1, 0);
+        map.tiles.get_mut(&sand).unwrap().surface = TileSurface::Sand;
+        map.tiles.get_mut(&water).unwrap().fluid = Some(Fluid {
+            kind: FluidKind::Water,
+            height: 0.5,
+        });
+        map.tiles.get_mut(&cliff).unwrap().height = 5;
+
+        let mut combat_data = CombatData::create_mock()
+            .with_units(vec![CombatUnit::create_mock(1, 1)
+                .with_stats(UnitStats::create_mock().with_movement(3).with_jump(4))]);
+        combat_data.start_unit_turn(1);
+        let unit = &combat_data.units[&1];
+
+        assert_eq!(
+            Some(1),
+            map.calculate_path_costs(unit, &combat_data, &Hex::ZERO, &Hex::new(0, 1))
+        );
+        assert_eq!(
+            Some(2),
+            map.calculate_path_costs(unit, &combat_data, &Hex::ZERO, &sand)
+        );
+        assert_eq!(
+            Some(2),
+            map.calculate_path_costs(unit, &combat_data, &sand, &water)
+        );
+        assert_eq!(
+            Some(3),
+            map.calculate_path_costs(unit, &combat_data, &Hex::ZERO, &cliff),
+            "Climbing 4 levels should cost 2 extra movement"
+        );
+        assert_eq!(
+            Some(1),
+            map.calculate_path_costs(unit, &combat_data, &cliff, &Hex::ZERO),
+            "Dropping down should be free"
+        );
+        assert_eq!(
+            Some(4),
+            map.calculate_total_path_cost(unit, &combat_data, &[Hex::ZERO, sand, water])
+        );
+
+        let result = map.field_of_movement(unit, &combat_data);
+        assert_contains!(result, &sand);
+        assert_contains!(result, &cliff);
+        assert_not_contains!(result, &water, "Water behind sand should be out of reach");
+    }
+
+    #[test]
+    fn props_should_block_tiles_and_raise_their_surface() {
+        let props = PropDefinition::mock_data();
+        let mut map = GameMap::new(2);
+        let blocked = Hex::new(1, 0);
+        let raised = Hex::new(-1, 0);
+        map.tiles
+            .get_mut(&blocked)
+            .unwrap()
+            .props
+            .push(PropInstance::from_definition(&props[&DEBUG_PROP_CUBE]));
+        map.tiles
+            .get_mut(&raised)
+            .unwrap()
+            .props
+            .push(PropInstance::from_definition(&props[&DEBUG_PROP_CRATE]));
+        assert_eq!(2, map.tiles[&raised].surface_height());
+
+        let walker = create_combat_data_for_movement_type(Hex::ZERO, 2, MovementType::Walker);
+        let result = map.field_of_movement(&walker.units[&1], &walker);
+        assert_not_contains!(result, &blocked);
+        assert_contains!(result, &raised);
+        assert_eq!(
+            None,
+            map.calculate_path_costs(&walker.units[&1], &walker, &Hex::ZERO, &blocked)
+        );
+
+        let flyer = create_combat_data_for_movement_type(Hex::ZERO, 2, MovementType::Flyer);
+        assert_eq!(
+            None,
+            map.calculate_path_costs(&flyer.units[&1], &flyer, &Hex::ZERO, &blocked),
+            "Flyers should not be able to pass blocking props either"
+        );
+    }
 }
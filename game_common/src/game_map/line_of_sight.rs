@@ -0,0 +1,70 @@
+use crate::game_map::GameMap;
+use hexx::Hex;
+
+/// How many height levels above its tile a unit can see from, and be seen at.
+pub const EYE_HEIGHT: u8 = 2;
+
+impl GameMap {
+    /// Whether a unit standing on `from` can see a unit standing on `to`.
+    /// The sight line runs from eye height to eye height, and is blocked by any tile or prop in between which rises above it.
+    #[must_use]
+    pub fn has_line_of_sight(&self, from: Hex, to: Hex) -> bool {
+        let Some(from_tile) = self.tiles.get(&from) else {
+            return false;
+        };
+        let Some(to_tile) = self.tiles.get(&to) else {
+            return false;
+        };
+
+        let start = from_tile.surface_height() as i64 + EYE_HEIGHT as i64;
+        let end = to_tile.surface_height() as i64 + EYE_HEIGHT as i64;
+        let steps = from.unsigned_distance_to(to) as i64;
+
+        // Integer math keeps this deterministic for client and server
+        from.line_to(to).enumerate().all(|(index, hex)| {
+            let index = index as i64;
+            if index == 0 || index == steps {
+                return true;
+            }
+
+            let Some(tile) = self.tiles.get(&hex) else {
+                return true;
+            };
+            let sight_height = start * (steps - index) + end * index;
+            tile.surface_height() as i64 * steps <= sight_height
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_map::GameMap;
+    use hexx::Hex;
+
+    #[test]
+    fn walls_should_block_line_of_sight() {
+        let mut map = GameMap::new(4);
+        let from = Hex::new(-2, 0);
+        let to = Hex::new(2, 0);
+        assert!(map.has_line_of_sight(from, to));
+
+        map.tiles.get_mut(&Hex::ZERO).unwrap().height = 4;
+        assert!(!map.has_line_of_sight(from, to));
+        assert!(!map.has_line_of_sight(to, from));
+
+        map.tiles.get_mut(&from).unwrap().height = 5;
+        assert!(
+            map.has_line_of_sight(from, to),
+            "Units should be able to look over walls from above"
+        );
+    }
+
+    #[test]
+    fn adjacent_tiles_should_always_be_visible() {
+        let mut map = GameMap::new(2);
+        map.tiles.get_mut(&Hex::new(1, 0)).unwrap().height = 20;
+
+        assert!(map.has_line_of_sight(Hex::ZERO, Hex::new(1, 0)));
+        assert!(map.has_line_of_sight(Hex::ZERO, Hex::ZERO));
+    }
+}
@@ -0,0 +1,57 @@
+/// Every binary map file starts with these bytes. RON files start with the name of a version instead.
+pub const BINARY_MAP_MAGIC: &[u8; 4] = b"HXM\0";
+
+/// How a map is encoded. Loading detects the format on its own, so both can be used interchangeably.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum MapFormat {
+    /// Human readable, useful for maps which are checked into version control.
+    #[default]
+    Ron,
+    /// Compressed bincode. Much smaller and faster to read and write, especially for bigger maps.
+    Binary,
+}
+
+impl MapFormat {
+    #[must_use]
+    pub fn detect(bytes: &[u8]) -> MapFormat {
+        if bytes.starts_with(BINARY_MAP_MAGIC) {
+            MapFormat::Binary
+        } else {
+            MapFormat::Ron
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_map::{Fluid, FluidKind, GameMap, MapFormat, TileSurface};
+
+    #[test]
+    fn binary_maps_should_be_much_smaller_than_ron() {
+        let mut map = GameMap::new(30);
+        for (hex, tile) in map.tiles.iter_mut() {
+            tile.height = (hex.x * 7 + hex.y * 13).rem_euclid(6) as u8 + 1;
+            tile.surface = match (hex.x - hex.y).rem_euclid(4) {
+                0 => TileSurface::Grass,
+                1 => TileSurface::Stone,
+                2 => TileSurface::Sand,
+                _ => TileSurface::Earth,
+            };
+            if hex.length() % 9 == 0 {
+                tile.fluid = Some(Fluid {
+                    height: 0.5,
+                    kind: FluidKind::Water,
+                });
+            }
+        }
+
+        let ron = map.encode(MapFormat::Ron).unwrap();
+        let binary = map.encode(MapFormat::Binary).unwrap();
+        assert!(
+            binary.len() * 10 < ron.len(),
+            "Binary: {} bytes, RON: {} bytes",
+            binary.len(),
+            ron.len()
+        );
+    }
+}
@@ -0,0 +1,526 @@
+use crate::game_map::fluid::Fluid;
+use crate::game_map::fluid_kind::FluidKind;
+use crate::game_map::game_map::GameMap;
+use crate::game_map::map_metadata::MapMetadata;
+use crate::game_map::tile_data::TileData;
+use crate::game_map::tile_surface::TileSurface;
+use crate::game_map::{HEX_LAYOUT, MAX_HEIGHT};
+use bevy::math::Vec2;
+use bevy::utils::{HashMap, HashSet};
+use hexx::Hex;
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+
+/// The lowest jump stat units usually have. Generated maps stay traversable for units with at least this much jump.
+pub const DEFAULT_JUMP: u8 = 3;
+
+/// Shallow water only slows units down, deep water can't be entered without swimming or flying.
+const SHALLOW_WATER_HEIGHT: f32 = 0.5;
+const DEEP_WATER_HEIGHT: f32 = 1.5;
+
+/// How many attempts are made to connect isolated spawn zones before giving up on a seed.
+const MAX_CARVING_ITERATIONS: usize = 32;
+
+/// How many seeds are tried before giving up on generating a map with the given settings.
+const MAX_GENERATION_ATTEMPTS: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct MapGeneratorSettings {
+    pub radius: u32,
+    pub team_count: u8,
+    /// How many tiles each spawn zone consists of.
+    pub spawn_zone_size: u8,
+    pub max_height: u8,
+    /// Every spawn zone is guaranteed to be reachable from every other one with this jump stat.
+    pub jump: u8,
+    /// Hills and valleys per team.
+    pub hill_count: u32,
+    /// Bodies of water per team.
+    pub lake_count: u32,
+}
+
+impl Default for MapGeneratorSettings {
+    fn default() -> Self {
+        MapGeneratorSettings {
+            radius: 10,
+            team_count: 2,
+            spawn_zone_size: 6,
+            max_height: 8,
+            jump: DEFAULT_JUMP,
+            hill_count: 4,
+            lake_count: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapGenerationError {
+    pub seed: u64,
+    /// Teams whose spawn zone couldn't be connected during the last attempt.
+    pub unreachable_teams: Vec<u8>,
+}
+
+impl std::fmt::Display for MapGenerationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unable to generate a map for seed {:016x} after {} attempts, spawn zones {:?} stayed unreachable.",
+            self.seed, MAX_GENERATION_ATTEMPTS, self.unreachable_teams
+        )
+    }
+}
+
+/// A circular area which shapes the terrain around its center.
+#[derive(Copy, Clone)]
+struct Feature {
+    center: Hex,
+    radius: u32,
+    strength: i32,
+}
+
+impl Feature {
+    fn random(rng: &mut impl Rng, map_radius: u32, max_radius: u32, strength: i32) -> Self {
+        let map_radius = map_radius as i32;
+        let x = rng.gen_range(-map_radius..=map_radius);
+        let y_min = (-map_radius).max(-x - map_radius);
+        let y_max = map_radius.min(-x + map_radius);
+        Feature {
+            center: Hex::new(x, rng.gen_range(y_min..=y_max)),
+            radius: rng.gen_range(1..=max_radius.max(1)),
+            strength,
+        }
+    }
+
+    /// How much of the feature's strength applies to `hex`, ranging from 1.0 at its center to 0.0 at its edge.
+    fn falloff(&self, hex: Hex) -> f32 {
+        let distance = self.center.unsigned_distance_to(hex) as f32;
+        (1.0 - distance / (self.radius as f32 + 1.0)).max(0.0)
+    }
+
+    fn rotated(&self, steps: u32) -> Self {
+        Feature {
+            center: self.center.rotate_cw(steps),
+            ..*self
+        }
+    }
+}
+
+/// Creates a random battlefield. The same seed and settings will always produce the same map.
+///
+/// Spawn zones are spread out evenly around the map's center. For team counts which evenly divide the six directions
+/// of a hexagon, the entire map is rotationally symmetric, so no team gets an advantage from the terrain.
+///
+/// Every spawn zone is guaranteed to be reachable from every other one with `settings.jump`. Seeds for which that
+/// can't be achieved are replaced with new ones derived from `seed`, the metadata contains the one actually used.
+pub fn generate_map(
+    seed: u64,
+    settings: &MapGeneratorSettings,
+) -> Result<GameMap, MapGenerationError> {
+    let mut seed_rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut attempt_seed = seed;
+    let mut unreachable_teams = Vec::new();
+    for _ in 0..MAX_GENERATION_ATTEMPTS {
+        let map = generate_map_for_seed(attempt_seed, settings);
+        unreachable_teams = find_unreachable_spawn_zones(&map, settings.jump);
+        if unreachable_teams.is_empty() {
+            return Ok(map);
+        }
+
+        attempt_seed = seed_rng.gen();
+    }
+
+    Err(MapGenerationError {
+        seed,
+        unreachable_teams,
+    })
+}
+
+fn generate_map_for_seed(seed: u64, settings: &MapGeneratorSettings) -> GameMap {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let team_count = settings.team_count.max(1);
+    let symmetric = 6 % team_count as u32 == 0;
+    let rotations: Vec<u32> = if symmetric {
+        let step = 6 / team_count as u32;
+        (0..team_count as u32).map(|team| team * step).collect()
+    } else {
+        vec![0]
+    };
+
+    let feature_count = if symmetric { 1 } else { team_count as u32 };
+    let mut hills = Vec::new();
+    for _ in 0..settings.hill_count * feature_count {
+        let strength = rng.gen_range(-2..=settings.max_height as i32);
+        let feature = Feature::random(&mut rng, settings.radius, settings.radius / 2, strength);
+        hills.extend(rotations.iter().map(|steps| feature.rotated(*steps)));
+    }
+
+    let mut lakes = Vec::new();
+    for _ in 0..settings.lake_count * feature_count {
+        let feature = Feature::random(&mut rng, settings.radius, settings.radius / 4, 0);
+        lakes.extend(rotations.iter().map(|steps| feature.rotated(*steps)));
+    }
+
+    let max_height = settings.max_height.clamp(1, MAX_HEIGHT);
+    let mut map = GameMap::new(settings.radius);
+    for (hex, tile) in map.tiles.iter_mut() {
+        let height = hills.iter().fold(2.0, |height, hill| {
+            height + hill.strength as f32 * hill.falloff(*hex)
+        });
+        tile.height = (height.round() as i32).clamp(1, max_height as i32) as u8;
+        tile.surface = if tile.height + 1 >= max_height {
+            TileSurface::Stone
+        } else if tile.height <= 1 {
+            TileSurface::Earth
+        } else {
+            TileSurface::Grass
+        };
+
+        let wetness = lakes
+            .iter()
+            .map(|lake| lake.falloff(*hex))
+            .fold(0.0, f32::max);
+        if wetness > 0.0 {
+            tile.height = 1;
+            tile.surface = TileSurface::Sand;
+            tile.fluid = Some(Fluid {
+                height: if wetness > 0.6 {
+                    DEEP_WATER_HEIGHT
+                } else {
+                    SHALLOW_WATER_HEIGHT
+                },
+                kind: FluidKind::Water,
+            });
+        }
+    }
+
+    let spawn_zones = place_spawn_zones(&mut map, settings, &rotations);
+    connect_spawn_zones(&mut map, &spawn_zones, settings.jump);
+
+    map.metadata = MapMetadata {
+        name: format!("Generated {:016x}", seed),
+        author: "Map Generator".into(),
+        team_count,
+        recommended_unit_count: settings.spawn_zone_size,
+        min_players: 1,
+        max_players: team_count,
+    };
+
+    map
+}
+
+/// Marks and flattens the spawn zone of every team. Returns the center of each zone, ordered by team.
+fn place_spawn_zones(
+    map: &mut GameMap,
+    settings: &MapGeneratorSettings,
+    rotations: &[u32],
+) -> Vec<Hex> {
+    let team_count = settings.team_count.max(1);
+    let distance = (settings.radius as f32 * 2.0 / 3.0).round();
+    let mut centers = Vec::new();
+    let mut zones: Vec<Vec<Hex>> = Vec::new();
+    for team in 0..team_count {
+        let zone: Vec<Hex> = if rotations.len() == team_count as usize && team > 0 {
+            // Rotating the first zone keeps things perfectly fair
+            zones[0]
+                .iter()
+                .map(|hex| hex.rotate_cw(rotations[team as usize]))
+                .collect()
+        } else {
+            let angle = TAU * team as f32 / team_count as f32;
+            let position = Vec2::new(angle.cos(), angle.sin())
+                * distance
+                * HEX_LAYOUT.hex_size.x
+                * 3f32.sqrt();
+            let center = HEX_LAYOUT.world_pos_to_hex(position);
+            center
+                .spiral_range(0..=settings.radius)
+                .filter(|hex| {
+                    map.tiles.contains_key(hex) && !zones.iter().any(|zone| zone.contains(hex))
+                })
+                .take(settings.spawn_zone_size as usize)
+                .collect()
+        };
+
+        centers.push(zone.first().copied().unwrap_or_default());
+        zones.push(zone);
+    }
+
+    for (team, zone) in zones.iter().enumerate() {
+        let height = zone
+            .iter()
+            .map(|hex| map.tiles[hex].height as u32)
+            .sum::<u32>()
+            / zone.len().max(1) as u32;
+        for hex in zone {
+            let tile = map.tiles.get_mut(hex).unwrap();
+            tile.height = height as u8;
+            tile.fluid = None;
+            tile.surface = TileSurface::Grass;
+            tile.spawn_zone = Some(team as u8 + 1);
+        }
+    }
+
+    centers
+}
+
+/// Carves paths towards every spawn zone which can't be reached from the first one yet.
+fn connect_spawn_zones(map: &mut GameMap, centers: &[Hex], jump: u8) {
+    for _ in 0..MAX_CARVING_ITERATIONS {
+        let Some(start) = centers.first() else {
+            return;
+        };
+
+        let reachable = reachable_hexes(map, *start, jump);
+        let Some(isolated) = map
+            .tiles
+            .iter()
+            .filter(|(hex, tile)| tile.spawn_zone.is_some() && !reachable.contains(*hex))
+            .map(|(hex, _)| *hex)
+            .min_by_key(|hex| (hex.x, hex.y))
+        else {
+            return;
+        };
+
+        let Some(path) = find_path_to_carve(map, isolated, &reachable, jump) else {
+            return;
+        };
+
+        carve_path(map, &path, jump);
+    }
+}
+
+/// The shortest path from `isolated` towards a `reachable` tile which doesn't lead through any spawn zones.
+/// Only targets whose height can be reached by sloping the tiles in between are considered.
+fn find_path_to_carve(
+    map: &GameMap,
+    isolated: Hex,
+    reachable: &HashSet<Hex>,
+    jump: u8,
+) -> Option<Vec<Hex>> {
+    let isolated_height = map.tiles[&isolated].surface_height();
+    let mut parents = HashMap::from([(isolated, isolated)]);
+    let mut queue = VecDeque::from([(isolated, 0u32)]);
+    while let Some((hex, distance)) = queue.pop_front() {
+        let tile = &map.tiles[&hex];
+        if reachable.contains(&hex)
+            && tile.surface_height().abs_diff(isolated_height) as u32 <= jump as u32 * distance
+        {
+            let mut path = vec![hex];
+            while let Some(parent) = parents.get(path.last().unwrap()).copied() {
+                if parent == *path.last().unwrap() {
+                    break;
+                }
+                path.push(parent);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        if hex != isolated && tile.spawn_zone.is_some() {
+            continue;
+        }
+
+        for neighbor in hex.all_neighbors() {
+            let Some(neighbor_tile) = map.tiles.get(&neighbor) else {
+                continue;
+            };
+
+            let is_other_spawn_zone =
+                neighbor_tile.spawn_zone.is_some() && !reachable.contains(&neighbor);
+            if is_other_spawn_zone || parents.contains_key(&neighbor) {
+                continue;
+            }
+
+            parents.insert(neighbor, hex);
+            queue.push_back((neighbor, distance + 1));
+        }
+    }
+
+    None
+}
+
+/// Levels out every step along `path`, so walkers can pass through.
+/// Both ends keep their height, the tiles in between are sloped towards them.
+fn carve_path(map: &mut GameMap, path: &[Hex], jump: u8) {
+    let (Some(first), Some(last)) = (path.first(), path.last()) else {
+        return;
+    };
+
+    let jump = jump as i32;
+    let last_index = path.len() as i32 - 1;
+    let target_height = map.tiles[last].surface_height() as i32;
+    let mut previous_height = map.tiles[first].surface_height() as i32;
+    for (index, hex) in path.iter().enumerate().take(path.len() - 1).skip(1) {
+        let tile = map.tiles.get_mut(hex).unwrap();
+        if tile.has_deep_fluid() {
+            tile.fluid = Some(Fluid {
+                height: SHALLOW_WATER_HEIGHT,
+                kind: FluidKind::Water,
+            });
+        }
+        tile.props.retain(|prop| !prop.components.blocking);
+
+        // Staying within jump range of the target ensures the remaining tiles can still slope towards it
+        let remaining_steps = last_index - index as i32;
+        let min_height = (previous_height - jump)
+            .max(target_height - jump * remaining_steps)
+            .max(1);
+        let max_height = (previous_height + jump)
+            .min(target_height + jump * remaining_steps)
+            .min(MAX_HEIGHT as i32);
+
+        let extra_height = (tile.surface_height() - tile.height) as i32;
+        let surface_height =
+            (tile.surface_height() as i32).clamp(min_height, max_height.max(min_height));
+        tile.height = (surface_height - extra_height).max(1) as u8;
+        previous_height = tile.surface_height() as i32;
+    }
+}
+
+/// Whether walkers with the given jump stat may step from `from` onto `to`, ignoring any units.
+#[must_use]
+pub fn can_walk_between(from: &TileData, to: &TileData, jump: u8) -> bool {
+    to.height > 0
+        && !to.has_deep_fluid()
+        && !to.is_blocked_by_props()
+        && from.surface_height().abs_diff(to.surface_height()) <= jump
+}
+
+/// Every hex a walker with the given jump stat could reach from `start`, ignoring movement limits and units.
+#[must_use]
+pub fn reachable_hexes(map: &GameMap, start: Hex, jump: u8) -> HashSet<Hex> {
+    let mut result = HashSet::new();
+    if !map.tiles.contains_key(&start) {
+        return result;
+    }
+
+    let mut queue = VecDeque::from([start]);
+    result.insert(start);
+    while let Some(hex) = queue.pop_front() {
+        let tile = &map.tiles[&hex];
+        for neighbor in hex.all_neighbors() {
+            if result.contains(&neighbor) {
+                continue;
+            }
+
+            let Some(neighbor_tile) = map.tiles.get(&neighbor) else {
+                continue;
+            };
+
+            if can_walk_between(tile, neighbor_tile, jump) {
+                result.insert(neighbor);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    result
+}
+
+/// Teams whose spawn zone can't be reached by walkers of the first team.
+#[must_use]
+pub fn find_unreachable_spawn_zones(map: &GameMap, jump: u8) -> Vec<u8> {
+    let mut spawn_tiles: HashMap<u8, Vec<Hex>> = HashMap::new();
+    for (hex, tile) in &map.tiles {
+        if let Some(team) = tile.spawn_zone {
+            spawn_tiles.entry(team).or_default().push(*hex);
+        }
+    }
+
+    let Some(first_team) = spawn_tiles.keys().min().copied() else {
+        return Vec::new();
+    };
+
+    let start = *spawn_tiles[&first_team]
+        .iter()
+        .min_by_key(|hex| (hex.x, hex.y))
+        .unwrap();
+    let reachable = reachable_hexes(map, start, jump);
+    let mut result: Vec<u8> = spawn_tiles
+        .into_iter()
+        .filter(|(_, hexes)| hexes.iter().any(|hex| !reachable.contains(hex)))
+        .map(|(team, _)| team)
+        .collect();
+    result.sort();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_map::map_generator::{
+        find_unreachable_spawn_zones, generate_map, MapGeneratorSettings,
+    };
+    use crate::game_map::MAX_HEIGHT;
+
+    #[test]
+    fn same_seed_should_produce_same_map() {
+        let settings = MapGeneratorSettings::default();
+        assert_eq!(generate_map(42, &settings), generate_map(42, &settings));
+        assert_ne!(generate_map(42, &settings), generate_map(43, &settings));
+    }
+
+    #[test]
+    fn spawn_zones_should_be_mirrored_and_reachable() {
+        for team_count in 1..=6 {
+            let settings = MapGeneratorSettings {
+                team_count,
+                ..Default::default()
+            };
+
+            for seed in 0..25 {
+                let map = generate_map(seed, &settings).unwrap();
+                assert!(
+                    find_unreachable_spawn_zones(&map, settings.jump).is_empty(),
+                    "Seed {seed} with {team_count} teams has unreachable spawn zones"
+                );
+
+                let first_zone = map.get_spawn_points_for_team(&1);
+                assert_eq!(settings.spawn_zone_size as usize, first_zone.len());
+                for team in 2..=team_count {
+                    let zone = map.get_spawn_points_for_team(&team);
+                    assert_eq!(first_zone.len(), zone.len());
+                    if 6 % team_count == 0 {
+                        let rotation = (team as u32 - 1) * 6 / team_count as u32;
+                        for hex in &first_zone {
+                            let tile = &map.tiles[hex];
+                            let rotated_tile = &map.tiles[&hex.rotate_cw(rotation)];
+                            assert_eq!(Some(team), rotated_tile.spawn_zone);
+                            assert_eq!(tile.height, rotated_tile.height);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_seed_should_produce_reachable_spawn_zones() {
+        for radius in [6, 10, 14] {
+            for team_count in [2, 3, 4, 5] {
+                for (max_height, jump) in [(8, 3), (MAX_HEIGHT, 3), (MAX_HEIGHT, 2)] {
+                    let settings = MapGeneratorSettings {
+                        radius,
+                        team_count,
+                        max_height,
+                        jump,
+                        ..Default::default()
+                    };
+
+                    // Seed 682 used to leave team 2 unreachable with the default settings
+                    for seed in (0..200).chain([682]) {
+                        let map = generate_map(seed, &settings).unwrap_or_else(|e| {
+                            panic!("{e} - settings: {settings:?}");
+                        });
+                        assert!(
+                            find_unreachable_spawn_zones(&map, jump).is_empty(),
+                            "Seed {seed} has unreachable spawn zones - settings: {settings:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::content_hash::content_hash;
+use crate::game_map::GameMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Identifies a map by its content, so clients can tell whether they already know it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct MapHash(pub u64);
+
+impl Display for MapHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl GameMap {
+    /// Hashes everything within the map, including its metadata. Tiles are sorted first, so the result doesn't depend on HashMap ordering.
+    #[must_use]
+    pub fn content_hash(&self) -> MapHash {
+        let mut tiles: Vec<_> = self.tiles.iter().collect();
+        tiles.sort_by_key(|(hex, _)| (hex.x, hex.y));
+
+        MapHash(content_hash(&(&self.metadata, self.radius, tiles)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_map::{GameMap, TileSurface};
+    use hexx::Hex;
+
+    #[test]
+    fn content_hash_should_only_depend_on_content() {
+        let map = GameMap::new(3);
+        let mut rebuilt_map = GameMap::new(0);
+        rebuilt_map.radius = 3;
+        rebuilt_map.metadata = map.metadata.clone();
+        let mut tiles: Vec<_> = map.tiles.iter().collect();
+        tiles.sort_by_key(|(hex, _)| (-hex.y, -hex.x));
+        for (hex, tile) in tiles {
+            rebuilt_map.tiles.insert(*hex, tile.clone());
+        }
+        assert_eq!(map.content_hash(), rebuilt_map.content_hash());
+
+        rebuilt_map.tiles.get_mut(&Hex::ZERO).unwrap().surface = TileSurface::Sand;
+        assert_ne!(map.content_hash(), rebuilt_map.content_hash());
+    }
+
+    #[test]
+    fn content_hash_should_include_metadata() {
+        let map = GameMap::new(3);
+        let mut renamed_map = map.clone();
+        renamed_map.metadata.name = "Renamed".into();
+        assert_ne!(map.content_hash(), renamed_map.content_hash());
+
+        let mut resized_map = map.clone();
+        resized_map.metadata.max_players += 1;
+        assert_ne!(map.content_hash(), resized_map.content_hash());
+    }
+}
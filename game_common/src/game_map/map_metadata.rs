@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// Describes a map and which kinds of matches it is suited for.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MapMetadata {
+    pub name: String,
+    pub author: String,
+    /// Number of teams with their own spawn zone. Spawn zones are numbered from 1 up to this.
+    pub team_count: u8,
+    /// How many units each team is expected to bring. Every spawn zone should be at least this big.
+    pub recommended_unit_count: u8,
+    pub min_players: u8,
+    pub max_players: u8,
+}
+
+impl Default for MapMetadata {
+    fn default() -> Self {
+        MapMetadata {
+            name: "Unnamed Map".into(),
+            author: "Unknown".into(),
+            team_count: 2,
+            recommended_unit_count: 3,
+            min_players: 1,
+            max_players: 1,
+        }
+    }
+}
+
+impl MapMetadata {
+    #[must_use]
+    pub fn supports_player_count(&self, player_count: usize) -> bool {
+        (self.min_players as usize..=self.max_players as usize).contains(&player_count)
+    }
+}
@@ -0,0 +1,352 @@
+use crate::game_map::game_map::GameMap;
+use crate::game_map::map_generator::{reachable_hexes, DEFAULT_JUMP};
+use bevy::utils::{HashMap, HashSet};
+use hexx::Hex;
+use std::fmt::Formatter;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum MapIssueSeverity {
+    /// The map can be played, but probably not the way its creator intended.
+    Warning,
+    /// The map can't be played at all.
+    Error,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MapIssue {
+    /// Combat needs at least the players' and the monsters' team.
+    TooFewTeams {
+        team_count: u8,
+    },
+    InvalidPlayerRange {
+        min: u8,
+        max: u8,
+    },
+    MissingSpawnZone {
+        team: u8,
+    },
+    SpawnZoneTooSmall {
+        team: u8,
+        tiles: usize,
+        recommended: u8,
+    },
+    /// Spawn tiles on which no unit can be placed, e.g. because they are blocked by props or covered by deep fluids.
+    UnusableSpawnTiles {
+        team: u8,
+        hexes: Vec<Hex>,
+    },
+    /// Spawn tiles for a team which doesn't exist according to the metadata.
+    UnusedSpawnZone {
+        team: u8,
+    },
+    /// Units placed within this spawn zone can't walk to the first one.
+    UnreachableSpawnZone {
+        team: u8,
+        hexes: Vec<Hex>,
+    },
+    /// Tiles units could stand on, but never walk to.
+    UnreachableTiles {
+        hexes: Vec<Hex>,
+    },
+}
+
+impl MapIssue {
+    #[must_use]
+    pub fn severity(&self) -> MapIssueSeverity {
+        match self {
+            MapIssue::TooFewTeams { .. }
+            | MapIssue::InvalidPlayerRange { .. }
+            | MapIssue::MissingSpawnZone { .. }
+            | MapIssue::SpawnZoneTooSmall { .. }
+            | MapIssue::UnreachableSpawnZone { .. } => MapIssueSeverity::Error,
+            MapIssue::UnusableSpawnTiles { .. }
+            | MapIssue::UnusedSpawnZone { .. }
+            | MapIssue::UnreachableTiles { .. } => MapIssueSeverity::Warning,
+        }
+    }
+
+    /// The hexes causing this issue, if it's caused by specific tiles.
+    #[must_use]
+    pub fn hexes(&self) -> &[Hex] {
+        match self {
+            MapIssue::UnusableSpawnTiles { hexes, .. }
+            | MapIssue::UnreachableSpawnZone { hexes, .. }
+            | MapIssue::UnreachableTiles { hexes } => hexes,
+            _ => &[],
+        }
+    }
+}
+
+impl std::fmt::Display for MapIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapIssue::TooFewTeams { team_count } => {
+                write!(f, "Map needs at least 2 teams, but has {team_count}.")
+            }
+            MapIssue::InvalidPlayerRange { min, max } => {
+                write!(f, "Player range {min}-{max} is invalid.")
+            }
+            MapIssue::MissingSpawnZone { team } => {
+                write!(f, "Team {team} has no usable spawn tiles.")
+            }
+            MapIssue::SpawnZoneTooSmall {
+                team,
+                tiles,
+                recommended,
+            } => write!(
+                f,
+                "Spawn zone {team} only has {tiles} usable tiles, but {recommended} units are recommended."
+            ),
+            MapIssue::UnusableSpawnTiles { team, hexes } => write!(
+                f,
+                "Spawn zone {team} contains {} tiles on which no unit can be placed.",
+                hexes.len()
+            ),
+            MapIssue::UnusedSpawnZone { team } => {
+                write!(f, "Spawn zone {team} is marked, but the map has fewer teams.")
+            }
+            MapIssue::UnreachableSpawnZone { team, hexes } => write!(
+                f,
+                "{} tiles of spawn zone {team} can't be reached from spawn zone 1.",
+                hexes.len()
+            ),
+            MapIssue::UnreachableTiles { hexes } => {
+                write!(f, "{} tiles can't be reached by walking units.", hexes.len())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MapValidationReport {
+    pub issues: Vec<MapIssue>,
+}
+
+impl MapValidationReport {
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &MapIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity() == MapIssueSeverity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &MapIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity() == MapIssueSeverity::Warning)
+    }
+}
+
+/// Checks whether `map` can actually be played the way its metadata describes.
+/// Reachability is checked for walking units with the default jump stat.
+#[must_use]
+pub fn validate_map(map: &GameMap) -> MapValidationReport {
+    let metadata = &map.metadata;
+    let mut issues = Vec::new();
+    if metadata.team_count < 2 {
+        issues.push(MapIssue::TooFewTeams {
+            team_count: metadata.team_count,
+        });
+    }
+
+    if metadata.min_players == 0 || metadata.min_players > metadata.max_players {
+        issues.push(MapIssue::InvalidPlayerRange {
+            min: metadata.min_players,
+            max: metadata.max_players,
+        });
+    }
+
+    let mut usable_spawn_tiles: HashMap<u8, Vec<Hex>> = HashMap::new();
+    let mut unusable_spawn_tiles: HashMap<u8, Vec<Hex>> = HashMap::new();
+    for (hex, tile) in &map.tiles {
+        let Some(team) = tile.spawn_zone else {
+            continue;
+        };
+
+        if tile.can_unit_be_placed_here(&team) {
+            usable_spawn_tiles.entry(team).or_default().push(*hex);
+        } else {
+            unusable_spawn_tiles.entry(team).or_default().push(*hex);
+        }
+    }
+
+    let mut marked_teams: Vec<u8> = usable_spawn_tiles
+        .keys()
+        .chain(unusable_spawn_tiles.keys())
+        .copied()
+        .collect::<HashSet<u8>>()
+        .into_iter()
+        .collect();
+    marked_teams.sort();
+    for team in marked_teams {
+        if team > metadata.team_count {
+            issues.push(MapIssue::UnusedSpawnZone { team });
+        }
+
+        if let Some(hexes) = unusable_spawn_tiles.remove(&team) {
+            issues.push(MapIssue::UnusableSpawnTiles {
+                team,
+                hexes: sorted(hexes),
+            });
+        }
+    }
+
+    for team in 1..=metadata.team_count {
+        let tiles = usable_spawn_tiles.get(&team).map_or(0, Vec::len);
+        if tiles == 0 {
+            issues.push(MapIssue::MissingSpawnZone { team });
+        } else if tiles < metadata.recommended_unit_count as usize {
+            issues.push(MapIssue::SpawnZoneTooSmall {
+                team,
+                tiles,
+                recommended: metadata.recommended_unit_count,
+            });
+        }
+    }
+
+    let Some(start) = usable_spawn_tiles
+        .get(&1)
+        .and_then(|hexes| hexes.iter().min_by_key(|hex| (hex.x, hex.y)))
+    else {
+        return MapValidationReport { issues };
+    };
+
+    let reachable = reachable_hexes(map, *start, DEFAULT_JUMP);
+    for team in 2..=metadata.team_count {
+        let unreachable: Vec<Hex> = usable_spawn_tiles
+            .get(&team)
+            .into_iter()
+            .flatten()
+            .filter(|hex| !reachable.contains(*hex))
+            .copied()
+            .collect();
+        if !unreachable.is_empty() {
+            issues.push(MapIssue::UnreachableSpawnZone {
+                team,
+                hexes: sorted(unreachable),
+            });
+        }
+    }
+
+    let unreachable_tiles: Vec<Hex> = map
+        .tiles
+        .iter()
+        .filter(|(hex, tile)| {
+            tile.height > 0
+                && !tile.has_deep_fluid()
+                && !tile.is_blocked_by_props()
+                && !reachable.contains(*hex)
+        })
+        .map(|(hex, _)| *hex)
+        .collect();
+    if !unreachable_tiles.is_empty() {
+        issues.push(MapIssue::UnreachableTiles {
+            hexes: sorted(unreachable_tiles),
+        });
+    }
+
+    MapValidationReport { issues }
+}
+
+fn sorted(mut hexes: Vec<Hex>) -> Vec<Hex> {
+    hexes.sort_by_key(|hex| (hex.x, hex.y));
+    hexes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_map::map_validation::{validate_map, MapIssue};
+    use crate::game_map::{Fluid, FluidKind, GameMap};
+    use hexx::Hex;
+
+    fn create_valid_map() -> GameMap {
+        let mut map = GameMap::new(3);
+        map.metadata.recommended_unit_count = 2;
+        for (hex, team) in [
+            (Hex::new(0, -3), 1),
+            (Hex::new(1, -3), 1),
+            (Hex::new(0, 3), 2),
+            (Hex::new(-1, 3), 2),
+        ] {
+            map.tiles.get_mut(&hex).unwrap().spawn_zone = Some(team);
+        }
+
+        map
+    }
+
+    #[test]
+    fn valid_maps_should_have_no_issues() {
+        assert_eq!(
+            Vec::<MapIssue>::new(),
+            validate_map(&create_valid_map()).issues
+        );
+    }
+
+    #[test]
+    fn spawn_tiles_in_deep_water_should_be_unusable() {
+        let mut map = create_valid_map();
+        let hex = Hex::new(1, -3);
+        map.tiles.get_mut(&hex).unwrap().fluid = Some(Fluid {
+            height: 2.0,
+            kind: FluidKind::Water,
+        });
+
+        let report = validate_map(&map);
+        assert!(report.has_errors());
+        assert_eq!(
+            vec![
+                MapIssue::UnusableSpawnTiles {
+                    team: 1,
+                    hexes: vec![hex]
+                },
+                MapIssue::SpawnZoneTooSmall {
+                    team: 1,
+                    tiles: 1,
+                    recommended: 2
+                }
+            ],
+            report.issues
+        );
+    }
+
+    #[test]
+    fn islands_should_be_reported() {
+        let mut map = create_valid_map();
+        for hex in Hex::new(0, 3).range(2) {
+            if let Some(tile) = map.tiles.get_mut(&hex) {
+                if tile.spawn_zone.is_none() {
+                    tile.height = 0;
+                }
+            }
+        }
+
+        let report = validate_map(&map);
+        assert!(report.has_errors());
+        assert!(report.issues.contains(&MapIssue::UnreachableSpawnZone {
+            team: 2,
+            hexes: vec![Hex::new(-1, 3), Hex::new(0, 3)]
+        }));
+        assert!(report
+            .warnings()
+            .any(|issue| matches!(issue, MapIssue::UnreachableTiles { .. })));
+    }
+
+    #[test]
+    fn maps_with_a_single_team_should_be_rejected() {
+        let mut map = create_valid_map();
+        map.metadata.team_count = 1;
+
+        let report = validate_map(&map);
+        assert_eq!(
+            vec![
+                MapIssue::TooFewTeams { team_count: 1 },
+                MapIssue::UnusedSpawnZone { team: 2 }
+            ],
+            report.issues
+        );
+    }
+}
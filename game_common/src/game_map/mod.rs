@@ -2,6 +2,13 @@ mod field_of_movement_with_edge_detection;
 mod fluid;
 mod fluid_kind;
 mod game_map;
+mod line_of_sight;
+mod map_format;
+pub mod map_generator;
+mod map_hash;
+mod map_metadata;
+pub mod map_validation;
+mod prop_instance;
 mod tile_data;
 mod tile_surface;
 mod versioned_map_data;
@@ -10,12 +17,23 @@ use bevy::math::Vec2;
 use hexx::{HexLayout, HexOrientation};
 
 pub use {
-    fluid::Fluid, fluid_kind::FluidKind, game_map::GameMap, tile_data::TileData,
+    fluid::Fluid,
+    fluid_kind::{FluidKind, LAVA_CONTACT_DAMAGE, SLIME_DURATION},
+    game_map::GameMap,
+    line_of_sight::EYE_HEIGHT,
+    map_format::MapFormat,
+    map_hash::MapHash,
+    map_metadata::MapMetadata,
+    prop_instance::PropInstance,
+    tile_data::TileData,
     tile_surface::TileSurface,
 };
 
 pub const MAX_HEIGHT: u8 = 20;
 
+/// Climbing up costs one additional movement for every full step of this many height levels.
+pub const HEIGHT_PER_CLIMBING_COST: u8 = 2;
+
 pub const HEX_LAYOUT: HexLayout = HexLayout {
     hex_size: Vec2::splat(1.0),
     origin: Vec2::ZERO,
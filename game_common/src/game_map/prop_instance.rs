@@ -0,0 +1,89 @@
+use crate::game_data::element::Element;
+use crate::game_data::prop::{Interaction, PropComponents, PropDefinition, PropId};
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// A prop placed on a tile.
+/// Its components start out as a copy of the definition's defaults, and destructible props keep track of their
+/// remaining hp in there.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PropInstance {
+    pub prop_id: PropId,
+    pub components: PropComponents,
+}
+
+impl PropInstance {
+    #[must_use]
+    pub fn from_definition(definition: &PropDefinition) -> Self {
+        PropInstance {
+            prop_id: definition.id,
+            components: definition.components.clone(),
+        }
+    }
+
+    /// Reduces the remaining hp of destructible props. Returns true once the prop has been destroyed.
+    pub fn apply_damage(&mut self, element: Option<Element>, amount: u32) -> bool {
+        let Some(destructible) = self.components.destructible.as_mut() else {
+            return false;
+        };
+
+        let amount = match element {
+            None => amount,
+            Some(element) => destructible.resistances.apply(element, amount),
+        };
+        destructible.hp = destructible.hp.saturating_sub(amount);
+        destructible.hp == 0
+    }
+
+    /// Triggers the prop's interaction, if it has one. Returns false if nothing happened.
+    pub fn interact(&mut self, props: &HashMap<PropId, PropDefinition>) -> bool {
+        let Some(interaction) = self.components.interactable else {
+            return false;
+        };
+
+        match interaction {
+            Interaction::ReplaceWith(prop_id) => {
+                let Some(definition) = props.get(&prop_id) else {
+                    return false;
+                };
+
+                *self = PropInstance::from_definition(definition);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_data::prop::{Interaction, PropComponents, PropDefinition};
+    use crate::game_map::PropInstance;
+    use bevy::utils::HashMap;
+
+    #[test]
+    fn interacting_should_replace_the_prop() {
+        let mut props = HashMap::new();
+        for (id, replacement, blocking) in [(1, 2, true), (2, 1, false)] {
+            props.insert(
+                id,
+                PropDefinition {
+                    id,
+                    name: format!("Door {id}"),
+                    components: PropComponents {
+                        blocking,
+                        interactable: Some(Interaction::ReplaceWith(replacement)),
+                        ..Default::default()
+                    },
+                },
+            );
+        }
+
+        let mut door = PropInstance::from_definition(&props[&1]);
+        assert!(door.interact(&props));
+        assert_eq!(2, door.prop_id);
+        assert!(!door.components.blocking);
+
+        assert!(door.interact(&props));
+        assert_eq!(PropInstance::from_definition(&props[&1]), door);
+    }
+}
@@ -1,4 +1,6 @@
+use crate::game_data::element::Element;
 use crate::game_map::fluid::Fluid;
+use crate::game_map::prop_instance::PropInstance;
 use crate::game_map::tile_surface::TileSurface;
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +10,8 @@ pub struct TileData {
     pub surface: TileSurface,
     pub fluid: Option<Fluid>,
     pub spawn_zone: Option<u8>,
+    /// Props stacked on top of each other, starting at the tile's surface.
+    pub props: Vec<PropInstance>,
 }
 
 impl TileData {
@@ -20,12 +24,40 @@ impl TileData {
             return false;
         }
 
-        if let Some(fluid) = &self.fluid {
-            if fluid.height > 1.0 {
-                return false;
-            }
-        }
+        !self.has_deep_fluid() && !self.is_blocked_by_props()
+    }
+
+    /// Deep fluids can only be entered by units which are able to swim or fly.
+    #[must_use]
+    pub fn has_deep_fluid(&self) -> bool {
+        self.fluid.as_ref().is_some_and(|fluid| fluid.height > 1.0)
+    }
+
+    /// The height units stand at on this tile, including everything props are adding on top.
+    #[must_use]
+    pub fn surface_height(&self) -> u8 {
+        self.props.iter().fold(self.height, |height, prop| {
+            height.saturating_add(prop.components.extra_height)
+        })
+    }
+
+    /// Tiles with blocking props can't be entered by anyone besides teleporters, and nobody can stop there.
+    #[must_use]
+    pub fn is_blocked_by_props(&self) -> bool {
+        self.props.iter().any(|prop| prop.components.blocking)
+    }
+
+    /// Whether any prop on this tile can be damaged.
+    #[must_use]
+    pub fn has_destructible_props(&self) -> bool {
+        self.props
+            .iter()
+            .any(|prop| prop.components.destructible.is_some())
+    }
 
-        true
+    /// Damages every destructible prop on this tile and removes the ones which were destroyed.
+    pub fn damage_props(&mut self, element: Option<Element>, amount: u32) {
+        self.props
+            .retain_mut(|prop| !prop.apply_damage(element, amount));
     }
 }
@@ -9,6 +9,19 @@ pub enum TileSurface {
     Earth,
 }
 
+impl TileSurface {
+    /// How much movement it costs to enter a tile with this surface.
+    #[must_use]
+    pub fn movement_cost(&self) -> u32 {
+        match self {
+            TileSurface::Grass => 1,
+            TileSurface::Stone => 1,
+            TileSurface::Sand => 2,
+            TileSurface::Earth => 1,
+        }
+    }
+}
+
 impl Display for TileSurface {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -1,54 +1,504 @@
+use crate::game_data::element::Resistances;
+use crate::game_data::prop::{Destructible, Interaction, PropComponents, PropId};
+use crate::game_map::fluid::Fluid;
 use crate::game_map::game_map::GameMap;
-use bevy::log::error;
-use ron::de::from_reader;
-use ron::to_string;
-use serde::{Deserialize, Serialize};
+use crate::game_map::map_format::{MapFormat, BINARY_MAP_MAGIC};
+use crate::game_map::map_metadata::MapMetadata;
+use crate::game_map::prop_instance::PropInstance;
+use crate::game_map::tile_data::TileData;
+use crate::game_map::tile_surface::TileSurface;
+use bevy::log::{error, info};
+use bevy::utils::hashbrown::HashMap;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use hexx::Hex;
+use serde::{Deserialize, Serialize, Serializer};
 use std::fs;
-use std::fs::File;
+use std::io::Write;
 
 /// Enum to keep track of different Map Data Versions. Exclusively used for Saving and loading maps,
 /// once that's done, everything else should just use the underlying GameMap of the latest version.
+///
+/// Whenever the serialized representation of [GameMap] changes, freeze the old layout in a new `GameMapVx` struct,
+/// add a variant for the new version and teach the previous one how to [VersionedMapData::upgrade] to it.
+/// Every version also needs a fixture inside `test_fixtures/maps`, so the whole chain stays covered by tests.
 #[derive(Serialize, Deserialize)]
 pub enum VersionedMapData {
-    V1(GameMap),
+    V1(GameMapV1),
+    /// Added props to tiles.
+    V2(GameMapV2),
+    /// Added metadata.
+    V3(GameMap),
 }
 
 impl VersionedMapData {
-    pub fn write_to_disk(&self, path: &str) -> Result<(), ()> {
-        // let config = PrettyConfig::new();
-        match to_string(self) {
-            Ok(result) => match fs::write(path, result) {
-                Ok(_) => Ok(()),
-                Err(e) => {
-                    error!("Encountered Error when writing map data to disk: {:?}", e);
-                    Err(())
-                }
-            },
-            Err(e) => {
-                error!("Encountered Error when serializing map data: {:?}", e);
-                Err(())
+    pub const LATEST_VERSION: u32 = 3;
+
+    #[must_use]
+    pub fn version(&self) -> u32 {
+        match self {
+            VersionedMapData::V1(_) => 1,
+            VersionedMapData::V2(_) => 2,
+            VersionedMapData::V3(_) => 3,
+        }
+    }
+
+    /// Migrates the data by exactly one version. The latest version is returned as is.
+    #[must_use]
+    pub fn upgrade(self) -> Self {
+        match self {
+            VersionedMapData::V1(map) => VersionedMapData::V2(map.migrate()),
+            VersionedMapData::V2(map) => VersionedMapData::V3(map.migrate()),
+            VersionedMapData::V3(map) => VersionedMapData::V3(map),
+        }
+    }
+
+    /// Walks through the migration chain until the data has reached the latest version.
+    #[must_use]
+    pub fn into_latest(self) -> GameMap {
+        let mut data = self;
+        loop {
+            match data {
+                VersionedMapData::V3(map) => return map,
+                older => data = older.upgrade(),
             }
         }
     }
 
-    pub fn load_from_file(path: &str) -> Result<GameMap, ()> {
-        match File::open(path) {
-            Ok(file) => {
-                match from_reader::<File, Self>(file) {
-                    Ok(data) => match data {
-                        VersionedMapData::V1(map) => Ok(map),
-                        // Older versions would call a .migrate method on them which would upgrade them to VX+1.
-                    },
-                    Err(e) => {
-                        error!("Was unable to parse file. Error: {:?}", e);
-                        Err(())
-                    }
-                }
+    /// Serializes `map` as the latest version, without having to move it into a [VersionedMapData] first.
+    pub fn encode(map: &GameMap, format: MapFormat) -> Result<Vec<u8>, String> {
+        let data = LatestMapData(map);
+        let result = match format {
+            MapFormat::Ron => ron::to_string(&data)
+                .map(String::into_bytes)
+                .map_err(|e| format!("Encountered Error when serializing map data: {:?}", e)),
+            MapFormat::Binary => bincode::serde::encode_to_vec(&data, bincode::config::standard())
+                .map_err(|e| format!("Encountered Error when serializing map data: {:?}", e))
+                .and_then(|bytes| {
+                    let mut encoder =
+                        DeflateEncoder::new(BINARY_MAP_MAGIC.to_vec(), Compression::fast());
+                    encoder
+                        .write_all(&bytes)
+                        .and_then(|_| encoder.finish())
+                        .map_err(|e| {
+                            format!("Encountered Error when compressing map data: {:?}", e)
+                        })
+                }),
+        };
+
+        if let Err(e) = &result {
+            error!("{}", e);
+        }
+        result
+    }
+
+    /// Deserializes a map stored in any [MapFormat] and version.
+    pub fn decode(bytes: &[u8]) -> Result<GameMap, String> {
+        let data = match MapFormat::detect(bytes) {
+            MapFormat::Ron => ron::de::from_bytes::<Self>(bytes)
+                .map_err(|e| format!("Was unable to parse map data. Error: {}", e)),
+            MapFormat::Binary => {
+                let mut decoder = DeflateDecoder::new(&bytes[BINARY_MAP_MAGIC.len()..]);
+                bincode::serde::decode_from_std_read::<Self, _, _>(
+                    &mut decoder,
+                    bincode::config::standard(),
+                )
+                .map_err(|e| format!("Was unable to parse binary map data. Error: {}", e))
             }
+        };
+
+        let data = data.map_err(|e| {
+            error!("{}", e);
+            e
+        })?;
+
+        if data.version() != Self::LATEST_VERSION {
+            info!(
+                "Migrating map data from version {} to {}.",
+                data.version(),
+                Self::LATEST_VERSION
+            );
+        }
+
+        Ok(data.into_latest())
+    }
+
+    pub fn write_to_disk(map: &GameMap, path: &str, format: MapFormat) -> Result<(), String> {
+        let bytes = Self::encode(map, format)?;
+        fs::write(path, bytes).map_err(|e| {
+            let message = format!("Was unable to write {}: {}", path, e);
+            error!("{}", message);
+            message
+        })
+    }
+
+    pub fn load_from_file(path: &str) -> Result<GameMap, String> {
+        match fs::read(path) {
+            Ok(bytes) => Self::decode(&bytes),
             Err(e) => {
-                error!("Was unable to open file. Error: {:?}", e);
-                Err(())
+                let message = format!("Was unable to open {}: {}", path, e);
+                error!("{}", message);
+                Err(message)
             }
         }
     }
 }
+
+/// Serializes exactly like [VersionedMapData::V3], but only borrows the map.
+struct LatestMapData<'a>(&'a GameMap);
+
+impl Serialize for LatestMapData<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_variant(
+            "VersionedMapData",
+            VersionedMapData::LATEST_VERSION - 1,
+            "V3",
+            self.0,
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GameMapV1 {
+    pub radius: u32,
+    pub tiles: HashMap<Hex, TileDataV1>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TileDataV1 {
+    pub height: u8,
+    pub surface: TileSurface,
+    pub fluid: Option<Fluid>,
+    pub spawn_zone: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GameMapV2 {
+    pub radius: u32,
+    pub tiles: HashMap<Hex, TileDataV2>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TileDataV2 {
+    pub height: u8,
+    pub surface: TileSurface,
+    pub fluid: Option<Fluid>,
+    pub spawn_zone: Option<u8>,
+    pub props: Vec<PropInstanceV2>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PropInstanceV2 {
+    pub prop_id: PropId,
+    pub components: PropComponentsV2,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PropComponentsV2 {
+    pub offset: [f32; 3],
+    pub blocking: bool,
+    pub extra_height: u8,
+    pub destructible: Option<DestructibleV2>,
+    pub interactable: Option<InteractionV2>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DestructibleV2 {
+    pub hp: u32,
+    #[serde(default)]
+    pub resistances: ResistancesV2,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResistancesV2 {
+    pub fire: i32,
+    pub ice: i32,
+    pub lightning: i32,
+    pub earth: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum InteractionV2 {
+    ReplaceWith(PropId),
+}
+
+impl GameMapV1 {
+    fn migrate(self) -> GameMapV2 {
+        let tiles = self
+            .tiles
+            .into_iter()
+            .map(|(hex, tile)| {
+                (
+                    hex,
+                    TileDataV2 {
+                        height: tile.height,
+                        surface: tile.surface,
+                        fluid: tile.fluid,
+                        spawn_zone: tile.spawn_zone,
+                        props: Vec::new(),
+                    },
+                )
+            })
+            .collect();
+
+        GameMapV2 {
+            radius: self.radius,
+            tiles,
+        }
+    }
+}
+
+impl GameMapV2 {
+    fn migrate(self) -> GameMap {
+        // Older maps had no way of declaring their teams, so just count the spawn zones which are in use
+        let team_count = self
+            .tiles
+            .values()
+            .filter_map(|tile| tile.spawn_zone)
+            .max()
+            .unwrap_or_default();
+
+        GameMap {
+            metadata: MapMetadata {
+                team_count,
+                ..Default::default()
+            },
+            radius: self.radius,
+            tiles: self
+                .tiles
+                .into_iter()
+                .map(|(hex, tile)| (hex, tile.upgrade()))
+                .collect(),
+        }
+    }
+}
+
+impl TileDataV2 {
+    fn upgrade(self) -> TileData {
+        TileData {
+            height: self.height,
+            surface: self.surface,
+            fluid: self.fluid,
+            spawn_zone: self.spawn_zone,
+            props: self
+                .props
+                .into_iter()
+                .map(PropInstanceV2::upgrade)
+                .collect(),
+        }
+    }
+}
+
+impl PropInstanceV2 {
+    fn upgrade(self) -> PropInstance {
+        let components = self.components;
+        PropInstance {
+            prop_id: self.prop_id,
+            components: PropComponents {
+                offset: components.offset,
+                blocking: components.blocking,
+                extra_height: components.extra_height,
+                destructible: components.destructible.map(|x| Destructible {
+                    hp: x.hp,
+                    resistances: Resistances {
+                        fire: x.resistances.fire,
+                        ice: x.resistances.ice,
+                        lightning: x.resistances.lightning,
+                        earth: x.resistances.earth,
+                    },
+                }),
+                interactable: components.interactable.map(|x| match x {
+                    InteractionV2::ReplaceWith(prop_id) => Interaction::ReplaceWith(prop_id),
+                }),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_data::element::Resistances;
+    use crate::game_data::prop::{Destructible, Interaction, PropComponents};
+    use crate::game_map::fluid::Fluid;
+    use crate::game_map::fluid_kind::FluidKind;
+    use crate::game_map::game_map::GameMap;
+    use crate::game_map::map_format::MapFormat;
+    use crate::game_map::map_metadata::MapMetadata;
+    use crate::game_map::tile_data::TileData;
+    use crate::game_map::tile_surface::TileSurface;
+    use crate::game_map::versioned_map_data::{LatestMapData, VersionedMapData};
+    use crate::game_map::PropInstance;
+    use hexx::Hex;
+    use ron::de::from_reader;
+    use std::fs::File;
+
+    fn fixture_path(version: u32) -> String {
+        format!(
+            "{}/test_fixtures/maps/v{}.map",
+            env!("CARGO_MANIFEST_DIR"),
+            version
+        )
+    }
+
+    fn tile(height: u8, surface: TileSurface, fluid: Option<FluidKind>) -> TileData {
+        TileData {
+            height,
+            surface,
+            fluid: fluid.map(|kind| Fluid { height: 0.5, kind }),
+            spawn_zone: None,
+            props: Vec::new(),
+        }
+    }
+
+    /// The map every fixture contains, as far as its version is able to represent it.
+    fn expected_map(version: u32) -> GameMap {
+        let mut map = GameMap::new(1);
+        map.tiles
+            .insert(Hex::new(0, 0), tile(2, TileSurface::Stone, None));
+        map.tiles.insert(
+            Hex::new(0, 1),
+            tile(3, TileSurface::Sand, Some(FluidKind::Water)),
+        );
+        map.tiles
+            .insert(Hex::new(0, -1), tile(0, TileSurface::Earth, None));
+        map.tiles.insert(
+            Hex::new(1, -1),
+            tile(1, TileSurface::Earth, Some(FluidKind::Lava)),
+        );
+        map.tiles.get_mut(&Hex::new(1, 0)).unwrap().spawn_zone = Some(1);
+        map.tiles.get_mut(&Hex::new(-1, 0)).unwrap().spawn_zone = Some(2);
+
+        if version >= 2 {
+            map.tiles
+                .get_mut(&Hex::new(0, 0))
+                .unwrap()
+                .props
+                .push(PropInstance {
+                    prop_id: 1,
+                    components: PropComponents {
+                        blocking: true,
+                        extra_height: 2,
+                        ..Default::default()
+                    },
+                });
+        }
+
+        if version >= 3 {
+            map.metadata = MapMetadata {
+                name: "Fixture".into(),
+                author: "Tests".into(),
+                team_count: 2,
+                recommended_unit_count: 1,
+                min_players: 1,
+                max_players: 2,
+            };
+        } else {
+            map.metadata.team_count = 2;
+        }
+
+        map
+    }
+
+    #[test]
+    fn fixtures_of_every_version_should_load() {
+        for version in 1..=VersionedMapData::LATEST_VERSION {
+            let path = fixture_path(version);
+            let data: VersionedMapData = from_reader(File::open(&path).unwrap()).unwrap();
+            assert_eq!(version, data.version(), "{path}");
+
+            let map = GameMap::load_from_file(&path).unwrap();
+            assert_eq!(expected_map(version), map, "{path}");
+        }
+    }
+
+    #[test]
+    fn upgrading_should_advance_one_version_at_a_time() {
+        let path = fixture_path(1);
+        let mut data: VersionedMapData = from_reader(File::open(path).unwrap()).unwrap();
+        for version in 2..=VersionedMapData::LATEST_VERSION {
+            data = data.upgrade();
+            assert_eq!(version, data.version());
+        }
+
+        assert_eq!(expected_map(1), data.into_latest());
+    }
+
+    #[test]
+    fn v2_props_should_be_upgraded_with_all_their_components() {
+        let data = "V2((radius:0,tiles:{(x:0,y:0):(height:1,surface:Grass,fluid:None,spawn_zone:None,props:[\
+            (prop_id:2,components:(destructible:Some((hp:5,resistances:(fire:-50))))),\
+            (prop_id:3,components:(offset:(0.5,0.0,0.0),interactable:Some(ReplaceWith(4))))])}))";
+        let data: VersionedMapData = ron::from_str(data).unwrap();
+        let map = data.into_latest();
+
+        let props = &map.tiles[&Hex::ZERO].props;
+        assert_eq!(
+            vec![
+                PropInstance {
+                    prop_id: 2,
+                    components: PropComponents {
+                        destructible: Some(Destructible {
+                            hp: 5,
+                            resistances: Resistances {
+                                fire: -50,
+                                ..Default::default()
+                            },
+                        }),
+                        ..Default::default()
+                    },
+                },
+                PropInstance {
+                    prop_id: 3,
+                    components: PropComponents {
+                        offset: [0.5, 0.0, 0.0],
+                        interactable: Some(Interaction::ReplaceWith(4)),
+                        ..Default::default()
+                    },
+                },
+            ],
+            *props
+        );
+    }
+
+    #[test]
+    fn borrowed_serialization_should_match_latest_version() {
+        let map = expected_map(VersionedMapData::LATEST_VERSION);
+        let owned = VersionedMapData::V3(map.clone());
+        let borrowed = LatestMapData(&map);
+
+        assert_eq!(
+            ron::to_string(&owned).unwrap(),
+            ron::to_string(&borrowed).unwrap()
+        );
+
+        let config = bincode::config::standard();
+        assert_eq!(
+            bincode::serde::encode_to_vec(&owned, config).unwrap(),
+            bincode::serde::encode_to_vec(&borrowed, config).unwrap()
+        );
+    }
+
+    #[test]
+    fn formats_should_be_detected_when_decoding() {
+        let map = expected_map(VersionedMapData::LATEST_VERSION);
+        for format in [MapFormat::Ron, MapFormat::Binary] {
+            let bytes = VersionedMapData::encode(&map, format).unwrap();
+            assert_eq!(format, MapFormat::detect(&bytes));
+            assert_eq!(map, VersionedMapData::decode(&bytes).unwrap());
+        }
+    }
+
+    #[test]
+    fn saved_maps_should_load_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("map.map");
+        let path = path.to_str().unwrap();
+
+        let map = expected_map(VersionedMapData::LATEST_VERSION);
+        map.write_to_disk(path);
+        assert_eq!(map, GameMap::load_from_file(path).unwrap());
+    }
+}
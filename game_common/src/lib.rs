@@ -1,17 +1,30 @@
 pub mod combat_data;
 pub mod combat_turn;
 pub mod combat_unit;
+pub mod content_hash;
+pub mod desync;
 pub mod game_data;
 pub mod game_map;
+pub mod lobby;
 pub mod network_events;
 pub mod network_helpers;
 pub mod player;
 pub mod player_resources;
+pub mod positioning;
+pub mod skill_effect;
+pub mod status_effect;
 pub mod turn_resources;
 pub mod unit_stats;
 pub mod validation;
 
-pub const TEST_MAP_NAME: &str = "test_map.map";
+/// Directory containing all parseable game data files, relative to the working directory.
+pub const GAME_DATA_DIRECTORY: &str = "data";
 
-/// Using a const makes finding these easier once desyncing is implemented.
+/// Directory containing all maps, relative to the working directory.
+/// The server picks its maps from here, and the map editor saves them in here.
+pub const MAP_DIRECTORY: &str = "maps";
+pub const MAP_FILE_EXTENSION: &str = "map";
+
+/// Marks places which assume client and server are in sync. Desyncs are detected via [network_events::server_to_client::StateChecksum],
+/// but these places would still fail before a resync could happen.
 pub const DESYNC_TODO_MESSAGE: &str = "TODO: Desync if this ever happens.";
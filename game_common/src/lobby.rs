@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+pub type LobbyId = u32;
+
+/// What players browsing for a lobby get to see about it.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct LobbyInfo {
+    pub id: LobbyId,
+    pub name: String,
+    pub player_count: usize,
+    /// Lobbies can't be joined anymore once their game started.
+    pub in_game: bool,
+}
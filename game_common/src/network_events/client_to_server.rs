@@ -1,5 +1,8 @@
 use crate::combat_unit::UnitId;
 use crate::game_data::skill::SkillId;
+use crate::game_map::MapHash;
+use crate::lobby::LobbyId;
+use crate::player::SessionToken;
 use bevy::prelude::Event;
 use hexx::Hex;
 use serde::{Deserialize, Serialize};
@@ -7,10 +10,22 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 #[cfg_attr(feature = "ecs", derive(Event))]
 pub enum ClientToServerMessage {
+    // Connection
+    /// Needs to stay the first variant, so the server can still decode it for clients using another protocol version.
+    Hello(Hello),
+    /// Sent when a [StateChecksum](crate::network_events::server_to_client::StateChecksum) didn't match.
+    /// The server answers with a full snapshot.
+    RequestResync,
+
     // Lobby
+    ListLobbies,
+    CreateLobby(CreateLobby),
+    JoinLobby(JoinLobby),
+    LeaveLobby,
     StartGame,
 
     // Combat
+    RequestMap(RequestMap),
     FinishedLoading,
     EndTurn,
     PlaceUnit(PlaceUnit),
@@ -22,6 +37,31 @@ pub enum ClientToServerMessage {
     PickUnit(PickUnit),
 }
 
+/// The first message sent by every client. No other message will be processed until the server accepted it.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Hello {
+    pub protocol_version: u32,
+    pub game_data_hash: u64,
+    /// Set when trying to rejoin after losing the connection.
+    pub session_token: Option<SessionToken>,
+}
+
+/// Creates a new lobby and joins it right away.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct CreateLobby {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct JoinLobby {
+    pub lobby_id: LobbyId,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct RequestMap {
+    pub hash: MapHash,
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct PickUnit {
     pub unit_id: UnitId,
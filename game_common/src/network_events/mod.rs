@@ -1,51 +1,101 @@
 pub mod client_to_server;
 pub mod server_to_client;
 
-use bevy::prelude::error;
-use bincode::config::Configuration;
-use bincode::error::EncodeError;
-use bytes::Bytes;
+use bincode::error::{DecodeError, EncodeError};
+use bytes::{BufMut, Bytes, BytesMut};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::fmt::Formatter;
 use std::time::Duration;
 
 pub const NETWORK_IDLE_TIMEOUT: Duration = Duration::new(5, 0);
 
+/// Needs to be increased whenever the message schema changes, so outdated clients get refused instead of misunderstood.
+pub const PROTOCOL_VERSION: u32 = 5;
+
+/// Every message is sent as a frame, starting with its payload length as a big endian u32.
+pub const FRAME_HEADER_SIZE: usize = 4;
+
+/// Frames announcing a larger payload are rejected without reading any further.
+pub const MAX_FRAME_SIZE: usize = 4 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum NetworkMessageError {
+    Encode(EncodeError),
+    Decode(DecodeError),
+    /// The payload didn't consist of exactly one message.
+    TrailingBytes {
+        payload_size: usize,
+        read_bytes: usize,
+    },
+    /// Once this happens while reading, the stream can't be trusted anymore and should be closed.
+    FrameTooLarge {
+        size: usize,
+    },
+}
+
+impl std::fmt::Display for NetworkMessageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkMessageError::Encode(e) => write!(f, "Failed to encode message: {}", e),
+            NetworkMessageError::Decode(e) => write!(f, "Failed to decode message: {}", e),
+            NetworkMessageError::TrailingBytes {
+                payload_size,
+                read_bytes,
+            } => write!(
+                f,
+                "Message only used {} of {} payload bytes.",
+                read_bytes, payload_size
+            ),
+            NetworkMessageError::FrameTooLarge { size } => write!(
+                f,
+                "Frame size of {} bytes exceeds the maximum of {} bytes.",
+                size, MAX_FRAME_SIZE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NetworkMessageError {}
+
 pub trait NetworkMessage: DeserializeOwned + Serialize {
-    fn serialize(&self) -> Result<Bytes, EncodeError>;
-    fn deserialize(bytes: &[u8]) -> Result<Vec<Self>, ()>;
+    /// Encodes this message into a complete, length-prefixed frame.
+    fn serialize(&self) -> Result<Bytes, NetworkMessageError>;
+    /// Decodes the payload of a single frame, as returned by [crate::network_helpers::FrameBuffer::next_frame].
+    fn deserialize(payload: &[u8]) -> Result<Self, NetworkMessageError>;
 }
 
 impl<T> NetworkMessage for T
 where
     T: DeserializeOwned + Serialize,
 {
-    fn serialize(&self) -> Result<Bytes, EncodeError> {
+    fn serialize(&self) -> Result<Bytes, NetworkMessageError> {
         let config = bincode::config::standard();
-        match bincode::serde::encode_to_vec(self, config) {
-            Ok(result) => Ok(Bytes::from(result)),
-            Err(e) => Err(e),
+        let payload =
+            bincode::serde::encode_to_vec(self, config).map_err(NetworkMessageError::Encode)?;
+        if payload.len() > MAX_FRAME_SIZE {
+            return Err(NetworkMessageError::FrameTooLarge {
+                size: payload.len(),
+            });
         }
+
+        let mut frame = BytesMut::with_capacity(FRAME_HEADER_SIZE + payload.len());
+        frame.put_u32(payload.len() as u32);
+        frame.put_slice(&payload);
+        Ok(frame.freeze())
     }
 
-    fn deserialize(bytes: &[u8]) -> Result<Vec<T>, ()> {
+    fn deserialize(payload: &[u8]) -> Result<T, NetworkMessageError> {
         let config = bincode::config::standard();
-
-        let mut result = Vec::new();
-        let mut byte_slice = bytes;
-        while byte_slice.len() > 0 {
-            match bincode::serde::decode_from_slice::<T, Configuration>(byte_slice, config) {
-                Ok((message, read_bytes)) => {
-                    result.push(message);
-                    byte_slice = &byte_slice[read_bytes..];
-                }
-                Err(e) => {
-                    // Invalid Data or buffer was not big enough
-                    error!("Was unable to decode message! {:?}", e)
-                }
-            }
+        let (message, read_bytes) = bincode::serde::decode_from_slice::<T, _>(payload, config)
+            .map_err(NetworkMessageError::Decode)?;
+        if read_bytes != payload.len() {
+            return Err(NetworkMessageError::TrailingBytes {
+                payload_size: payload.len(),
+                read_bytes,
+            });
         }
 
-        Ok(result)
+        Ok(message)
     }
 }
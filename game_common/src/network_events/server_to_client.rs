@@ -1,29 +1,47 @@
+use crate::combat_data::CombatData;
 use crate::combat_unit::{ActorId, CombatUnit, UnitId};
-use crate::player::{Player, PlayerId, ReadyState};
+use crate::player::{Player, PlayerId, ReadyState, SessionToken};
+use crate::player_resources::PlayerResources;
 use bevy::prelude::Event;
 use hexx::Hex;
 use serde::{Deserialize, Serialize};
 
-use crate::game_data::skill::{SkillId, SkillInvocationResult};
+use crate::game_data::reaction::ReactionId;
+use crate::game_data::skill::SkillId;
 use crate::game_data::unit_definition::UnitDefinition;
+use crate::game_map::{GameMap, MapHash};
+use crate::lobby::LobbyInfo;
+use crate::skill_effect::SkillEffect;
 #[cfg(feature = "test_helpers")]
 use enum_as_inner::EnumAsInner;
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 #[cfg_attr(feature = "test_helpers", derive(EnumAsInner))]
 pub enum ServerToClientMessage {
+    /// Needs to stay the first variant, so clients using another protocol version can still decode it.
+    ConnectionRefused(ConnectionRefused),
     YouConnected(YouConnected),
-    OtherPlayerConnected(OtherPlayerConnected),
-
+    PlayerDisconnected(PlayerDisconnected),
+    PlayerReconnected(PlayerReconnected),
+    ResumeGame(ResumeGame),
+    StateChecksum(StateChecksum),
+
+    LobbyList(LobbyList),
+    JoinedLobby(JoinedLobby),
+    LeftLobby(LeftLobby),
+    PlayerJoinedLobby(PlayerJoinedLobby),
+    PlayerLeftLobby(PlayerLeftLobby),
     StartGame(StartGame),
 
     LoadMap(LoadMap),
+    MapData(MapData),
     PlayerIsReady(UpdateReadyStateForPlayer),
     PlayerTurnToPlaceUnit(PlayerTurnToPlaceUnit),
     PlaceUnit(PlaceUnit),
     StartUnitTurn(StartUnitTurn),
     MoveUnit(MoveUnit),
     UseSkill(UseSkill),
+    ReactionTriggered(ReactionTriggered),
     CombatFinished(CombatFinished),
 
     ErrorWhenProcessingMessage(ErrorWhenProcessingMessage),
@@ -35,23 +53,103 @@ pub enum ServerToClientMessage {
 #[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
 pub struct StartGame {}
 
+/// Clients which don't have a map with this hash cached yet should respond with a RequestMap message.
 #[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
 pub struct LoadMap {
-    // TODO: Either send some kind of map identifier or just the entire GameMap struct
-    pub path: String,
+    pub hash: MapHash,
+}
+
+#[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
+pub struct MapData {
+    pub hash: MapHash,
+    pub map: GameMap,
+}
+
+/// Sent in response to a [crate::network_events::client_to_server::Hello] the server didn't accept.
+/// The connection gets closed right afterwards.
+#[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
+pub struct ConnectionRefused {
+    pub reason: String,
 }
 
 #[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
 pub struct YouConnected {
     pub player_id: PlayerId,
-    pub connected_players: Vec<Player>,
+    /// Needs to be sent with the next [crate::network_events::client_to_server::Hello] to rejoin as the same player.
+    pub session_token: SessionToken,
+}
+
+/// Sent to the other players in a game. Their session is kept around so they can rejoin.
+/// Disconnecting before the game started results in a [PlayerLeftLobby] instead.
+#[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
+pub struct PlayerDisconnected {
+    pub player_id: PlayerId,
+}
+
+#[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
+pub struct PlayerReconnected {
+    pub player_id: PlayerId,
+}
+
+/// Sent right after [YouConnected] to players rejoining a game which is already in progress,
+/// and as a response to clients requesting a resync.
+#[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
+pub struct ResumeGame {
+    pub player_resources: PlayerResources,
+    pub state: GameSnapshot,
+}
+
+/// Sent after every batch of updates during a game. Clients compare these with their own state once they applied
+/// the updates, and send a RequestResync if anything differs.
+#[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
+pub struct StateChecksum {
+    pub player_resources: u64,
+    /// Only set while the player is in combat.
+    pub combat_data: Option<u64>,
+}
+
+/// Everything a client needs to know about the state its player is currently in.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum GameSnapshot {
+    WaitingForOthers,
+    PickUnit {
+        units: Vec<UnitDefinition>,
+    },
+    Combat {
+        map_hash: MapHash,
+        combat_data: CombatData,
+    },
+    CombatFinished,
+}
+
+#[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
+pub struct LobbyList {
+    pub lobbies: Vec<LobbyInfo>,
 }
 
+/// Sent after creating or joining a lobby, as well as when resuming a session.
+#[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
+pub struct JoinedLobby {
+    pub lobby: LobbyInfo,
+    /// Everyone in the lobby, including the player who joined.
+    pub players: Vec<Player>,
+}
+
+#[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
+pub struct LeftLobby {}
+
+/// Sent to everyone else in the lobby.
 #[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
-pub struct OtherPlayerConnected {
+pub struct PlayerJoinedLobby {
     pub player: Player,
 }
 
+/// Sent to everyone else in the lobby.
+#[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
+pub struct PlayerLeftLobby {
+    pub player_id: PlayerId,
+}
+
 #[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
 pub struct UpdateReadyStateForPlayer {
     pub player_id: PlayerId,
@@ -87,7 +185,16 @@ pub struct MoveUnit {
 pub struct UseSkill {
     pub id: SkillId,
     pub target_coordinates: Hex,
-    pub hits: Vec<SkillInvocationResult>,
+    pub effects: Vec<SkillEffect>,
+}
+
+/// Sent once for every reaction, in the order in which they were resolved.
+#[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
+pub struct ReactionTriggered {
+    pub unit_id: UnitId,
+    pub reaction: ReactionId,
+    pub target_coordinates: Hex,
+    pub effects: Vec<SkillEffect>,
 }
 
 #[derive(Event, Serialize, Deserialize, PartialEq, Debug)]
@@ -1,26 +1,158 @@
-use bevy::prelude::warn;
-use bytes::BytesMut;
+use crate::network_events::{NetworkMessageError, FRAME_HEADER_SIZE, MAX_FRAME_SIZE};
+use bytes::{Buf, Bytes, BytesMut};
 
 const INITIAL_BUFFER_CAPACITY: usize = 2048;
-const MINIMUM_BUFFER_CAPACITY: usize = INITIAL_BUFFER_CAPACITY / 4;
-const CRITICAL_BUFFER_CAPACITY: usize = MINIMUM_BUFFER_CAPACITY / 2;
+const MINIMUM_FREE_CAPACITY: usize = INITIAL_BUFFER_CAPACITY / 4;
 
-pub fn create_buffer() -> BytesMut {
-    BytesMut::with_capacity(INITIAL_BUFFER_CAPACITY)
+/// Collects the bytes received from a stream and splits them back into the frames they were sent as.
+/// Frames may arrive split across any number of reads, or several of them within a single one.
+pub struct FrameBuffer {
+    buffer: BytesMut,
 }
 
-/// Temporary (TM) quick and easy solution to make sure we don't have to figure out how to handle incoming messages larger than the local buffer capacity for the time being.
-/// Should be called after a split() to ensure buffer.len() is 0.
-pub fn reclaim_buffer_capacity_if_necessary(buffer: &mut BytesMut) {
-    if buffer.capacity() < MINIMUM_BUFFER_CAPACITY {
-        if buffer.capacity() < CRITICAL_BUFFER_CAPACITY {
-            // If this ever gets triggered, it's time to rewrite how these buffers work or to increase INITIAL_BUFFER_SIZE.
-            warn!(
-                "Buffer capacity was below critical threshold: {} ",
-                buffer.capacity()
-            )
+impl Default for FrameBuffer {
+    fn default() -> Self {
+        FrameBuffer {
+            buffer: BytesMut::with_capacity(INITIAL_BUFFER_CAPACITY),
         }
+    }
+}
+
+impl FrameBuffer {
+    /// The buffer incoming bytes should be read into. Always has some free capacity left.
+    pub fn read_buffer(&mut self) -> &mut BytesMut {
+        if self.buffer.capacity() - self.buffer.len() < MINIMUM_FREE_CAPACITY {
+            self.buffer.reserve(INITIAL_BUFFER_CAPACITY);
+        }
+
+        &mut self.buffer
+    }
+
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Removes and returns the payload of the next complete frame, if it has been fully received yet.
+    pub fn next_frame(&mut self) -> Result<Option<Bytes>, NetworkMessageError> {
+        if self.buffer.len() < FRAME_HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let mut header = [0; FRAME_HEADER_SIZE];
+        header.copy_from_slice(&self.buffer[..FRAME_HEADER_SIZE]);
+        let size = u32::from_be_bytes(header) as usize;
+        if size > MAX_FRAME_SIZE {
+            return Err(NetworkMessageError::FrameTooLarge { size });
+        }
+
+        if self.buffer.len() < FRAME_HEADER_SIZE + size {
+            self.buffer
+                .reserve(FRAME_HEADER_SIZE + size - self.buffer.len());
+            return Ok(None);
+        }
+
+        self.buffer.advance(FRAME_HEADER_SIZE);
+        Ok(Some(self.buffer.split_to(size).freeze()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network_events::{NetworkMessage, NetworkMessageError, MAX_FRAME_SIZE};
+    use crate::network_helpers::FrameBuffer;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct TestMessage {
+        id: u32,
+        text: String,
+    }
+
+    fn create_messages(rng: &mut ChaCha8Rng) -> Vec<TestMessage> {
+        (0..20)
+            .map(|id| TestMessage {
+                id,
+                text: "x".repeat(rng.gen_range(0..5000)),
+            })
+            .collect()
+    }
+
+    fn read_all(frame_buffer: &mut FrameBuffer) -> Vec<TestMessage> {
+        let mut result = Vec::new();
+        while let Some(frame) = frame_buffer.next_frame().unwrap() {
+            result.push(TestMessage::deserialize(&frame).unwrap());
+        }
+        result
+    }
+
+    #[test]
+    fn messages_split_at_random_boundaries_should_be_reassembled() {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        for _ in 0..50 {
+            let messages = create_messages(&mut rng);
+            let stream: Vec<u8> = messages
+                .iter()
+                .flat_map(|message| message.serialize().unwrap())
+                .collect();
+
+            let mut frame_buffer = FrameBuffer::default();
+            let mut received = Vec::new();
+            let mut remaining = stream.as_slice();
+            while !remaining.is_empty() {
+                let (chunk, rest) =
+                    remaining.split_at(rng.gen_range(1..=remaining.len().min(3000)));
+                frame_buffer.extend_from_slice(chunk);
+                received.extend(read_all(&mut frame_buffer));
+                remaining = rest;
+            }
+
+            assert_eq!(messages, received);
+        }
+    }
+
+    #[test]
+    fn messages_split_into_single_bytes_should_be_reassembled() {
+        let message = TestMessage {
+            id: 1,
+            text: "Hello".into(),
+        };
+
+        let mut frame_buffer = FrameBuffer::default();
+        let mut received = Vec::new();
+        for byte in message.serialize().unwrap().iter() {
+            assert!(received.is_empty());
+            frame_buffer.extend_from_slice(&[*byte]);
+            received.extend(read_all(&mut frame_buffer));
+        }
+
+        assert_eq!(vec![message], received);
+    }
+
+    #[test]
+    fn frames_larger_than_maximum_should_be_rejected() {
+        let mut frame_buffer = FrameBuffer::default();
+        frame_buffer.extend_from_slice(&(MAX_FRAME_SIZE as u32 + 1).to_be_bytes());
+
+        assert!(matches!(
+            frame_buffer.next_frame(),
+            Err(NetworkMessageError::FrameTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_payloads_should_not_affect_following_frames() {
+        let message = TestMessage {
+            id: 1,
+            text: "Hello".into(),
+        };
+
+        let mut frame_buffer = FrameBuffer::default();
+        frame_buffer.extend_from_slice(&[0, 0, 0, 2, 0xFF, 0xFF]);
+        frame_buffer.extend_from_slice(&message.serialize().unwrap());
 
-        buffer.reserve(INITIAL_BUFFER_CAPACITY);
+        let invalid_frame = frame_buffer.next_frame().unwrap().unwrap();
+        assert!(TestMessage::deserialize(&invalid_frame).is_err());
+        assert_eq!(vec![message], read_all(&mut frame_buffer));
     }
 }
@@ -3,6 +3,10 @@ use std::fmt::{Display, Formatter};
 
 pub type PlayerId = usize;
 
+/// Handed out by the server once a player joined. Clients use it to resume their session after losing their connection.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct SessionToken(pub u64);
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct Player {
     pub id: PlayerId,
@@ -1,10 +1,20 @@
+use crate::content_hash::content_hash;
 use crate::game_data::unit_definition::UnitDefinition;
 use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
 
 /// General Resources available to player, shared between client and server.
 /// Things in here should always be kept in sync.
-#[derive(Default)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ecs", derive(Resource))]
 pub struct PlayerResources {
     pub units: Vec<UnitDefinition>,
 }
+
+impl PlayerResources {
+    /// Sent by the server after every update, so clients can tell when they are out of sync.
+    #[must_use]
+    pub fn content_hash(&self) -> u64 {
+        content_hash(self)
+    }
+}
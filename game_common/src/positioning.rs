@@ -0,0 +1,134 @@
+use crate::combat_unit::CombatUnit;
+use crate::game_map::GameMap;
+use hexx::{EdgeDirection, Hex};
+
+/// Damage bonus per height level the attacker stands above its target, or malus if it's below.
+pub const HEIGHT_DAMAGE_MODIFIER_PER_LEVEL: i32 = 10;
+/// Upper limit for the height bonus as well as the height malus.
+pub const MAX_HEIGHT_DAMAGE_MODIFIER: i32 = 30;
+pub const SIDE_ATTACK_DAMAGE_MODIFIER: i32 = 25;
+pub const BACK_ATTACK_DAMAGE_MODIFIER: i32 = 50;
+
+/// From where an attack hits its target, relative to the direction the target is facing.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum AttackDirection {
+    Front,
+    Side,
+    Back,
+}
+
+impl AttackDirection {
+    /// Attacks from the tile the target is facing, as well as the two tiles next to it, count as frontal attacks.
+    #[must_use]
+    pub fn calculate(attacker_position: Hex, target_position: Hex, facing: EdgeDirection) -> Self {
+        if attacker_position == target_position {
+            return AttackDirection::Front;
+        }
+
+        let direction = target_position.main_direction_to(attacker_position);
+        let difference = (direction.index() as i32 - facing.index() as i32).rem_euclid(6);
+        match difference.min(6 - difference) {
+            0 | 1 => AttackDirection::Front,
+            2 => AttackDirection::Side,
+            _ => AttackDirection::Back,
+        }
+    }
+
+    #[must_use]
+    pub fn damage_modifier(&self) -> i32 {
+        match self {
+            AttackDirection::Front => 0,
+            AttackDirection::Side => SIDE_ATTACK_DAMAGE_MODIFIER,
+            AttackDirection::Back => BACK_ATTACK_DAMAGE_MODIFIER,
+        }
+    }
+}
+
+/// Percentage by which damage from `user` to `target` is modified due to their height difference and the
+/// direction from which `target` is being attacked.
+#[must_use]
+pub fn positional_damage_modifier(user: &CombatUnit, target: &CombatUnit, map: &GameMap) -> i32 {
+    let height = |hex: Hex| {
+        map.tiles
+            .get(&hex)
+            .map_or(0, |tile| tile.surface_height() as i32)
+    };
+    let height_difference = height(user.position) - height(target.position);
+    let height_modifier = (height_difference * HEIGHT_DAMAGE_MODIFIER_PER_LEVEL)
+        .clamp(-MAX_HEIGHT_DAMAGE_MODIFIER, MAX_HEIGHT_DAMAGE_MODIFIER);
+
+    let direction = AttackDirection::calculate(user.position, target.position, target.facing);
+    height_modifier + direction.damage_modifier()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::combat_unit::CombatUnit;
+    use crate::game_map::GameMap;
+    use crate::positioning::{
+        positional_damage_modifier, AttackDirection, BACK_ATTACK_DAMAGE_MODIFIER,
+        HEIGHT_DAMAGE_MODIFIER_PER_LEVEL, MAX_HEIGHT_DAMAGE_MODIFIER,
+    };
+    use hexx::{EdgeDirection, Hex};
+
+    #[test]
+    fn attack_direction_should_depend_on_facing() {
+        let target = Hex::ZERO;
+        let facing = EdgeDirection::POINTY_RIGHT;
+
+        let front = target.neighbor(facing);
+        let front_side = target.neighbor(facing.clockwise());
+        let side = target.neighbor(facing.rotate_cw(2));
+        let back = target.neighbor(facing.rotate_cw(3));
+        let other_side = target.neighbor(facing.rotate_ccw(2));
+
+        assert_eq!(
+            AttackDirection::Front,
+            AttackDirection::calculate(front, target, facing)
+        );
+        assert_eq!(
+            AttackDirection::Front,
+            AttackDirection::calculate(front_side, target, facing)
+        );
+        assert_eq!(
+            AttackDirection::Side,
+            AttackDirection::calculate(side, target, facing)
+        );
+        assert_eq!(
+            AttackDirection::Side,
+            AttackDirection::calculate(other_side, target, facing)
+        );
+        assert_eq!(
+            AttackDirection::Back,
+            AttackDirection::calculate(back, target, facing)
+        );
+    }
+
+    #[test]
+    fn height_modifier_should_be_clamped() {
+        let mut map = GameMap::new(2);
+        let user_position = Hex::new(-1, 0);
+        let target_position = Hex::new(1, 0);
+        let user = CombatUnit::create_mock(1, 1).with_position(user_position);
+        let mut target = CombatUnit::create_mock(2, 2).with_position(target_position);
+        target.facing = EdgeDirection::POINTY_LEFT;
+
+        map.tiles.get_mut(&user_position).unwrap().height += 1;
+        assert_eq!(
+            HEIGHT_DAMAGE_MODIFIER_PER_LEVEL,
+            positional_damage_modifier(&user, &target, &map)
+        );
+
+        map.tiles.get_mut(&target_position).unwrap().height += 10;
+        assert_eq!(
+            -MAX_HEIGHT_DAMAGE_MODIFIER,
+            positional_damage_modifier(&user, &target, &map)
+        );
+
+        target.facing = EdgeDirection::POINTY_RIGHT;
+        assert_eq!(
+            BACK_ATTACK_DAMAGE_MODIFIER - MAX_HEIGHT_DAMAGE_MODIFIER,
+            positional_damage_modifier(&user, &target, &map)
+        );
+    }
+}
@@ -0,0 +1,352 @@
+use crate::combat_data::CombatData;
+use crate::combat_unit::UnitId;
+use crate::game_map::{GameMap, TileData};
+use crate::status_effect::StatusEffect;
+use crate::DESYNC_TODO_MESSAGE;
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+
+/// Everything that happens when a skill is used.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillInvocationResult {
+    pub effects: Vec<SkillEffect>,
+}
+
+/// A single thing which happens as the result of a skill being used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SkillEffect {
+    PhysicalDamage {
+        target: UnitId,
+        amount: u32,
+    },
+    MagicalDamage {
+        target: UnitId,
+        amount: u32,
+    },
+    Heal {
+        target: UnitId,
+        amount: u32,
+    },
+    DrainMp {
+        user: UnitId,
+        target: UnitId,
+        amount: u32,
+    },
+    /// The target was pushed or pulled along `path`.
+    Move {
+        target: UnitId,
+        path: Vec<Hex>,
+    },
+    ApplyStatus {
+        target: UnitId,
+        status: StatusEffect,
+    },
+    /// The tile was modified, either directly or because props on it were damaged or destroyed.
+    ChangeTerrain {
+        hex: Hex,
+        tile: TileData,
+    },
+}
+
+/// Applies `effects` in order. Used by both client and server, so the results always stay in sync.
+pub fn apply_skill_effects(
+    effects: &[SkillEffect],
+    combat_data: &mut CombatData,
+    map: &mut GameMap,
+) {
+    for effect in effects {
+        match effect {
+            SkillEffect::PhysicalDamage { target, amount }
+            | SkillEffect::MagicalDamage { target, amount } => {
+                let target = combat_data
+                    .units
+                    .get_mut(target)
+                    .expect(DESYNC_TODO_MESSAGE);
+                target.hp = target.hp.saturating_sub(*amount);
+            }
+            SkillEffect::Heal { target, amount } => {
+                let target = combat_data
+                    .units
+                    .get_mut(target)
+                    .expect(DESYNC_TODO_MESSAGE);
+                target.hp = (target.hp + amount).min(target.stats_after_buffs.max_health);
+            }
+            SkillEffect::DrainMp {
+                user,
+                target,
+                amount,
+            } => {
+                let target = combat_data
+                    .units
+                    .get_mut(target)
+                    .expect(DESYNC_TODO_MESSAGE);
+                let amount = (*amount).min(target.mp);
+                target.mp -= amount;
+
+                let user = combat_data.units.get_mut(user).expect(DESYNC_TODO_MESSAGE);
+                user.mp = (user.mp + amount).min(user.stats_after_buffs.max_mana);
+            }
+            SkillEffect::Move { target, path } => {
+                let target = combat_data
+                    .units
+                    .get_mut(target)
+                    .expect(DESYNC_TODO_MESSAGE);
+                let destination = *path.last().expect(DESYNC_TODO_MESSAGE);
+                combat_data.unit_positions.remove(&target.position);
+                combat_data.unit_positions.insert(destination, target.id);
+                target.position = destination;
+                map.apply_fluid_contact(target, &path[1..]);
+            }
+            SkillEffect::ApplyStatus { target, status } => {
+                let target = combat_data
+                    .units
+                    .get_mut(target)
+                    .expect(DESYNC_TODO_MESSAGE);
+                target.apply_status_effect(status.clone());
+            }
+            SkillEffect::ChangeTerrain { hex, tile } => {
+                map.tiles.insert(*hex, tile.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::combat_data::CombatData;
+    use crate::combat_unit::CombatUnit;
+    use crate::game_data::element::{Element, Resistances};
+    use crate::game_data::prop::{PropDefinition, DEBUG_PROP_CRATE, DEBUG_PROP_CUBE};
+    use crate::game_data::skill::{
+        SkillDefinition, SkillEffectDefinition, SkillRange, SkillShape, SkillTargeting,
+        TerrainChange,
+    };
+    use crate::game_map::{Fluid, FluidKind, GameMap, PropInstance, LAVA_CONTACT_DAMAGE};
+    use crate::skill_effect::{apply_skill_effects, SkillEffect};
+    use crate::status_effect::StatusEffectKind;
+    use hexx::Hex;
+
+    const USER_ID: u32 = 1;
+    const TARGET_ID: u32 = 2;
+
+    fn create_skill(effects: Vec<SkillEffectDefinition>) -> SkillDefinition {
+        SkillDefinition {
+            id: 1,
+            name: "Test".into(),
+            mp_costs: 0,
+            targeting: SkillTargeting::MouseCursor(SkillRange {
+                min: 0,
+                max: 5,
+                max_height_difference: None,
+                requires_line_of_sight: false,
+            }),
+            shape: SkillShape::SingleTile,
+            element: None,
+            effects,
+        }
+    }
+
+    fn create_combat_data(target_position: Hex) -> CombatData {
+        CombatData::create_mock().with_units(vec![
+            CombatUnit::create_mock(USER_ID, 1).with_position(Hex::ZERO),
+            CombatUnit::create_mock(TARGET_ID, 2)
+                .with_position(target_position)
+                .with_facing(Hex::ZERO.main_direction_to(target_position).const_neg()),
+        ])
+    }
+
+    fn invoke_and_apply(
+        skill: &SkillDefinition,
+        cursor: Hex,
+        combat_data: &mut CombatData,
+        map: &mut GameMap,
+    ) -> Vec<SkillEffect> {
+        let user = &combat_data.units[&USER_ID];
+        let result = skill.invoke(user, cursor, combat_data, map);
+        apply_skill_effects(&result.effects, combat_data, map);
+        result.effects
+    }
+
+    #[test]
+    fn damage_and_heal_should_stay_within_bounds() {
+        let target_position = Hex::new(1, 0);
+        let mut map = GameMap::new(3);
+        let mut combat_data = create_combat_data(target_position);
+
+        let skill = create_skill(vec![SkillEffectDefinition::MagicalDamage { power: 100 }]);
+        invoke_and_apply(&skill, target_position, &mut combat_data, &mut map);
+        assert_eq!(0, combat_data.units[&TARGET_ID].hp);
+
+        let mut combat_data = create_combat_data(target_position);
+        combat_data.units.get_mut(&TARGET_ID).unwrap().hp = 5;
+        let skill = create_skill(vec![SkillEffectDefinition::Heal { power: 100 }]);
+        invoke_and_apply(&skill, target_position, &mut combat_data, &mut map);
+        assert_eq!(
+            combat_data.units[&TARGET_ID].stats_after_buffs.max_health,
+            combat_data.units[&TARGET_ID].hp
+        );
+    }
+
+    #[test]
+    fn drain_mp_should_move_mp_from_target_to_user() {
+        let target_position = Hex::new(1, 0);
+        let mut map = GameMap::new(3);
+        let mut combat_data = create_combat_data(target_position);
+        combat_data.units.get_mut(&USER_ID).unwrap().mp = 2;
+        combat_data.units.get_mut(&TARGET_ID).unwrap().mp = 3;
+
+        let skill = create_skill(vec![SkillEffectDefinition::DrainMp { power: 5 }]);
+        invoke_and_apply(&skill, target_position, &mut combat_data, &mut map);
+
+        assert_eq!(5, combat_data.units[&USER_ID].mp);
+        assert_eq!(0, combat_data.units[&TARGET_ID].mp);
+    }
+
+    #[test]
+    fn push_should_stop_at_walls() {
+        let target_position = Hex::new(1, 0);
+        let wall = Hex::new(3, 0);
+        let mut map = GameMap::new(3);
+        map.tiles.get_mut(&wall).unwrap().height = 5;
+        let mut combat_data = create_combat_data(target_position);
+
+        let skill = create_skill(vec![SkillEffectDefinition::Push { distance: 5 }]);
+        let effects = invoke_and_apply(&skill, target_position, &mut combat_data, &mut map);
+
+        assert_eq!(
+            vec![SkillEffect::Move {
+                target: TARGET_ID,
+                path: vec![target_position, Hex::new(2, 0)],
+            }],
+            effects
+        );
+        assert_eq!(Hex::new(2, 0), combat_data.units[&TARGET_ID].position);
+        assert_eq!(
+            Some(&TARGET_ID),
+            combat_data.unit_positions.get(&Hex::new(2, 0))
+        );
+        assert_eq!(None, combat_data.unit_positions.get(&target_position));
+    }
+
+    #[test]
+    fn pull_should_stop_next_to_user() {
+        let target_position = Hex::new(3, 0);
+        let mut map = GameMap::new(3);
+        let mut combat_data = create_combat_data(target_position);
+
+        let skill = create_skill(vec![SkillEffectDefinition::Pull { distance: 5 }]);
+        invoke_and_apply(&skill, target_position, &mut combat_data, &mut map);
+
+        assert_eq!(Hex::new(1, 0), combat_data.units[&TARGET_ID].position);
+    }
+
+    #[test]
+    fn terrain_changes_should_modify_the_map() {
+        let target_position = Hex::new(1, 0);
+        let mut map = GameMap::new(3);
+        let mut combat_data = create_combat_data(Hex::new(-1, 0));
+
+        let skill = create_skill(vec![SkillEffectDefinition::ChangeTerrain(
+            TerrainChange::Raise(2),
+        )]);
+        invoke_and_apply(&skill, target_position, &mut combat_data, &mut map);
+
+        assert_eq!(3, map.tiles[&target_position].height);
+    }
+
+    #[test]
+    fn damage_should_destroy_props() {
+        let target_position = Hex::new(1, 0);
+        let props = PropDefinition::mock_data();
+        let mut map = GameMap::new(3);
+        let tile = map.tiles.get_mut(&target_position).unwrap();
+        tile.props
+            .push(PropInstance::from_definition(&props[&DEBUG_PROP_CRATE]));
+        tile.props
+            .push(PropInstance::from_definition(&props[&DEBUG_PROP_CUBE]));
+        let mut combat_data = create_combat_data(Hex::new(-1, 0));
+
+        let skill = create_skill(vec![SkillEffectDefinition::MagicalDamage { power: 3 }]);
+        invoke_and_apply(&skill, target_position, &mut combat_data, &mut map);
+        let props_on_tile = &map.tiles[&target_position].props;
+        assert_eq!(2, props_on_tile.len());
+        assert_eq!(
+            Some(2),
+            props_on_tile[0].components.destructible.map(|x| x.hp)
+        );
+
+        invoke_and_apply(&skill, target_position, &mut combat_data, &mut map);
+        let props_on_tile = &map.tiles[&target_position].props;
+        assert_eq!(1, props_on_tile.len());
+        assert_eq!(
+            DEBUG_PROP_CUBE, props_on_tile[0].prop_id,
+            "Indestructible props should stay"
+        );
+    }
+
+    fn add_fluid(map: &mut GameMap, hex: Hex, kind: FluidKind) {
+        map.tiles.get_mut(&hex).unwrap().fluid = Some(Fluid { height: 0.5, kind });
+    }
+
+    #[test]
+    fn elemental_damage_should_consider_fluids_and_resistances() {
+        let target_position = Hex::new(1, 0);
+        let mut map = GameMap::new(3);
+        let combat_data = create_combat_data(target_position);
+        let user = &combat_data.units[&USER_ID];
+        let mut target = combat_data.units[&TARGET_ID].clone();
+
+        let mut skill = create_skill(vec![SkillEffectDefinition::MagicalDamage { power: 10 }]);
+        skill.element = Some(Element::Lightning);
+        assert_eq!(10, skill.calculate_damage(10, user, &target, &map));
+
+        add_fluid(&mut map, target_position, FluidKind::Water);
+        assert_eq!(15, skill.calculate_damage(10, user, &target, &map));
+
+        add_fluid(&mut map, Hex::ZERO, FluidKind::Water);
+        assert_eq!(
+            20,
+            skill.calculate_damage(10, user, &target, &map),
+            "Fluids below user and target should stack"
+        );
+
+        target.resistances = Resistances {
+            lightning: 50,
+            ..Default::default()
+        };
+        assert_eq!(10, skill.calculate_damage(10, user, &target, &map));
+
+        skill.element = Some(Element::Fire);
+        target.resistances.fire = -100;
+        assert_eq!(0, skill.calculate_damage(10, user, &target, &map));
+
+        skill.element = None;
+        assert_eq!(
+            10,
+            skill.calculate_damage(10, user, &target, &map),
+            "Neutral damage should ignore fluids and resistances"
+        );
+    }
+
+    #[test]
+    fn pushing_through_lava_and_slime_should_affect_target() {
+        let target_position = Hex::new(1, 0);
+        let mut map = GameMap::new(3);
+        add_fluid(&mut map, Hex::new(2, 0), FluidKind::Lava);
+        add_fluid(&mut map, Hex::new(3, 0), FluidKind::Slime);
+        let mut combat_data = create_combat_data(target_position);
+        combat_data.units.get_mut(&TARGET_ID).unwrap().hp = 20;
+
+        let skill = create_skill(vec![SkillEffectDefinition::Push { distance: 2 }]);
+        invoke_and_apply(&skill, target_position, &mut combat_data, &mut map);
+
+        let target = &combat_data.units[&TARGET_ID];
+        assert_eq!(Hex::new(3, 0), target.position);
+        assert_eq!(20 - LAVA_CONTACT_DAMAGE, target.hp);
+        assert_eq!(StatusEffectKind::Slimed, target.status_effects[0].kind);
+        assert_eq!(
+            target.base_stats.movement / 2,
+            target.stats_after_buffs.movement
+        );
+    }
+}
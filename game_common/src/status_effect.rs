@@ -0,0 +1,192 @@
+use crate::unit_stats::UnitStats;
+use serde::{Deserialize, Serialize};
+
+/// A temporary effect on a unit, usually applied through skills.
+/// Effects tick down at the end of the affected unit's turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    /// How many more turns this effect will last.
+    pub remaining_turns: u8,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum StatusEffectKind {
+    /// Deals damage at the end of each of the unit's turns.
+    Poison {
+        damage_per_turn: u32,
+    },
+    /// Increases speed by half, so the unit gets its turns more often.
+    Haste,
+    /// Decreases speed by half, so the unit gets its turns less often.
+    Slow,
+    /// The unit can neither move nor act during its turns.
+    Stun,
+    /// Covered in slime, halving the unit's movement.
+    Slimed,
+    StatModifier(StatModifier),
+}
+
+/// Flat bonus or malus to a single stat.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StatModifier {
+    pub stat: ModifiableStat,
+    pub amount: i32,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ModifiableStat {
+    Movement,
+    Jump,
+    Strength,
+    Speed,
+}
+
+impl StatusEffectKind {
+    /// Whether this is something a unit would rather not have.
+    #[must_use]
+    pub fn is_harmful(&self) -> bool {
+        match self {
+            StatusEffectKind::Poison { .. } => true,
+            StatusEffectKind::Haste => false,
+            StatusEffectKind::Slow => true,
+            StatusEffectKind::Stun => true,
+            StatusEffectKind::Slimed => true,
+            StatusEffectKind::StatModifier(modifier) => modifier.amount < 0,
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> String {
+        match self {
+            StatusEffectKind::Poison { .. } => "Poison".into(),
+            StatusEffectKind::Haste => "Haste".into(),
+            StatusEffectKind::Slow => "Slow".into(),
+            StatusEffectKind::Stun => "Stun".into(),
+            StatusEffectKind::Slimed => "Slimed".into(),
+            StatusEffectKind::StatModifier(modifier) => {
+                format!("{:?} {:+}", modifier.stat, modifier.amount)
+            }
+        }
+    }
+}
+
+/// Applies every effect in `effects` to `base_stats`.
+/// Flat modifiers are applied first, so haste, slow and slime also scale bonuses.
+#[must_use]
+pub fn calculate_stats_after_buffs(base_stats: &UnitStats, effects: &[StatusEffect]) -> UnitStats {
+    let mut result = base_stats.clone();
+    for effect in effects {
+        if let StatusEffectKind::StatModifier(modifier) = effect.kind {
+            match modifier.stat {
+                ModifiableStat::Movement => {
+                    result.movement = apply_modifier(result.movement.into(), modifier.amount)
+                        .min(u8::MAX.into()) as u8;
+                }
+                ModifiableStat::Jump => {
+                    result.jump = apply_modifier(result.jump.into(), modifier.amount)
+                        .min(u8::MAX.into()) as u8;
+                }
+                ModifiableStat::Strength => {
+                    result.strength = apply_modifier(result.strength, modifier.amount);
+                }
+                ModifiableStat::Speed => {
+                    result.speed = apply_modifier(result.speed, modifier.amount);
+                }
+            }
+        }
+    }
+
+    for effect in effects {
+        match effect.kind {
+            StatusEffectKind::Haste => result.speed += result.speed / 2,
+            StatusEffectKind::Slow => result.speed -= result.speed / 2,
+            StatusEffectKind::Slimed => result.movement /= 2,
+            _ => {}
+        }
+    }
+
+    // Turn order would stall with a speed of 0
+    result.speed = result.speed.max(1);
+    result
+}
+
+fn apply_modifier(value: u32, amount: i32) -> u32 {
+    value.saturating_add_signed(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::combat_unit::CombatUnit;
+    use crate::status_effect::{
+        calculate_stats_after_buffs, ModifiableStat, StatModifier, StatusEffect, StatusEffectKind,
+    };
+    use crate::unit_stats::UnitStats;
+
+    fn effect(kind: StatusEffectKind, remaining_turns: u8) -> StatusEffect {
+        StatusEffect {
+            kind,
+            remaining_turns,
+        }
+    }
+
+    #[test]
+    fn stats_after_buffs_should_include_all_effects() {
+        let base_stats = UnitStats::create_mock();
+        let effects = [
+            effect(
+                StatusEffectKind::StatModifier(StatModifier {
+                    stat: ModifiableStat::Speed,
+                    amount: 10,
+                }),
+                1,
+            ),
+            effect(StatusEffectKind::Haste, 1),
+            effect(
+                StatusEffectKind::StatModifier(StatModifier {
+                    stat: ModifiableStat::Movement,
+                    amount: -10,
+                }),
+                1,
+            ),
+        ];
+
+        let result = calculate_stats_after_buffs(&base_stats, &effects);
+        assert_eq!((base_stats.speed + 10) * 3 / 2, result.speed);
+        assert_eq!(0, result.movement, "Stats should never drop below 0");
+        assert_eq!(base_stats.jump, result.jump);
+
+        let result = calculate_stats_after_buffs(&base_stats, &[effect(StatusEffectKind::Slow, 1)]);
+        assert_eq!(base_stats.speed / 2, result.speed);
+    }
+
+    #[test]
+    fn applying_same_effect_twice_should_refresh_duration() {
+        let mut unit = CombatUnit::create_mock(1, 1);
+        unit.apply_status_effect(effect(StatusEffectKind::Haste, 2));
+        unit.apply_status_effect(effect(StatusEffectKind::Haste, 3));
+        unit.apply_status_effect(effect(StatusEffectKind::Haste, 1));
+
+        assert_eq!(
+            vec![effect(StatusEffectKind::Haste, 3)],
+            unit.status_effects
+        );
+        assert_eq!(unit.base_stats.speed * 3 / 2, unit.stats_after_buffs.speed);
+    }
+
+    #[test]
+    fn expired_effects_should_be_removed_on_tick() {
+        let mut unit = CombatUnit::create_mock(1, 1);
+        unit.apply_status_effect(effect(StatusEffectKind::Slow, 1));
+        unit.apply_status_effect(effect(StatusEffectKind::Poison { damage_per_turn: 4 }, 2));
+
+        unit.tick_status_effects();
+        assert_eq!(6, unit.hp);
+        assert_eq!(unit.base_stats.speed, unit.stats_after_buffs.speed);
+        assert_eq!(1, unit.status_effects.len());
+
+        unit.tick_status_effects();
+        assert_eq!(2, unit.hp);
+        assert!(unit.status_effects.is_empty());
+    }
+}
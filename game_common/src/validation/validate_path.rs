@@ -40,6 +40,10 @@ pub fn validate_path_for_current_unit(
 
     let mut total_cost = 0;
     for i in 1..path.len() {
+        if path[i - 1].unsigned_distance_to(path[i]) != 1 {
+            return Err(ValidationError::new("Path has gaps!"));
+        }
+
         match map.calculate_path_costs(unit, combat_data, &path[i - 1], &path[i]) {
             None => {
                 return Err(ValidationError::new(format!(
@@ -56,6 +60,14 @@ pub fn validate_path_for_current_unit(
         return Err(ValidationError::new("Not enough movement remaining!"));
     }
 
+    let destination = path.last().unwrap();
+    if !map.can_unit_stop_on(unit, combat_data, destination) {
+        return Err(ValidationError::new(format!(
+            "Unit cannot stop on {:?}",
+            destination
+        )));
+    }
+
     Ok(())
 }
 
@@ -63,7 +75,7 @@ pub fn validate_path_for_current_unit(
 mod tests {
     use crate::combat_data::CombatData;
     use crate::combat_unit::CombatUnit;
-    use crate::game_map::GameMap;
+    use crate::game_map::{GameMap, TileSurface};
     use crate::unit_stats::UnitStats;
     use crate::validation::validate_path_for_current_unit;
     use hexx::Hex;
@@ -107,4 +119,21 @@ mod tests {
         let result = validate_path_for_current_unit(&map, &combat_data, &path);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn path_through_expensive_terrain() {
+        let unit_pos = Hex::new(-2, 0);
+        let target_pos = Hex::new(1, 0);
+        let mut map = GameMap::new(2);
+        map.tiles.get_mut(&Hex::ZERO).unwrap().surface = TileSurface::Sand;
+        let combat_data = CombatData::create_mock()
+            .with_units(vec![CombatUnit::create_mock(1, 1)
+                .with_position(unit_pos)
+                .with_stats(UnitStats::create_mock().with_movement(3))])
+            .with_unit_turn(1);
+        let path = vec![unit_pos, Hex::new(-1, 0), Hex::ZERO, target_pos];
+
+        let result = validate_path_for_current_unit(&map, &combat_data, &path);
+        assert!(result.is_err(), "Sand should cost more than one movement");
+    }
 }
@@ -1,4 +1,5 @@
 use crate::game_data::skill::{SkillDefinition, SkillTargeting};
+use crate::game_map::GameMap;
 use crate::validation::validation_error::ValidationError;
 use hexx::Hex;
 
@@ -6,18 +7,103 @@ pub fn validate_skill_target_is_in_range(
     skill: &SkillDefinition,
     origin: Hex,
     target: Hex,
+    map: &GameMap,
 ) -> Result<(), ValidationError> {
     match &skill.targeting {
         SkillTargeting::UserPosition => Ok(()),
         SkillTargeting::MouseCursor(range) => {
+            let Some(target_tile) = map.tiles.get(&target) else {
+                return Err(ValidationError::new("Target is outside the map!"));
+            };
+
             let distance = origin.unsigned_distance_to(target);
             if distance < range.min {
-                Err(ValidationError::new("Target is too close!"))
+                return Err(ValidationError::new("Target is too close!"));
             } else if distance > range.max {
-                Err(ValidationError::new("Target is too far!"))
-            } else {
-                Ok(())
+                return Err(ValidationError::new("Target is too far!"));
+            }
+
+            if let Some(max_height_difference) = range.max_height_difference {
+                let origin_height = map
+                    .tiles
+                    .get(&origin)
+                    .map_or(0, |tile| tile.surface_height());
+                if origin_height.abs_diff(target_tile.surface_height()) > max_height_difference {
+                    return Err(ValidationError::new("Target is out of vertical reach!"));
+                }
+            }
+
+            if range.requires_line_of_sight && !map.has_line_of_sight(origin, target) {
+                return Err(ValidationError::new("Target is not in line of sight!"));
             }
+
+            Ok(())
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::game_data::skill::{
+        SkillDefinition, SkillRange, SkillTargeting, DEBUG_SINGLE_TARGET_ATTACK_ID,
+    };
+    use crate::game_data::GameData;
+    use crate::game_map::GameMap;
+    use crate::validation::validate_skill_target_is_in_range;
+    use hexx::Hex;
+
+    fn create_skill(range: SkillRange) -> SkillDefinition {
+        let mut skill = GameData::create_mock().with_all_mock_skills().skills
+            [&DEBUG_SINGLE_TARGET_ATTACK_ID]
+            .clone();
+        skill.targeting = SkillTargeting::MouseCursor(range);
+        skill
+    }
+
+    #[test]
+    fn vertical_reach_should_be_limited() {
+        let skill = create_skill(SkillRange {
+            min: 1,
+            max: 1,
+            max_height_difference: Some(2),
+            requires_line_of_sight: false,
+        });
+        let mut map = GameMap::new(2);
+        let target = Hex::new(1, 0);
+
+        map.tiles.get_mut(&target).unwrap().height = 3;
+        assert!(validate_skill_target_is_in_range(&skill, Hex::ZERO, target, &map).is_ok());
+
+        map.tiles.get_mut(&target).unwrap().height = 4;
+        assert!(validate_skill_target_is_in_range(&skill, Hex::ZERO, target, &map).is_err());
+        assert!(
+            validate_skill_target_is_in_range(&skill, target, Hex::ZERO, &map).is_err(),
+            "Reach should be limited in both directions"
+        );
+    }
+
+    #[test]
+    fn line_of_sight_should_only_matter_if_required() {
+        let mut range = SkillRange {
+            min: 1,
+            max: 4,
+            max_height_difference: None,
+            requires_line_of_sight: false,
+        };
+        let mut map = GameMap::new(2);
+        map.tiles.get_mut(&Hex::ZERO).unwrap().height = 10;
+        let origin = Hex::new(-2, 0);
+        let target = Hex::new(2, 0);
+
+        let skill = create_skill(range.clone());
+        assert!(validate_skill_target_is_in_range(&skill, origin, target, &map).is_ok());
+
+        range.requires_line_of_sight = true;
+        let skill = create_skill(range);
+        assert!(validate_skill_target_is_in_range(&skill, origin, target, &map).is_err());
+        assert!(
+            validate_skill_target_is_in_range(&skill, origin, Hex::new(5, 0), &map).is_err(),
+            "Targets outside the map should never be valid"
+        );
+    }
+}
@@ -1,17 +1,16 @@
 use crate::combat_data::CombatData;
 use crate::combat_turn::CombatTurn;
 use crate::combat_unit::ActorId;
-use crate::player::PlayerId;
 use crate::validation::validation_error::ValidationError;
 
 pub fn validate_turn_order(
-    player_id: PlayerId,
+    actor: ActorId,
     combat_data: &CombatData,
 ) -> Result<(), ValidationError> {
     match &combat_data.current_turn {
         CombatTurn::Undefined => Err(ValidationError::new("Undefined turn behaviour!")),
         CombatTurn::PlaceUnit(turn) => {
-            if turn.player_id == player_id {
+            if ActorId::Player(turn.player_id) == actor {
                 Ok(())
             } else {
                 Err(ValidationError::new("It's not your turn!"))
@@ -20,7 +19,7 @@ pub fn validate_turn_order(
         CombatTurn::UnitTurn(turn) => {
             let unit = &combat_data.units[&turn.unit_id];
 
-            if unit.owner == ActorId::Player(player_id) {
+            if unit.owner == actor {
                 Ok(())
             } else {
                 Err(ValidationError::new("It's not your turn!"))
@@ -1,10 +1,11 @@
+use crate::handshake;
 use crate::message_processor;
-use crate::message_processor::ServerToClientMessageVariant;
 use crate::shared_state::SharedState;
 use bytes::Bytes;
 use game_common::network_events::client_to_server::ClientToServerMessage;
+use game_common::network_events::server_to_client::{ConnectionRefused, ServerToClientMessage};
 use game_common::network_events::NetworkMessage;
-use game_common::network_helpers;
+use game_common::network_helpers::FrameBuffer;
 use std::error::Error;
 use std::sync::Arc;
 use tokio::io::AsyncReadExt;
@@ -28,7 +29,7 @@ async fn handle_connection_impl(
     incoming_session: IncomingSession,
     state: Arc<Mutex<SharedState>>,
 ) -> Result<(), Box<dyn Error>> {
-    let mut buffer = network_helpers::create_buffer();
+    let mut frame_buffer = FrameBuffer::default();
 
     let session_request = incoming_session.await?;
 
@@ -44,8 +45,8 @@ async fn handle_connection_impl(
     let mut client = {
         let (client_sender, client_receiver) = mpsc::unbounded_channel();
         let mut state = state.lock().await;
+        // The player gets added once the client sent a valid handshake
         state.connections.insert(connection_id, client_sender);
-        state.add_player_and_notify(connection_id);
         ConnectedClient {
             id: connection_id,
             receiver: client_receiver,
@@ -54,20 +55,32 @@ async fn handle_connection_impl(
 
     let (mut send_stream, mut receive_stream) = connection.accept_bi().await?;
 
-    loop {
+    'connection: loop {
         // TODO: Figure out if this is cancellation-safe
         tokio::select! {
             Some(msg) = client.receiver.recv() => {
                 send_stream.write_all(&msg).await?;
             }
-            result = receive_stream.read_buf(&mut buffer) => match result {
+            result = receive_stream.read_buf(frame_buffer.read_buffer()) => match result {
                 Ok(bytes) => {
                     if bytes == 0 {
                         warn!("Bytes was 0!");
                         break;
                     }
-                    process_message_from_client(Arc::clone(&state), client.id, buffer.split().freeze()).await;
-                    network_helpers::reclaim_buffer_capacity_if_necessary(&mut buffer);
+                    loop {
+                        match frame_buffer.next_frame() {
+                            Ok(Some(frame)) => {
+                                if !process_message_from_client(Arc::clone(&state), client.id, frame).await {
+                                    break 'connection;
+                                }
+                            }
+                            Ok(None) => break,
+                            Err(e) => {
+                                error!("Closing connection {}: {}", client.id, e);
+                                break 'connection;
+                            }
+                        }
+                    }
                 }
                 Err(e) => {
                     error!(
@@ -81,56 +94,76 @@ async fn handle_connection_impl(
         }
     }
 
-    state.lock().await.connections.remove(&client.id);
+    // Make sure everything which is still queued up, like a refused handshake, reaches the client
+    while let Ok(msg) = client.receiver.try_recv() {
+        if send_stream.write_all(&msg).await.is_err() {
+            break;
+        }
+    }
+    let _ = send_stream.finish().await;
+
+    state.lock().await.remove_connection(&client.id);
     info!("Connection {} has been removed.", client.id);
     Ok(())
 }
 
+/// Returns false if the connection should be closed.
 async fn process_message_from_client(
     state: Arc<Mutex<SharedState>>,
     connection_id: ConnectionId,
-    bytes: Bytes,
-) {
-    match ClientToServerMessage::deserialize(&bytes.to_vec()) {
-        Ok(messages) => {
-            let mut state = state.lock().await;
-            debug!(
-                "Received {} bytes from {}: {:?}",
-                bytes.len(),
-                connection_id,
-                messages
-            );
-            let sender = state.connection_to_player_map[&connection_id];
-            for message in messages {
-                match message_processor::process_message(&mut state, sender, message) {
-                    Ok(outgoing_messages) => {
-                        for message in outgoing_messages {
-                            debug!("Sending {:?}", message);
-                            match message {
-                                ServerToClientMessageVariant::SendToSender(message) => {
-                                    state.send_to(&connection_id, message);
-                                }
-                                ServerToClientMessageVariant::SendTo((player_id, message)) => {
-                                    let connection_id = state.player_to_connection_map[&player_id];
-                                    state.send_to(&connection_id, message);
-                                }
-                                ServerToClientMessageVariant::Broadcast(message) => {
-                                    state.broadcast(message);
-                                }
-                            }
-                        }
-                    }
-                    Err(error_message) => {
-                        state.send_to(&connection_id, error_message);
-                    }
-                }
-            }
-        }
+    frame: Bytes,
+) -> bool {
+    let message = match ClientToServerMessage::deserialize(&frame) {
+        Ok(message) => message,
         Err(e) => {
-            error!(
-                "Was unable to deserialize message from bytes! Error: {:?}",
-                e
-            )
+            error!("Was unable to deserialize message from bytes! Error: {}", e);
+            return true;
+        }
+    };
+
+    let mut state = state.lock().await;
+    debug!(
+        "Received {} bytes from {}: {:?}",
+        frame.len(),
+        connection_id,
+        message
+    );
+    let Some(sender) = state.connection_to_player_map.get(&connection_id).copied() else {
+        return process_handshake(&mut state, connection_id, message);
+    };
+
+    match message_processor::process_message(&mut state, sender, message) {
+        Ok(outgoing_messages) => state.dispatch(sender, outgoing_messages),
+        Err(error_message) => {
+            state.send_to(&connection_id, error_message);
+        }
+    }
+
+    true
+}
+
+/// Assigns a player to the connection if `message` is an acceptable [ClientToServerMessage::Hello].
+/// Returns false if the connection got refused.
+fn process_handshake(
+    state: &mut SharedState,
+    connection_id: ConnectionId,
+    message: ClientToServerMessage,
+) -> bool {
+    let result = match message {
+        ClientToServerMessage::Hello(hello) => handshake::check_hello(&hello, state.game_data_hash)
+            .and_then(|_| state.accept_client(connection_id, hello.session_token)),
+        _ => Err("Expected a handshake before any other message.".into()),
+    };
+
+    match result {
+        Ok(_) => true,
+        Err(reason) => {
+            warn!("Refusing connection {}: {}", connection_id, reason);
+            state.send_to(
+                &connection_id,
+                ServerToClientMessage::ConnectionRefused(ConnectionRefused { reason }),
+            );
+            false
         }
     }
 }
@@ -0,0 +1,57 @@
+use game_common::network_events::client_to_server::Hello;
+use game_common::network_events::PROTOCOL_VERSION;
+
+/// Checks whether a client sending `hello` is able to play on this server.
+/// Returns a reason which can be shown to the player otherwise.
+pub fn check_hello(hello: &Hello, game_data_hash: u64) -> Result<(), String> {
+    if hello.protocol_version != PROTOCOL_VERSION {
+        return Err(format!(
+            "Client uses protocol version {}, but the server expects version {}. Please update your game.",
+            hello.protocol_version, PROTOCOL_VERSION
+        ));
+    }
+
+    if hello.game_data_hash != game_data_hash {
+        return Err(format!(
+            "Client game data ({:016x}) differs from the server's ({:016x}). Please make sure both use the same data files.",
+            hello.game_data_hash, game_data_hash
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::handshake::check_hello;
+    use game_common::network_events::client_to_server::Hello;
+    use game_common::network_events::PROTOCOL_VERSION;
+
+    #[test]
+    fn matching_clients_should_be_accepted() {
+        let hello = Hello {
+            protocol_version: PROTOCOL_VERSION,
+            game_data_hash: 42,
+            session_token: None,
+        };
+
+        assert!(check_hello(&hello, 42).is_ok());
+    }
+
+    #[test]
+    fn mismatching_clients_should_be_refused() {
+        let outdated = Hello {
+            protocol_version: PROTOCOL_VERSION + 1,
+            game_data_hash: 42,
+            session_token: None,
+        };
+        let modded = Hello {
+            protocol_version: PROTOCOL_VERSION,
+            game_data_hash: 43,
+            session_token: None,
+        };
+
+        assert!(check_hello(&outdated, 42).unwrap_err().contains("protocol"));
+        assert!(check_hello(&modded, 42).unwrap_err().contains("game data"));
+    }
+}
@@ -1,8 +1,11 @@
 use crate::in_game::states::InGameState;
-use crate::shared_state::SharedState;
-use game_common::player::PlayerId;
+use crate::map_registry::MapRegistry;
+use game_common::network_events::client_to_server::ClientToServerMessage;
+use game_common::network_events::server_to_client::{GameSnapshot, ResumeGame, StateChecksum};
+use game_common::player::{Player, PlayerId};
 use game_common::player_resources::PlayerResources;
 use hashbrown::HashMap;
+use std::sync::Arc;
 
 type StateId = u32;
 
@@ -18,22 +21,26 @@ pub struct InGameData {
 
     /// Stores everything a player owns.
     pub player_resources: HashMap<PlayerId, PlayerResources>,
+
+    /// Maps to choose from whenever a new combat starts.
+    pub map_registry: Arc<MapRegistry>,
 }
 
 impl InGameData {
-    pub fn new(shared_state: &SharedState) -> Self {
+    pub fn new(players: &HashMap<PlayerId, Player>, map_registry: Arc<MapRegistry>) -> Self {
         let mut data = InGameData {
             last_unused_state_id: 0,
             states: Default::default(),
             player_states: Default::default(),
             player_resources: Default::default(),
+            map_registry,
         };
 
         let state = InGameState::StartingGame;
         let state_id = data.get_unused_state_id();
 
         data.states.insert(state_id, state);
-        for (id, _) in &shared_state.players {
+        for (id, _) in players {
             data.assign_player_state(id.clone(), state_id);
             data.player_resources
                 .insert(id.clone(), PlayerResources::default());
@@ -85,6 +92,52 @@ impl InGameData {
         }
     }
 
+    /// What an absent `player` would need to send for the game to stop waiting for them, if anything.
+    #[must_use]
+    pub fn action_awaited_from(
+        &self,
+        player: &PlayerId,
+        players: &HashMap<PlayerId, Player>,
+    ) -> Option<ClientToServerMessage> {
+        self.state_for_player(player)?
+            .action_awaited_from(player, players, &self.player_resources)
+    }
+
+    /// Everything a rejoining `player` needs to know about the state they are in.
+    #[must_use]
+    pub fn snapshot_for_player(&self, player: &PlayerId) -> GameSnapshot {
+        match self.state_for_player(player) {
+            Some(state) => state.snapshot(),
+            None => GameSnapshot::WaitingForOthers,
+        }
+    }
+
+    #[must_use]
+    pub fn resume_game_for_player(&self, player: &PlayerId) -> ResumeGame {
+        ResumeGame {
+            player_resources: self.player_resources[player].clone(),
+            state: self.snapshot_for_player(player),
+        }
+    }
+
+    /// Lets clients verify that they are still in sync with us.
+    #[must_use]
+    pub fn state_checksum_for_player(&self, player: &PlayerId) -> StateChecksum {
+        let combat_data = match self.state_for_player(player) {
+            Some(InGameState::Combat(state)) => Some(state.combat_data.content_hash()),
+            _ => None,
+        };
+
+        StateChecksum {
+            player_resources: self.player_resources[player].content_hash(),
+            combat_data,
+        }
+    }
+
+    fn state_for_player(&self, player: &PlayerId) -> Option<&InGameState> {
+        self.states.get(self.player_states.get(player)?)
+    }
+
     pub fn deconstruct_for_processing(
         &mut self,
         sender: &PlayerId,
@@ -18,6 +18,12 @@ pub fn process_message(
     players: &mut HashMap<PlayerId, Player>,
     in_game_data: &mut InGameData,
 ) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
+    if message == ClientToServerMessage::RequestResync {
+        return Ok(vec![ServerToClientMessageVariant::SendToSender(
+            ServerToClientMessage::ResumeGame(in_game_data.resume_game_for_player(&sender)),
+        )]);
+    }
+
     let (state, mut state_data) = in_game_data.deconstruct_for_processing(&sender);
     let result = state.process_message(sender, message, players, &mut state_data, game_data)?;
 
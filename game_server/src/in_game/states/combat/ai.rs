@@ -0,0 +1,416 @@
+use crate::in_game::states::combat::{
+    count_alive_units, end_turn, move_unit, use_skill, CombatState,
+};
+use crate::message_processor::ServerToClientMessageVariant;
+use game_common::combat_data::CombatData;
+use game_common::combat_turn::CombatTurn;
+use game_common::combat_unit::{ActorId, CombatUnit, UnitId};
+use game_common::game_data::skill::{SkillDefinition, SkillId, SkillTargeting};
+use game_common::game_data::GameData;
+use game_common::game_map::GameMap;
+use game_common::network_events::client_to_server;
+use game_common::skill_effect::SkillEffect;
+use game_common::validation;
+use hashbrown::HashMap;
+use hexx::Hex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tracing::error;
+
+/// Extra score for actions which would defeat a hostile unit.
+const SCORE_FOR_DEFEATING_UNIT: i64 = 10;
+/// Score for a harmful status effect on an enemy, or a helpful one on an ally.
+const SCORE_FOR_STATUS_EFFECT: i64 = 3;
+
+/// Decides what AI units do during their turns.
+/// Every decision only depends on the visible combat state and the seed, so it's reproducible in tests.
+pub struct AiController {
+    rng: StdRng,
+}
+
+/// Everything an AI unit intends to do during its turn.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AiTurnPlan {
+    pub destination: Hex,
+    pub skill: Option<(SkillId, Hex)>,
+}
+
+impl AiController {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        AiController {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Scores every combination of reachable tile, usable skill and target, picking the best one.
+    /// If no skill would hurt any enemy, the unit moves as close to the nearest enemy as it can.
+    #[must_use]
+    pub fn plan_turn(
+        &mut self,
+        combat_data: &CombatData,
+        map: &GameMap,
+        game_data: &GameData,
+    ) -> AiTurnPlan {
+        let unit = combat_data.current_turn_unit();
+        let mut destinations = map.field_of_movement(unit, combat_data);
+        destinations.push(unit.position);
+        sort_hexagons(&mut destinations);
+
+        let turn = combat_data.current_turn.as_unit_turn().unwrap();
+        let skills = if turn.remaining_actions > 0 {
+            usable_skills(unit, game_data)
+        } else {
+            Vec::new()
+        };
+
+        let mut best_score = 0;
+        let mut best_plans = Vec::new();
+        for destination in &destinations {
+            let mut simulated = combat_data.clone();
+            simulate_move(&mut simulated, unit.id, *destination);
+            let moved_unit = &simulated.units[&unit.id];
+
+            for skill in &skills {
+                for cursor in candidate_cursor_positions(skill, *destination, map) {
+                    let score = score_skill_usage(moved_unit, skill, cursor, &simulated, map);
+                    if score > best_score {
+                        best_score = score;
+                        best_plans.clear();
+                    }
+                    if score > 0 && score == best_score {
+                        best_plans.push(AiTurnPlan {
+                            destination: *destination,
+                            skill: Some((skill.id, cursor)),
+                        });
+                    }
+                }
+            }
+        }
+
+        if !best_plans.is_empty() {
+            return self.choose(best_plans);
+        }
+
+        let mut closest_distance = u32::MAX;
+        let mut closest_plans = Vec::new();
+        for destination in destinations {
+            let distance = distance_to_closest_enemy(unit, destination, combat_data);
+            if distance < closest_distance {
+                closest_distance = distance;
+                closest_plans.clear();
+            }
+            if distance == closest_distance {
+                closest_plans.push(AiTurnPlan {
+                    destination,
+                    skill: None,
+                });
+            }
+        }
+
+        self.choose(closest_plans)
+    }
+
+    fn choose(&mut self, mut plans: Vec<AiTurnPlan>) -> AiTurnPlan {
+        let index = self.rng.gen_range(0..plans.len());
+        plans.swap_remove(index)
+    }
+}
+
+#[must_use]
+pub fn is_ai_turn(combat_data: &CombatData) -> bool {
+    match &combat_data.current_turn {
+        CombatTurn::UnitTurn(turn) => combat_data.units[&turn.unit_id].owner == ActorId::AI,
+        _ => false,
+    }
+}
+
+/// Takes turns for AI units until either a player unit's turn starts or one side has been defeated.
+/// AI actions go through the same handlers and validations as player commands.
+#[must_use]
+pub fn take_ai_turns(
+    combat_state: &mut CombatState,
+    game_data: &GameData,
+) -> Vec<ServerToClientMessageVariant> {
+    let mut messages = Vec::new();
+    while is_ai_turn(&combat_state.combat_data) && !is_combat_over(&combat_state.combat_data) {
+        let plan = combat_state.ai.plan_turn(
+            &combat_state.combat_data,
+            &combat_state.loaded_map,
+            game_data,
+        );
+
+        if plan.destination != combat_state.combat_data.current_turn_unit().position {
+            match combat_state
+                .loaded_map
+                .calculate_path_for_active_unit(&combat_state.combat_data, plan.destination)
+            {
+                None => error!("AI was unable to find a path to {:?}", plan.destination),
+                Some(path) => {
+                    let message = client_to_server::MoveUnit { path };
+                    match move_unit::move_unit(ActorId::AI, message, combat_state, game_data) {
+                        Ok(mut result) => messages.append(&mut result),
+                        Err(e) => error!("AI failed to move: {:?}", e),
+                    }
+                }
+            }
+        }
+
+        // Opportunity attacks might stop units before they reach their destination
+        let reached_destination =
+            plan.destination == combat_state.combat_data.current_turn_unit().position;
+        if let Some((id, target_coordinates)) = plan.skill.filter(|_| reached_destination) {
+            let message = client_to_server::UseSkill {
+                id,
+                target_coordinates,
+            };
+            match use_skill::use_skill(ActorId::AI, message, combat_state, game_data) {
+                Ok(mut result) => messages.append(&mut result),
+                Err(e) => error!("AI failed to use skill: {:?}", e),
+            }
+        }
+
+        if is_combat_over(&combat_state.combat_data) {
+            break;
+        }
+
+        match end_turn::end_turn(ActorId::AI, combat_state) {
+            Ok(mut result) => messages.append(&mut result),
+            Err(e) => {
+                error!("AI failed to end its turn: {:?}", e);
+                break;
+            }
+        }
+    }
+
+    messages
+}
+
+fn is_combat_over(combat_data: &CombatData) -> bool {
+    let (player_units, ai_units) = count_alive_units(&combat_data.units);
+    player_units == 0 || ai_units == 0
+}
+
+fn usable_skills<'a>(unit: &CombatUnit, game_data: &'a GameData) -> Vec<&'a SkillDefinition> {
+    let mut skill_ids = unit.all_available_skills(game_data);
+    skill_ids.sort();
+    skill_ids.dedup();
+
+    skill_ids
+        .iter()
+        .map(|id| &game_data.skills[id])
+        .filter(|skill| skill.mp_costs <= unit.mp)
+        .collect()
+}
+
+/// All cursor positions which make sense for `skill` when used from `origin`.
+fn candidate_cursor_positions(skill: &SkillDefinition, origin: Hex, map: &GameMap) -> Vec<Hex> {
+    let mut result: Vec<Hex> = match &skill.targeting {
+        // The cursor only determines the direction in which the shape is rotated
+        SkillTargeting::UserPosition => origin.all_neighbors().to_vec(),
+        SkillTargeting::MouseCursor(range) => origin
+            .range(range.max)
+            .filter(|hex| {
+                validation::validate_skill_target_is_in_range(skill, origin, *hex, map).is_ok()
+            })
+            .collect(),
+    };
+
+    sort_hexagons(&mut result);
+    result
+}
+
+fn simulate_move(combat_data: &mut CombatData, unit_id: UnitId, destination: Hex) {
+    let unit = combat_data.units.get_mut(&unit_id).unwrap();
+    combat_data.unit_positions.remove(&unit.position);
+    combat_data.unit_positions.insert(destination, unit_id);
+    unit.position = destination;
+}
+
+/// Hurting enemies is good, hurting allies is bad. Healing works the other way around.
+fn score_skill_usage(
+    user: &CombatUnit,
+    skill: &SkillDefinition,
+    cursor: Hex,
+    combat_data: &CombatData,
+    map: &GameMap,
+) -> i64 {
+    let result = skill.invoke(user, cursor, combat_data, map);
+
+    let mut remaining_hp = HashMap::new();
+    let mut score = 0;
+    for effect in &result.effects {
+        // Positive values are bad for the target
+        let (target, value) = match effect {
+            SkillEffect::PhysicalDamage { target, amount }
+            | SkillEffect::MagicalDamage { target, amount } => {
+                let hp = remaining_hp
+                    .entry(*target)
+                    .or_insert(combat_data.units[target].hp);
+                let damage = (*amount).min(*hp);
+                *hp -= damage;
+
+                let mut value = damage as i64;
+                if *hp == 0 && damage > 0 {
+                    value += SCORE_FOR_DEFEATING_UNIT;
+                }
+                (*target, value)
+            }
+            SkillEffect::Heal { target, amount } => {
+                let unit = &combat_data.units[target];
+                let hp = remaining_hp.entry(*target).or_insert(unit.hp);
+                let healed = (*amount).min(unit.stats_after_buffs.max_health.saturating_sub(*hp));
+                *hp += healed;
+                (*target, -(healed as i64))
+            }
+            SkillEffect::DrainMp { target, amount, .. } => (*target, *amount as i64 / 2),
+            SkillEffect::ApplyStatus { target, status } => {
+                if status.kind.is_harmful() {
+                    (*target, SCORE_FOR_STATUS_EFFECT)
+                } else {
+                    (*target, -SCORE_FOR_STATUS_EFFECT)
+                }
+            }
+            SkillEffect::Move { .. } | SkillEffect::ChangeTerrain { .. } => continue,
+        };
+
+        if combat_data.units[&target].owner == user.owner {
+            score -= value;
+        } else {
+            score += value;
+        }
+    }
+
+    score
+}
+
+fn distance_to_closest_enemy(unit: &CombatUnit, position: Hex, combat_data: &CombatData) -> u32 {
+    combat_data
+        .units
+        .values()
+        .filter(|x| x.owner != unit.owner && !x.is_dead())
+        .map(|x| position.unsigned_distance_to(x.position))
+        .min()
+        .unwrap_or(0)
+}
+
+/// Results of HashMap iteration have no defined order, so we sort them before picking anything.
+fn sort_hexagons(hexagons: &mut [Hex]) {
+    hexagons.sort_by_key(|hex| (hex.x, hex.y));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::in_game::states::combat::ai::{take_ai_turns, AiController};
+    use crate::in_game::states::combat::CombatState;
+    use game_common::combat_data::CombatData;
+    use game_common::combat_unit::{ActorId, CombatUnit};
+    use game_common::game_data::equipment::DEBUG_SWORD_ID;
+    use game_common::game_data::skill::DEBUG_SINGLE_TARGET_ATTACK_ID;
+    use game_common::game_data::GameData;
+    use game_common::game_map::GameMap;
+    use game_common::unit_stats::UnitStats;
+    use hexx::{EdgeDirection, Hex};
+
+    const AI_UNIT_ID: u32 = 1;
+    const PLAYER_UNIT_ID: u32 = 2;
+
+    fn create_game_data() -> GameData {
+        GameData::create_mock()
+            .with_all_mock_skills()
+            .with_all_mock_equipment()
+    }
+
+    fn create_combat_state(ai_position: Hex, player_position: Hex, seed: u64) -> CombatState {
+        CombatState {
+            combat_data: CombatData::create_mock()
+                .with_units(vec![
+                    CombatUnit::create_mock(AI_UNIT_ID, 1)
+                        .with_owner(ActorId::AI)
+                        .with_position(ai_position)
+                        .with_weapon(DEBUG_SWORD_ID)
+                        .with_stats(UnitStats::create_mock().with_movement(3)),
+                    CombatUnit::create_mock(PLAYER_UNIT_ID, 1)
+                        .with_position(player_position)
+                        .with_facing(EdgeDirection::POINTY_LEFT),
+                ])
+                .with_unit_turn(AI_UNIT_ID),
+            loaded_map: GameMap::new(5),
+            ai: AiController::new(seed),
+        }
+    }
+
+    #[test]
+    fn should_attack_enemy_in_reach() {
+        let player_position = Hex::new(3, 0);
+        let mut state = create_combat_state(Hex::ZERO, player_position, 42);
+
+        let plan = state
+            .ai
+            .plan_turn(&state.combat_data, &state.loaded_map, &create_game_data());
+
+        assert_eq!(
+            Some((DEBUG_SINGLE_TARGET_ATTACK_ID, player_position)),
+            plan.skill
+        );
+        assert_eq!(1, plan.destination.unsigned_distance_to(player_position));
+    }
+
+    #[test]
+    fn should_approach_enemy_out_of_reach() {
+        let player_position = Hex::new(5, 0);
+        let mut state = create_combat_state(Hex::new(-5, 0), player_position, 42);
+
+        let plan = state
+            .ai
+            .plan_turn(&state.combat_data, &state.loaded_map, &create_game_data());
+
+        assert_eq!(None, plan.skill);
+        assert_eq!(7, plan.destination.unsigned_distance_to(player_position));
+    }
+
+    #[test]
+    fn same_seed_should_result_in_same_plans() {
+        let game_data = create_game_data();
+        for seed in 0..10 {
+            let mut a = create_combat_state(Hex::ZERO, Hex::new(3, 0), seed);
+            let mut b = create_combat_state(Hex::ZERO, Hex::new(3, 0), seed);
+
+            assert_eq!(
+                a.ai.plan_turn(&a.combat_data, &a.loaded_map, &game_data),
+                b.ai.plan_turn(&b.combat_data, &b.loaded_map, &game_data),
+            );
+        }
+    }
+
+    #[test]
+    fn taking_ai_turns_should_use_regular_commands_until_player_turn() {
+        let player_position = Hex::new(3, 0);
+        let mut state = create_combat_state(Hex::ZERO, player_position, 42);
+        let old_hp = state.combat_data.units[&PLAYER_UNIT_ID].hp;
+
+        let result = take_ai_turns(&mut state, &create_game_data());
+
+        let result: Vec<_> = result
+            .into_iter()
+            .map(|x| x.into_broadcast().unwrap())
+            .collect();
+        assert!(result[0].as_move_unit().is_some());
+        assert!(result[1].as_use_skill().is_some());
+        assert_eq!(
+            PLAYER_UNIT_ID,
+            result.last().unwrap().as_start_unit_turn().unwrap().unit_id
+        );
+
+        assert_ne!(old_hp, state.combat_data.units[&PLAYER_UNIT_ID].hp);
+        assert_eq!(
+            PLAYER_UNIT_ID,
+            state
+                .combat_data
+                .current_turn
+                .as_unit_turn()
+                .unwrap()
+                .unit_id
+        );
+    }
+}
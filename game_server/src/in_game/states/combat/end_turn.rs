@@ -1,19 +1,102 @@
 use crate::in_game::states::combat::CombatState;
 use crate::message_processor::ServerToClientMessageVariant;
+use game_common::combat_unit::ActorId;
 use game_common::network_events::server_to_client::{ServerToClientMessage, StartUnitTurn};
-use game_common::player::PlayerId;
 use game_common::validation;
 
 pub fn end_turn(
-    sender: PlayerId,
+    sender: ActorId,
     match_data: &mut CombatState,
 ) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
     validation::validate_turn_order(sender, &match_data.combat_data)?;
-    // TODO: Test
 
+    match_data.combat_data.end_unit_turn();
     let unit_id = match_data.combat_data.get_unit_for_next_turn();
     match_data.combat_data.start_unit_turn(unit_id);
     Ok(vec![ServerToClientMessageVariant::Broadcast(
         ServerToClientMessage::StartUnitTurn(StartUnitTurn { unit_id }),
     )])
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::in_game::states::combat::ai::AiController;
+    use crate::in_game::states::combat::end_turn::end_turn;
+    use crate::in_game::states::combat::CombatState;
+    use game_common::combat_data::CombatData;
+    use game_common::combat_unit::{ActorId, CombatUnit};
+    use game_common::game_map::GameMap;
+    use game_common::status_effect::{StatusEffect, StatusEffectKind};
+    use game_common::unit_stats::UnitStats;
+    use hexx::Hex;
+
+    #[test]
+    fn ending_turn_should_tick_status_effects_of_active_unit() {
+        let poisoned_id = 1;
+        let other_id = 2;
+        let mut match_data = CombatState {
+            combat_data: CombatData::create_mock()
+                .with_units(vec![
+                    CombatUnit::create_mock(poisoned_id, 1)
+                        .with_stats(UnitStats::create_mock())
+                        .with_status_effect(StatusEffect {
+                            kind: StatusEffectKind::Poison { damage_per_turn: 3 },
+                            remaining_turns: 2,
+                        }),
+                    CombatUnit::create_mock(other_id, 1)
+                        .with_position(Hex::new(1, 0))
+                        .with_stats(UnitStats::create_mock())
+                        .with_status_effect(StatusEffect {
+                            kind: StatusEffectKind::Haste,
+                            remaining_turns: 1,
+                        }),
+                ])
+                .with_unit_turn(poisoned_id),
+            loaded_map: GameMap::new(2),
+            ai: AiController::new(0),
+        };
+
+        end_turn(ActorId::Player(1), &mut match_data).unwrap();
+
+        let poisoned = &match_data.combat_data.units[&poisoned_id];
+        assert_eq!(7, poisoned.hp);
+        assert_eq!(1, poisoned.status_effects[0].remaining_turns);
+
+        let other = &match_data.combat_data.units[&other_id];
+        assert_eq!(
+            1,
+            other.status_effects.len(),
+            "Only the unit whose turn ended should be affected"
+        );
+    }
+
+    #[test]
+    fn stunned_unit_should_be_unable_to_act() {
+        let unit_id = 1;
+        let mut match_data = CombatState {
+            combat_data: CombatData::create_mock()
+                .with_units(vec![CombatUnit::create_mock(unit_id, 1)
+                    .with_stats(UnitStats::create_mock())
+                    .with_status_effect(StatusEffect {
+                        kind: StatusEffectKind::Stun,
+                        remaining_turns: 1,
+                    })])
+                .with_unit_turn(unit_id),
+            loaded_map: GameMap::new(2),
+            ai: AiController::new(0),
+        };
+
+        let turn = match_data.combat_data.current_turn.as_unit_turn().unwrap();
+        assert_eq!(0, turn.remaining_movement);
+        assert_eq!(0, turn.remaining_actions);
+
+        end_turn(ActorId::Player(1), &mut match_data).unwrap();
+
+        let turn = match_data.combat_data.current_turn.as_unit_turn().unwrap();
+        assert!(match_data.combat_data.units[&unit_id]
+            .status_effects
+            .is_empty());
+        assert_eq!(UnitStats::create_mock().movement, turn.remaining_movement);
+        assert_eq!(1, turn.remaining_actions);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::in_game::command_invocation_result::{CommandInvocationResult, StateTransition};
 use crate::in_game::in_game_data::InGameData;
+use crate::in_game::states::combat::ai::AiController;
 use crate::in_game::states::combat_finished::CombatFinishedTransition;
 use crate::in_game::states::InGameState;
 use crate::in_game::states::StateTransitionKind;
@@ -8,57 +9,99 @@ use game_common::combat_data::CombatData;
 use game_common::combat_turn::CombatTurn;
 use game_common::combat_unit::{ActorId, CombatUnit, UnitId};
 use game_common::game_data::GameData;
+use game_common::game_map::map_generator::{generate_map, MapGeneratorSettings};
+use game_common::game_map::map_validation::validate_map;
 use game_common::game_map::GameMap;
-use game_common::network_events::client_to_server::ClientToServerMessage;
+use game_common::network_events::client_to_server::{ClientToServerMessage, PlaceUnit};
 use game_common::network_events::server_to_client::{
-    CombatFinished, ErrorWhenProcessingMessage, LoadMap, ServerToClientMessage,
+    CombatFinished, LoadMap, ServerToClientMessage,
 };
-use game_common::player::{Player, PlayerId};
+use game_common::player::{Player, PlayerId, ReadyState};
 use game_common::player_resources::PlayerResources;
-use game_common::TEST_MAP_NAME;
+use game_common::validation;
 use hashbrown::HashMap;
+use rand::seq::IteratorRandom;
+use rand::Rng;
+use tracing::{error, info};
 
+pub mod ai;
 pub mod end_turn;
 pub mod finish_loading;
 pub mod move_unit;
 mod on_combat_end;
 pub mod place_unit;
+pub mod reactions;
+pub mod request_map;
+pub mod spawn_encounter;
 pub mod use_skill;
 
 pub struct CombatStateTransition {}
 pub struct CombatState {
     pub loaded_map: GameMap,
     pub combat_data: CombatData,
+    pub ai: AiController,
 }
 
 impl CombatStateTransition {
     #[must_use]
     pub fn execute(
         &self,
+        game_data: &GameData,
         in_game_data: &mut InGameData,
         affected_players: Vec<PlayerId>,
     ) -> Vec<ServerToClientMessageVariant> {
         let mut result = Vec::new();
-        let map = match GameMap::load_from_file(TEST_MAP_NAME) {
-            Ok(map) => map,
-            Err(_) => {
-                // TODO: Load a "known good" map instead, and send player the correct map id
-                result.push(ServerToClientMessageVariant::SendToSender(
-                    ServerToClientMessage::ErrorWhenProcessingMessage(ErrorWhenProcessingMessage {
-                        message: "Server failed to load map!".into(),
-                    }),
-                ));
-                GameMap::new(2)
+        let mut rng = rand::thread_rng();
+        let map = match in_game_data
+            .map_registry
+            .pick_map(affected_players.len(), &mut rng)
+        {
+            Some(map) => map.clone(),
+            None => {
+                // Encounters always place players in the first spawn zone and monsters in the second one
+                let seed = rng.gen();
+                info!(
+                    "No map in the map pool supports {} players, generating one with seed {:016x}.",
+                    affected_players.len(),
+                    seed
+                );
+                match generate_playable_map(seed) {
+                    Ok(map) => map,
+                    Err(e) => {
+                        error!("{e}");
+                        for player in affected_players {
+                            result.push(ServerToClientMessageVariant::SendTo((
+                                player,
+                                create_error_response("Unable to find a map for this combat."),
+                            )));
+                        }
+                        return result;
+                    }
+                }
             }
         };
-        let combat_data = CombatData {
+        let mut combat_data = CombatData {
             units: Default::default(),
             unit_positions: Default::default(),
             current_turn: CombatTurn::Undefined,
         };
+
+        match game_data.encounters.values().choose(&mut rng) {
+            Some(encounter) => spawn_encounter::spawn_encounter(
+                encounter,
+                &map,
+                &mut combat_data,
+                game_data,
+                &mut rng,
+            ),
+            None => error!("Unable to start combat without any encounter definitions!"),
+        }
+
+        let map_hash = map.content_hash();
         let match_data = CombatState {
             combat_data,
             loaded_map: map,
+            ai: AiController::new(rand::random()),
         };
 
         in_game_data.insert_state_for_player(affected_players[0], InGameState::Combat(match_data));
@@ -70,9 +113,7 @@ impl CombatStateTransition {
         for player in affected_players {
             result.push(ServerToClientMessageVariant::SendTo((
                 player,
-                ServerToClientMessage::LoadMap(LoadMap {
-                    path: TEST_MAP_NAME.into(),
-                }),
+                ServerToClientMessage::LoadMap(LoadMap { hash: map_hash }),
             )));
         }
 
@@ -81,6 +122,54 @@ impl CombatStateTransition {
 }
 
 impl CombatState {
+    /// What `player_id` would need to send for this combat to continue, if it is currently waiting for them.
+    /// Units are placed on the first free spawn tile, as there's nobody around to pick a better one.
+    #[must_use]
+    pub fn action_awaited_from(
+        &self,
+        player_id: &PlayerId,
+        players: &HashMap<PlayerId, Player>,
+        player_resources: &HashMap<PlayerId, PlayerResources>,
+    ) -> Option<ClientToServerMessage> {
+        if players
+            .get(player_id)
+            .is_some_and(|x| x.ready_state != ReadyState::LoadedInGame)
+        {
+            return Some(ClientToServerMessage::FinishedLoading);
+        }
+
+        match &self.combat_data.current_turn {
+            CombatTurn::PlaceUnit(turn) if &turn.player_id == player_id => {
+                let unit_id = player_resources
+                    .get(player_id)?
+                    .units
+                    .iter()
+                    .map(|x| x.id)
+                    .find(|id| !self.combat_data.units.contains_key(id))?;
+                let mut spawn_points = self.loaded_map.get_spawn_points_for_team(&1);
+                spawn_points.sort_by_key(|hex| (hex.x, hex.y));
+                let hex = spawn_points.into_iter().find(|hex| {
+                    validation::validate_unit_can_be_placed_on_tile(
+                        &self.combat_data,
+                        &unit_id,
+                        &1,
+                        hex,
+                        &self.loaded_map,
+                    )
+                    .is_ok()
+                })?;
+
+                Some(ClientToServerMessage::PlaceUnit(PlaceUnit { unit_id, hex }))
+            }
+            CombatTurn::UnitTurn(turn) => {
+                let unit = self.combat_data.units.get(&turn.unit_id)?;
+                (unit.owner == ActorId::Player(*player_id))
+                    .then_some(ClientToServerMessage::EndTurn)
+            }
+            _ => None,
+        }
+    }
+
     pub fn on_message(
         &mut self,
         sender: PlayerId,
@@ -91,18 +180,24 @@ impl CombatState {
     ) -> Result<CommandInvocationResult, ServerToClientMessage> {
         let mut check_win_conditions = true;
         let mut messages = match message {
+            ClientToServerMessage::RequestMap(message) => {
+                check_win_conditions = false;
+                request_map::request_map(message, self)
+            }
             ClientToServerMessage::FinishedLoading => {
                 check_win_conditions = false;
                 finish_loading::finish_loading(sender, players, self)
             }
-            ClientToServerMessage::EndTurn => end_turn::end_turn(sender, self),
+            ClientToServerMessage::EndTurn => end_turn::end_turn(ActorId::Player(sender), self),
             ClientToServerMessage::PlaceUnit(message) => {
                 check_win_conditions = false;
                 place_unit::place_unit(sender, message, players, player_resources, self, game_data)
             }
-            ClientToServerMessage::MoveUnit(message) => move_unit::move_unit(sender, message, self),
+            ClientToServerMessage::MoveUnit(message) => {
+                move_unit::move_unit(ActorId::Player(sender), message, self, game_data)
+            }
             ClientToServerMessage::UseSkill(message) => {
-                use_skill::use_skill(sender, message, self, game_data)
+                use_skill::use_skill(ActorId::Player(sender), message, self, game_data)
             }
             _ => Err(create_error_response(format!(
                 "Unexpected message for server state InGame: {:?}",
@@ -110,6 +205,11 @@ impl CombatState {
             ))),
         }?;
 
+        if ai::is_ai_turn(&self.combat_data) {
+            check_win_conditions = true;
+            messages.append(&mut ai::take_ai_turns(self, game_data));
+        }
+
         let mut state_transitions = Vec::new();
         if check_win_conditions {
             let (player_units, ai_units) = count_alive_units(&self.combat_data.units);
@@ -133,6 +233,22 @@ impl CombatState {
     }
 }
 
+/// Generated maps only guarantee reachability, so they still need to pass the same validation as the map pool.
+fn generate_playable_map(seed: u64) -> Result<GameMap, String> {
+    let map = generate_map(seed, &MapGeneratorSettings::default()).map_err(|e| e.to_string())?;
+    let report = validate_map(&map);
+    if report.has_errors() {
+        let errors: Vec<String> = report.errors().map(ToString::to_string).collect();
+        return Err(format!(
+            "Generated map {} is not playable: {}",
+            map.metadata.name,
+            errors.join(" ")
+        ));
+    }
+
+    Ok(map)
+}
+
 fn count_alive_units(units: &HashMap<UnitId, CombatUnit>) -> (u32, u32) {
     // TODO: right now this quick & easy approach only works in co-operative scenarios
     let mut player_units = 0;
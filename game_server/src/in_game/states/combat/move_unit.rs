@@ -1,14 +1,19 @@
-use crate::in_game::states::combat::CombatState;
+use crate::in_game::states::combat::{reactions, CombatState};
 use crate::message_processor::ServerToClientMessageVariant;
+use game_common::combat_unit::ActorId;
+use game_common::game_data::GameData;
 use game_common::network_events::server_to_client::ServerToClientMessage;
 use game_common::network_events::{client_to_server, server_to_client};
-use game_common::player::PlayerId;
 use game_common::validation;
 
+/// Moves the active unit along the given path.
+/// Units which trigger an opportunity attack stop at the tile on which they got attacked.
+/// Fluids along the path affect the unit, even if it only passes through them.
 pub fn move_unit(
-    sender: PlayerId,
+    sender: ActorId,
     message: client_to_server::MoveUnit,
     match_data: &mut CombatState,
+    game_data: &GameData,
 ) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
     validation::validate_turn_order(sender, &match_data.combat_data)?;
     validation::validate_path_for_current_unit(
@@ -17,34 +22,73 @@ pub fn move_unit(
         &message.path,
     )?;
 
+    let mut path = message.path;
+    let opportunity_attack = reactions::find_opportunity_attack(
+        match_data.combat_data.current_turn_unit(),
+        &path,
+        &match_data.loaded_map,
+        &match_data.combat_data,
+        game_data,
+    );
+    if let Some((index, _)) = opportunity_attack {
+        path.truncate(index + 1);
+    }
+
+    let cost = match_data
+        .loaded_map
+        .calculate_total_path_cost(
+            match_data.combat_data.current_turn_unit(),
+            &match_data.combat_data,
+            &path,
+        )
+        .unwrap();
     let turn = match_data
         .combat_data
         .current_turn
         .as_unit_turn_mut()
         .unwrap();
-    turn.remaining_movement -= message.path.len() as u8 - 1;
+    turn.remaining_movement -= cost as u8;
 
     let unit = match_data.combat_data.units.get_mut(&turn.unit_id).unwrap();
 
     match_data.combat_data.unit_positions.remove(&unit.position);
-    unit.position = message.path.last().unwrap().clone();
+    unit.position = *path.last().unwrap();
+    unit.face_along_path(&path);
+    match_data.loaded_map.apply_fluid_contact(unit, &path[1..]);
+    let unit_id = unit.id;
     match_data
         .combat_data
         .unit_positions
-        .insert(unit.position, unit.id);
+        .insert(unit.position, unit_id);
+
+    let mut messages = Vec::new();
+    // Interrupted paths might consist of just the starting tile, at which point nothing moved
+    if path.len() > 1 {
+        messages.push(ServerToClientMessageVariant::Broadcast(
+            ServerToClientMessage::MoveUnit(server_to_client::MoveUnit { path }),
+        ));
+    }
+    if let Some((_, attacker)) = opportunity_attack {
+        messages.extend(reactions::resolve_reaction(
+            attacker, unit_id, match_data, game_data,
+        ));
+    }
 
-    Ok(vec![ServerToClientMessageVariant::Broadcast(
-        ServerToClientMessage::MoveUnit(server_to_client::MoveUnit { path: message.path }),
-    )])
+    Ok(messages)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::in_game::states::combat::ai::AiController;
     use crate::in_game::states::combat::move_unit::move_unit;
     use crate::in_game::states::combat::CombatState;
     use game_common::combat_data::CombatData;
-    use game_common::combat_unit::CombatUnit;
-    use game_common::game_map::GameMap;
+    use game_common::combat_unit::{ActorId, CombatUnit};
+    use game_common::game_data::element::Resistances;
+    use game_common::game_data::movement_type::MovementType;
+    use game_common::game_data::reaction::DEBUG_OPPORTUNITY_ATTACK_ID;
+    use game_common::game_data::GameData;
+    use game_common::game_map::{Fluid, FluidKind, GameMap, LAVA_CONTACT_DAMAGE};
     use game_common::network_events::client_to_server::MoveUnit;
     use game_common::unit_stats::UnitStats;
     use hexx::{EdgeDirection, Hex};
@@ -61,12 +105,19 @@ mod tests {
                     .with_stats(UnitStats::create_mock().with_movement(unit_movement))])
                 .with_unit_turn(unit_id),
             loaded_map: GameMap::new(2),
+            ai: AiController::new(0),
         };
 
         let path = vec![unit_start_pos, unit_new_pos];
         let message = MoveUnit { path: path.clone() };
 
-        let result = move_unit(1, message, &mut match_data).unwrap();
+        let result = move_unit(
+            ActorId::Player(1),
+            message,
+            &mut match_data,
+            &GameData::create_mock(),
+        )
+        .unwrap();
         assert_eq!(1, result.len());
         let result = result[0].as_broadcast().unwrap().as_move_unit().unwrap();
         assert_eq!(path, result.path);
@@ -85,4 +136,175 @@ mod tests {
             unit_movement - 1
         );
     }
+
+    #[test]
+    fn leaving_range_of_opportunity_attack_should_interrupt_movement() {
+        let unit_id = 1;
+        let enemy_id = 2;
+        let unit_movement = 5;
+        let game_data = GameData::create_mock()
+            .with_all_mock_skills()
+            .with_all_mock_reactions();
+        let mut match_data = CombatState {
+            combat_data: CombatData::create_mock()
+                .with_units(vec![
+                    CombatUnit::create_mock(unit_id, 1)
+                        .with_stats(UnitStats::create_mock().with_movement(unit_movement)),
+                    CombatUnit::create_mock(enemy_id, 2)
+                        .with_position(Hex::new(1, -1))
+                        .with_reaction(DEBUG_OPPORTUNITY_ATTACK_ID),
+                ])
+                .with_unit_turn(unit_id),
+            loaded_map: GameMap::new(4),
+            ai: AiController::new(0),
+        };
+
+        let path = vec![Hex::ZERO, Hex::new(1, 0), Hex::new(2, 0), Hex::new(3, 0)];
+        let result = move_unit(
+            ActorId::Player(1),
+            MoveUnit { path: path.clone() },
+            &mut match_data,
+            &game_data,
+        )
+        .unwrap();
+
+        assert_eq!(2, result.len());
+        let moved = result[0].as_broadcast().unwrap().as_move_unit().unwrap();
+        assert_eq!(path[0..2], moved.path);
+        let reaction = result[1]
+            .as_broadcast()
+            .unwrap()
+            .as_reaction_triggered()
+            .unwrap();
+        assert_eq!(enemy_id, reaction.unit_id);
+        assert_eq!(path[1], reaction.target_coordinates);
+
+        let unit = &match_data.combat_data.units[&unit_id];
+        assert_eq!(path[1], unit.position);
+        assert!(unit.hp < 10);
+        assert!(!match_data.combat_data.units[&enemy_id].is_reaction_ready);
+        assert_eq!(
+            unit_movement - 1,
+            match_data
+                .combat_data
+                .current_turn
+                .as_unit_turn()
+                .unwrap()
+                .remaining_movement
+        );
+    }
+
+    #[test]
+    fn leaving_vertical_reach_of_opportunity_attack_should_interrupt_movement() {
+        let unit_id = 1;
+        let enemy_id = 2;
+        let mut loaded_map = GameMap::new(4);
+        loaded_map.tiles.get_mut(&Hex::new(1, 0)).unwrap().height = 4;
+        let mut match_data = CombatState {
+            combat_data: CombatData::create_mock()
+                .with_units(vec![
+                    CombatUnit::create_mock(unit_id, 1)
+                        .with_stats(UnitStats::create_mock().with_movement(5)),
+                    CombatUnit::create_mock(enemy_id, 2)
+                        .with_position(Hex::new(1, -1))
+                        .with_reaction(DEBUG_OPPORTUNITY_ATTACK_ID),
+                ])
+                .with_unit_turn(unit_id),
+            loaded_map,
+            ai: AiController::new(0),
+        };
+
+        let path = vec![Hex::ZERO, Hex::new(1, 0), Hex::new(2, 0)];
+        let result = move_unit(
+            ActorId::Player(1),
+            MoveUnit { path: path.clone() },
+            &mut match_data,
+            &GameData::create_mock()
+                .with_all_mock_skills()
+                .with_all_mock_reactions(),
+        )
+        .unwrap();
+
+        // Climbing onto the hill already leaves the enemy's reach, so the unit gets attacked before taking a step
+        assert_eq!(1, result.len());
+        let reaction = result[0]
+            .as_broadcast()
+            .unwrap()
+            .as_reaction_triggered()
+            .unwrap();
+        assert_eq!(path[0], reaction.target_coordinates);
+        assert_eq!(path[0], match_data.combat_data.units[&unit_id].position);
+    }
+
+    #[test]
+    fn flying_over_the_void_should_not_be_interrupted() {
+        let unit_id = 1;
+        let enemy_id = 2;
+        let mut loaded_map = GameMap::new(4);
+        loaded_map.tiles.get_mut(&Hex::new(1, 0)).unwrap().height = 0;
+        let mut match_data = CombatState {
+            combat_data: CombatData::create_mock()
+                .with_units(vec![
+                    CombatUnit::create_mock(unit_id, 1)
+                        .with_stats(UnitStats::create_mock().with_movement(5))
+                        .with_movement_type(MovementType::Flyer),
+                    CombatUnit::create_mock(enemy_id, 2)
+                        .with_position(Hex::new(1, -1))
+                        .with_reaction(DEBUG_OPPORTUNITY_ATTACK_ID),
+                ])
+                .with_unit_turn(unit_id),
+            loaded_map,
+            ai: AiController::new(0),
+        };
+
+        let path = vec![Hex::ZERO, Hex::new(1, 0), Hex::new(2, 0), Hex::new(3, 0)];
+        let result = move_unit(
+            ActorId::Player(1),
+            MoveUnit { path: path.clone() },
+            &mut match_data,
+            &GameData::create_mock()
+                .with_all_mock_skills()
+                .with_all_mock_reactions(),
+        )
+        .unwrap();
+
+        assert_eq!(1, result.len());
+        assert_eq!(path[3], match_data.combat_data.units[&unit_id].position);
+        assert!(match_data.combat_data.units[&enemy_id].is_reaction_ready);
+    }
+
+    #[test]
+    fn walking_through_lava_should_damage_unit() {
+        let unit_id = 1;
+        let lava_position = Hex::new(1, 0);
+        let mut loaded_map = GameMap::new(3);
+        loaded_map.tiles.get_mut(&lava_position).unwrap().fluid = Some(Fluid {
+            kind: FluidKind::Lava,
+            height: 0.5,
+        });
+        let mut match_data = CombatState {
+            combat_data: CombatData::create_mock()
+                .with_units(vec![CombatUnit::create_mock(unit_id, 1)
+                    .with_stats(UnitStats::create_mock().with_movement(3))
+                    .with_resistances(Resistances {
+                        fire: 50,
+                        ..Default::default()
+                    })])
+                .with_unit_turn(unit_id),
+            loaded_map,
+            ai: AiController::new(0),
+        };
+
+        let path = vec![Hex::ZERO, lava_position, Hex::new(2, 0)];
+        move_unit(
+            ActorId::Player(1),
+            MoveUnit { path },
+            &mut match_data,
+            &GameData::create_mock(),
+        )
+        .unwrap();
+
+        let unit = &match_data.combat_data.units[&unit_id];
+        assert_eq!(10 - LAVA_CONTACT_DAMAGE / 2, unit.hp);
+    }
 }
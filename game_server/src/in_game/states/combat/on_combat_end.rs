@@ -6,7 +6,7 @@ use game_common::combat_unit::{ActorId, UnitId};
 use game_common::game_data::level::LevelUp::LevelUp;
 use game_common::game_data::GameData;
 use game_common::network_events::server_to_client::{CombatFinished, ServerToClientMessage};
-use game_common::player::{Player, PlayerId};
+use game_common::player::{Player, PlayerId, ReadyState};
 use game_common::player_resources::PlayerResources;
 use hashbrown::HashMap;
 
@@ -63,6 +63,11 @@ pub fn on_combat_end(
         }
     }
 
+    // Everyone needs to finish loading the next combat again
+    for player in players.values_mut() {
+        player.ready_state = ReadyState::NotReady;
+    }
+
     let mut messages = Vec::new();
     for player_id in players_in_state {
         messages.push(ServerToClientMessageVariant::SendTo((
@@ -11,6 +11,7 @@ use game_common::player::{Player, PlayerId};
 use game_common::player_resources::PlayerResources;
 use game_common::validation;
 use hashbrown::HashMap;
+use hexx::Hex;
 
 pub fn place_unit(
     sender: PlayerId,
@@ -20,7 +21,7 @@ pub fn place_unit(
     combat_state: &mut CombatState,
     game_data: &GameData,
 ) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
-    validation::validate_turn_order(sender, &combat_state.combat_data)?;
+    validation::validate_turn_order(ActorId::Player(sender), &combat_state.combat_data)?;
     let unit = validation::validate_player_owns_resource_unit_with_id(
         sender,
         message.unit_id,
@@ -37,6 +38,8 @@ pub fn place_unit(
 
     let mut unit = CombatUnit::from_unit_definition(unit, game_data);
     unit.position = message.hex;
+    // Spawn zones are at the edges of the map, so this faces units towards the enemy
+    unit.turn_towards(Hex::ZERO);
     combat_state
         .combat_data
         .unit_positions
@@ -45,7 +48,13 @@ pub fn place_unit(
 
     // TODO: start combat when X units have been placed by each player instead of doing... this
     // TODO: Also make sure that the user actually has any more units to place now that we can remove them
-    let next = if combat_state.combat_data.units.len() >= (3 * players.len() + 2) {
+    let placed_player_units = combat_state
+        .combat_data
+        .units
+        .values()
+        .filter(|x| x.owner != ActorId::AI)
+        .count();
+    let next = if placed_player_units >= 3 * players.len() {
         let unit_id = combat_state.combat_data.get_unit_for_next_turn();
         combat_state.combat_data.start_unit_turn(unit_id);
         ServerToClientMessageVariant::Broadcast(ServerToClientMessage::StartUnitTurn(
@@ -0,0 +1,188 @@
+use crate::in_game::states::combat::CombatState;
+use crate::message_processor::ServerToClientMessageVariant;
+use game_common::combat_data::CombatData;
+use game_common::combat_unit::{CombatUnit, UnitId};
+use game_common::game_data::movement_type::MovementType;
+use game_common::game_data::reaction::{ReactionDefinition, ReactionTarget, ReactionTrigger};
+use game_common::game_data::skill::SkillTargeting;
+use game_common::game_data::GameData;
+use game_common::game_map::GameMap;
+use game_common::network_events::server_to_client::{ReactionTriggered, ServerToClientMessage};
+use game_common::skill_effect::{apply_skill_effects, SkillEffect};
+use game_common::validation;
+use hexx::Hex;
+
+/// Resolves the reactions of every unit which was hit by `effects`, in the order in which they were hit.
+/// Reactions don't cause further reactions, so there is no way for them to chain endlessly.
+#[must_use]
+pub fn react_to_skill(
+    attacker_id: UnitId,
+    effects: &[SkillEffect],
+    match_data: &mut CombatState,
+    game_data: &GameData,
+) -> Vec<ServerToClientMessageVariant> {
+    let mut hits: Vec<(UnitId, bool, bool)> = Vec::new();
+    for effect in effects {
+        let (target, is_physical) = match effect {
+            SkillEffect::PhysicalDamage { target, .. } => (*target, true),
+            SkillEffect::MagicalDamage { target, .. } => (*target, false),
+            _ => continue,
+        };
+
+        match hits.iter_mut().find(|(id, _, _)| *id == target) {
+            Some((_, physical, magical)) => {
+                *physical |= is_physical;
+                *magical |= !is_physical;
+            }
+            None => hits.push((target, is_physical, !is_physical)),
+        }
+    }
+
+    let mut messages = Vec::new();
+    for (unit_id, physical, magical) in hits {
+        let combat_data = &match_data.combat_data;
+        let attacker = &combat_data.units[&attacker_id];
+        let unit = &combat_data.units[&unit_id];
+        let Some(reaction) = ready_reaction(unit, game_data) else {
+            continue;
+        };
+
+        let is_hostile = attacker.owner != unit.owner;
+        let is_triggered = match reaction.trigger {
+            ReactionTrigger::PhysicalDamageTaken => physical && is_hostile,
+            ReactionTrigger::MagicalDamageTaken => magical && is_hostile,
+            ReactionTrigger::DamageTaken => true,
+            ReactionTrigger::EnemyLeftRange => false,
+        };
+        if !is_triggered {
+            continue;
+        }
+
+        if let Some(message) = resolve_reaction(unit_id, attacker_id, match_data, game_data) {
+            messages.push(message);
+        }
+    }
+
+    messages
+}
+
+/// Walks along `path` and finds the first step at which `mover` leaves the range of an enemy's
+/// opportunity attack. Returns the index of the tile on which the mover gets attacked and the attacker.
+/// Teleporters vanish instead of walking away, so they never provoke opportunity attacks.
+#[must_use]
+pub fn find_opportunity_attack(
+    mover: &CombatUnit,
+    path: &[Hex],
+    map: &GameMap,
+    combat_data: &CombatData,
+    game_data: &GameData,
+) -> Option<(usize, UnitId)> {
+    if mover.movement_type == MovementType::Teleporter {
+        return None;
+    }
+
+    let mut reacting_units: Vec<&CombatUnit> = combat_data
+        .units
+        .values()
+        .filter(|unit| unit.owner != mover.owner)
+        .filter(|unit| {
+            ready_reaction(unit, game_data)
+                .is_some_and(|reaction| reaction.trigger == ReactionTrigger::EnemyLeftRange)
+        })
+        .collect();
+    reacting_units.sort_by_key(|unit| unit.id);
+
+    for (index, step) in path.windows(2).enumerate() {
+        // Units can't be attacked while passing through allies or over the void, as they wouldn't be able to stop there
+        if !map.can_unit_stop_on(mover, combat_data, &step[0]) {
+            continue;
+        }
+
+        for unit in &reacting_units {
+            let reaction = &game_data.reactions[&unit.reaction.unwrap()];
+            let skill = &game_data.skills[&reaction.skill];
+            if !matches!(skill.targeting, SkillTargeting::MouseCursor(_)) {
+                continue;
+            }
+
+            // Needs to match resolve_reaction, otherwise units would get stopped without being attacked
+            let is_in_range = |hex: Hex| {
+                validation::validate_skill_target_is_in_range(skill, unit.position, hex, map)
+                    .is_ok()
+            };
+            if is_in_range(step[0]) && !is_in_range(step[1]) {
+                return Some((index, unit.id));
+            }
+        }
+    }
+
+    None
+}
+
+/// Uses the reaction of `unit_id` against `trigger_id`, if its skill can reach its target.
+#[must_use]
+pub fn resolve_reaction(
+    unit_id: UnitId,
+    trigger_id: UnitId,
+    match_data: &mut CombatState,
+    game_data: &GameData,
+) -> Option<ServerToClientMessageVariant> {
+    let combat_data = &match_data.combat_data;
+    let unit = &combat_data.units[&unit_id];
+    let trigger = &combat_data.units[&trigger_id];
+    let reaction = ready_reaction(unit, game_data)?;
+    let skill = &game_data.skills[&reaction.skill];
+
+    let target_coordinates = match reaction.target {
+        ReactionTarget::TriggeringUnit => {
+            if trigger.is_dead() {
+                return None;
+            }
+            trigger.position
+        }
+        ReactionTarget::User => unit.position,
+    };
+
+    validation::validate_skill_target_is_in_range(
+        skill,
+        unit.position,
+        target_coordinates,
+        &match_data.loaded_map,
+    )
+    .ok()?;
+
+    let result = skill.invoke(
+        unit,
+        target_coordinates,
+        combat_data,
+        &match_data.loaded_map,
+    );
+    apply_skill_effects(
+        &result.effects,
+        &mut match_data.combat_data,
+        &mut match_data.loaded_map,
+    );
+    let unit = match_data.combat_data.units.get_mut(&unit_id).unwrap();
+    unit.is_reaction_ready = false;
+    unit.turn_towards(target_coordinates);
+
+    Some(ServerToClientMessageVariant::Broadcast(
+        ServerToClientMessage::ReactionTriggered(ReactionTriggered {
+            unit_id,
+            reaction: reaction.id,
+            target_coordinates,
+            effects: result.effects,
+        }),
+    ))
+}
+
+fn ready_reaction<'a>(
+    unit: &CombatUnit,
+    game_data: &'a GameData,
+) -> Option<&'a ReactionDefinition> {
+    if unit.is_dead() || !unit.is_reaction_ready {
+        return None;
+    }
+
+    unit.reaction.map(|id| &game_data.reactions[&id])
+}
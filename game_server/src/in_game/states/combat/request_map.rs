@@ -0,0 +1,58 @@
+use crate::in_game::states::combat::CombatState;
+use crate::message_processor::{create_error_response, ServerToClientMessageVariant};
+use game_common::network_events::client_to_server::RequestMap;
+use game_common::network_events::server_to_client::{MapData, ServerToClientMessage};
+
+pub fn request_map(
+    message: RequestMap,
+    match_data: &CombatState,
+) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
+    let hash = match_data.loaded_map.content_hash();
+    if message.hash != hash {
+        return Err(create_error_response(format!(
+            "Requested map {} is not the current map.",
+            message.hash
+        )));
+    }
+
+    Ok(vec![ServerToClientMessageVariant::SendToSender(
+        ServerToClientMessage::MapData(MapData {
+            hash,
+            map: match_data.loaded_map.clone(),
+        }),
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::in_game::states::combat::ai::AiController;
+    use crate::in_game::states::combat::request_map::request_map;
+    use crate::in_game::states::combat::CombatState;
+    use game_common::combat_data::CombatData;
+    use game_common::game_map::{GameMap, MapHash};
+    use game_common::network_events::client_to_server::RequestMap;
+
+    #[test]
+    fn should_only_send_current_map() {
+        let map = GameMap::new(2);
+        let hash = map.content_hash();
+        let match_data = CombatState {
+            loaded_map: map.clone(),
+            combat_data: CombatData::create_mock(),
+            ai: AiController::new(0),
+        };
+
+        let result = request_map(RequestMap { hash }, &match_data).unwrap();
+        assert_eq!(1, result.len());
+        let data = result[0]
+            .as_send_to_sender()
+            .unwrap()
+            .as_map_data()
+            .unwrap();
+        assert_eq!(hash, data.hash);
+        assert_eq!(map, data.map);
+
+        let wrong_hash = MapHash(hash.0.wrapping_add(1));
+        assert!(request_map(RequestMap { hash: wrong_hash }, &match_data).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use game_common::combat_data::CombatData;
+use game_common::combat_unit::CombatUnit;
+use game_common::game_data::encounter::EncounterDefinition;
+use game_common::game_data::GameData;
+use game_common::game_map::GameMap;
+use game_common::validation;
+use hexx::Hex;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use tracing::error;
+
+/// Turns every monster listed in `encounter` into a [CombatUnit] and places it on a random free tile
+/// within its spawn zone. Monsters which don't fit into their spawn zone are left out.
+pub fn spawn_encounter(
+    encounter: &EncounterDefinition,
+    map: &GameMap,
+    combat_data: &mut CombatData,
+    game_data: &GameData,
+    rng: &mut impl Rng,
+) {
+    for group in &encounter.monsters {
+        let monster = &game_data.monsters[&group.monster_id];
+
+        let mut candidates = map.get_spawn_points_for_team(&group.spawn_zone);
+        candidates.sort_by_key(|hex| (hex.x, hex.y));
+        candidates.shuffle(rng);
+        let mut candidates = candidates.into_iter();
+
+        let mut spawned = 0;
+        while spawned < group.count {
+            let mut unit = CombatUnit::from(monster);
+            // Same checks as for units placed by players
+            let Some(position) = candidates.find(|hex| {
+                validation::validate_unit_can_be_placed_on_tile(
+                    combat_data,
+                    &unit.id,
+                    &group.spawn_zone,
+                    hex,
+                    map,
+                )
+                .is_ok()
+            }) else {
+                break;
+            };
+
+            unit.position = position;
+            unit.turn_towards(Hex::ZERO);
+            combat_data.unit_positions.insert(position, unit.id);
+            combat_data.units.insert(unit.id, unit);
+            spawned += 1;
+        }
+
+        if spawned < group.count {
+            error!(
+                "Encounter {} wants to spawn {} {} in spawn zone {}, but the map only has {} free tiles there.",
+                encounter.id,
+                group.count,
+                monster.name,
+                group.spawn_zone,
+                spawned
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::in_game::states::combat::spawn_encounter::spawn_encounter;
+    use game_common::combat_data::CombatData;
+    use game_common::combat_unit::{ActorId, CombatUnit, CombatUnitKind};
+    use game_common::game_data::encounter::DEBUG_ENCOUNTER_ID;
+    use game_common::game_data::monster::DEBUG_MONSTER_ID;
+    use game_common::game_data::prop::DEBUG_PROP_CUBE;
+    use game_common::game_data::GameData;
+    use game_common::game_map::{Fluid, FluidKind, GameMap, PropInstance};
+    use hexx::Hex;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn monsters_should_spawn_with_their_stats_inside_spawn_zone() {
+        let game_data = GameData::create_mock()
+            .with_all_mock_monsters()
+            .with_all_mock_encounters();
+        let encounter = &game_data.encounters[&DEBUG_ENCOUNTER_ID];
+        let monster = &game_data.monsters[&DEBUG_MONSTER_ID];
+
+        let mut map = GameMap::new(3);
+        let spawn_zone = encounter.monsters[0].spawn_zone;
+        let spawn_tiles = [Hex::new(0, -3), Hex::new(1, -3), Hex::new(2, -3)];
+        for hex in spawn_tiles {
+            map.tiles.get_mut(&hex).unwrap().spawn_zone = Some(spawn_zone);
+        }
+
+        let mut combat_data = CombatData::create_mock();
+        spawn_encounter(
+            encounter,
+            &map,
+            &mut combat_data,
+            &game_data,
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        assert_eq!(
+            encounter.monsters[0].count as usize,
+            combat_data.units.len()
+        );
+        for unit in combat_data.units.values() {
+            assert_eq!(ActorId::AI, unit.owner);
+            assert!(spawn_tiles.contains(&unit.position));
+            assert_eq!(
+                Some(&unit.id),
+                combat_data.unit_positions.get(&unit.position)
+            );
+            assert_eq!(monster.stats.hp as u32, unit.hp);
+            assert_eq!(monster.stats.strength as u32, unit.base_stats.strength);
+            assert_eq!(
+                monster.skills,
+                unit.all_available_skills(&game_data),
+                "Monsters should use the skills from their definition"
+            );
+
+            let CombatUnitKind::Monster(data) = &unit.kind else {
+                panic!("Monsters should be spawned as monsters!");
+            };
+            assert_eq!(DEBUG_MONSTER_ID, data.monster_id);
+        }
+    }
+
+    #[test]
+    fn monsters_should_only_spawn_where_players_could_place_units() {
+        let game_data = GameData::create_mock()
+            .with_all_mock_props()
+            .with_all_mock_monsters()
+            .with_all_mock_encounters();
+        let encounter = &game_data.encounters[&DEBUG_ENCOUNTER_ID];
+
+        let mut map = GameMap::new(3);
+        let free_tile = Hex::new(0, -3);
+        let flooded_tile = Hex::new(1, -3);
+        let blocked_tile = Hex::new(2, -3);
+        let occupied_tile = Hex::new(3, -3);
+        for hex in [free_tile, flooded_tile, blocked_tile, occupied_tile] {
+            map.tiles.get_mut(&hex).unwrap().spawn_zone = Some(encounter.monsters[0].spawn_zone);
+        }
+        map.tiles.get_mut(&flooded_tile).unwrap().fluid = Some(Fluid {
+            height: 2.0,
+            kind: FluidKind::Water,
+        });
+        map.tiles
+            .get_mut(&blocked_tile)
+            .unwrap()
+            .props
+            .push(PropInstance::from_definition(
+                &game_data.props[&DEBUG_PROP_CUBE],
+            ));
+
+        let mut combat_data = CombatData::create_mock()
+            .with_units(vec![
+                CombatUnit::create_mock(1, 1).with_position(occupied_tile)
+            ]);
+        spawn_encounter(
+            encounter,
+            &map,
+            &mut combat_data,
+            &game_data,
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        let monsters: Vec<_> = combat_data
+            .units
+            .values()
+            .filter(|x| x.owner == ActorId::AI)
+            .collect();
+        assert_eq!(1, monsters.len());
+        assert_eq!(free_tile, monsters[0].position);
+        assert_eq!(Some(&1), combat_data.unit_positions.get(&occupied_tile));
+    }
+
+    #[test]
+    fn monsters_should_not_spawn_outside_spawn_zone() {
+        let game_data = GameData::create_mock()
+            .with_all_mock_monsters()
+            .with_all_mock_encounters();
+        let encounter = &game_data.encounters[&DEBUG_ENCOUNTER_ID];
+
+        let mut map = GameMap::new(3);
+        let spawn_tile = Hex::new(0, -3);
+        map.tiles.get_mut(&spawn_tile).unwrap().spawn_zone = Some(encounter.monsters[0].spawn_zone);
+
+        let mut combat_data = CombatData::create_mock();
+        spawn_encounter(
+            encounter,
+            &map,
+            &mut combat_data,
+            &game_data,
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        assert_eq!(1, combat_data.units.len());
+        assert_eq!(
+            spawn_tile,
+            combat_data.units.values().next().unwrap().position
+        );
+    }
+}
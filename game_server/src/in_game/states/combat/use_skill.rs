@@ -1,15 +1,16 @@
-use crate::in_game::states::combat::CombatState;
+use crate::in_game::states::combat::{reactions, CombatState};
 use crate::message_processor::ServerToClientMessageVariant;
+use game_common::combat_unit::ActorId;
 use game_common::game_data::GameData;
 use game_common::network_events::server_to_client::{
     ErrorWhenProcessingMessage, ServerToClientMessage,
 };
 use game_common::network_events::{client_to_server, server_to_client};
-use game_common::player::PlayerId;
+use game_common::skill_effect::apply_skill_effects;
 use game_common::validation;
 
 pub fn use_skill(
-    sender: PlayerId,
+    sender: ActorId,
     message: client_to_server::UseSkill,
     match_data: &mut CombatState,
     game_data: &GameData,
@@ -31,42 +32,20 @@ pub fn use_skill(
         &used_skill,
         unit.position,
         message.target_coordinates,
+        &match_data.loaded_map,
     )?;
 
-    let mut hits = Vec::new();
-    let targets = used_skill
-        .get_valid_target_hexagons(
-            message.target_coordinates,
-            unit.position,
-            &match_data.loaded_map,
-        )
-        .into_iter()
-        .filter_map(
-            |hex| match match_data.combat_data.unit_positions.get(&hex) {
-                None => None,
-                Some(unit_id) => Some(unit_id),
-            },
-        );
-
-    for unit_id in targets {
-        let target = &match_data.combat_data.units[unit_id];
-        let hit = used_skill.calculate_damage(unit, target);
-        hits.push(hit);
-    }
-
-    for x in &hits {
-        let unit = match_data
-            .combat_data
-            .units
-            .get_mut(&x.target_unit_id)
-            .unwrap();
-
-        if x.physical_damage > unit.hp {
-            unit.hp = 0;
-        } else {
-            unit.hp -= x.physical_damage;
-        }
-    }
+    let result = used_skill.invoke(
+        unit,
+        message.target_coordinates,
+        &match_data.combat_data,
+        &match_data.loaded_map,
+    );
+    apply_skill_effects(
+        &result.effects,
+        &mut match_data.combat_data,
+        &mut match_data.loaded_map,
+    );
 
     match_data
         .combat_data
@@ -77,25 +56,34 @@ pub fn use_skill(
 
     let user = match_data.combat_data.units.get_mut(&unit_id).unwrap();
     user.mp -= used_skill.mp_costs;
+    user.turn_towards(message.target_coordinates);
+
+    let mut reactions = reactions::react_to_skill(unit_id, &result.effects, match_data, game_data);
 
-    return Ok(vec![ServerToClientMessageVariant::Broadcast(
+    let mut messages = vec![ServerToClientMessageVariant::Broadcast(
         ServerToClientMessage::UseSkill(server_to_client::UseSkill {
             id: message.id,
             target_coordinates: message.target_coordinates,
-            hits,
+            effects: result.effects,
         }),
-    )]);
+    )];
+    messages.append(&mut reactions);
+    Ok(messages)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::in_game::states::combat::ai::AiController;
     use crate::in_game::states::combat::use_skill::use_skill;
     use crate::in_game::states::combat::CombatState;
     use game_common::combat_data::CombatData;
-    use game_common::combat_unit::CombatUnit;
+    use game_common::combat_unit::{ActorId, CombatUnit};
+    use game_common::game_data::equipment::DEBUG_SWORD_ID;
+    use game_common::game_data::reaction::{DEBUG_COUNTERSPELL_ID, DEBUG_RETALIATE_ID};
     use game_common::game_data::GameData;
     use game_common::game_map::GameMap;
     use game_common::network_events::client_to_server;
+    use game_common::skill_effect::SkillEffect;
     use game_common::unit_stats::UnitStats;
     use hexx::{EdgeDirection, Hex};
 
@@ -105,12 +93,15 @@ mod tests {
         let target_id = 2;
         let target_position = Hex::ZERO.neighbor(EdgeDirection::POINTY_RIGHT);
         let skill_id = 1;
-        let game_data = GameData::create_mock().with_all_mock_skills();
+        let game_data = GameData::create_mock()
+            .with_all_mock_skills()
+            .with_all_mock_equipment();
         let mut match_data = CombatState {
             combat_data: CombatData::create_mock()
                 .with_units(vec![
                     CombatUnit::create_mock(user_id, 1)
                         .with_position(Hex::ZERO)
+                        .with_weapon(DEBUG_SWORD_ID)
                         .with_stats(UnitStats::create_mock()),
                     CombatUnit::create_mock(target_id, 2)
                         .with_position(target_position)
@@ -118,6 +109,7 @@ mod tests {
                 ])
                 .with_unit_turn(user_id),
             loaded_map: GameMap::new(2),
+            ai: AiController::new(0),
         };
 
         let message = client_to_server::UseSkill {
@@ -126,24 +118,21 @@ mod tests {
         };
 
         let old_hp = match_data.combat_data.units[&target_id].hp;
-        let result = use_skill(1, message, &mut match_data, &game_data).unwrap();
+        let result = use_skill(ActorId::Player(1), message, &mut match_data, &game_data).unwrap();
         assert_eq!(1, result.len());
 
         let result = result[0].as_broadcast().unwrap().as_use_skill().unwrap();
         assert_eq!(skill_id, result.id);
         assert_eq!(target_position, result.target_coordinates);
 
-        assert_eq!(1, result.hits.len());
-        let result = &result.hits[0];
-        assert_ne!(
-            0, result.physical_damage,
-            "Default skill should deal some damage"
-        );
+        assert_eq!(1, result.effects.len());
+        let SkillEffect::PhysicalDamage { target, amount } = result.effects[0] else {
+            panic!("Default skill should deal physical damage");
+        };
+        assert_eq!(target_id, target);
+        assert_ne!(0, amount, "Default skill should deal some damage");
 
-        assert_eq!(
-            old_hp - result.physical_damage,
-            match_data.combat_data.units[&target_id].hp
-        );
+        assert_eq!(old_hp - amount, match_data.combat_data.units[&target_id].hp);
 
         // TODO: check resource consumption
 
@@ -157,4 +146,72 @@ mod tests {
             0
         );
     }
+
+    #[test]
+    fn physical_attacks_should_trigger_retaliation_once() {
+        let user_id = 1;
+        let retaliating_id = 2;
+        let counterspell_id = 3;
+        let target_position = Hex::ZERO.neighbor(EdgeDirection::POINTY_RIGHT);
+        let game_data = GameData::create_mock()
+            .with_all_mock_skills()
+            .with_all_mock_equipment()
+            .with_all_mock_reactions();
+        let mut match_data = CombatState {
+            combat_data: CombatData::create_mock()
+                .with_units(vec![
+                    CombatUnit::create_mock(user_id, 1)
+                        .with_position(Hex::ZERO)
+                        .with_weapon(DEBUG_SWORD_ID)
+                        .with_stats(UnitStats::create_mock().with_health(20)),
+                    CombatUnit::create_mock(retaliating_id, 2)
+                        .with_position(target_position)
+                        .with_facing(EdgeDirection::POINTY_LEFT)
+                        .with_reaction(DEBUG_RETALIATE_ID),
+                    CombatUnit::create_mock(counterspell_id, 2)
+                        .with_position(Hex::new(0, 2))
+                        .with_reaction(DEBUG_COUNTERSPELL_ID),
+                ])
+                .with_unit_turn(user_id),
+            loaded_map: GameMap::new(3),
+            ai: AiController::new(0),
+        };
+
+        let message = client_to_server::UseSkill {
+            id: 1,
+            target_coordinates: target_position,
+        };
+        let old_hp = match_data.combat_data.units[&user_id].hp;
+        let result = use_skill(ActorId::Player(1), message, &mut match_data, &game_data).unwrap();
+
+        assert_eq!(2, result.len(), "Only the unit which got hit should react");
+        let reaction = result[1]
+            .as_broadcast()
+            .unwrap()
+            .as_reaction_triggered()
+            .unwrap();
+        assert_eq!(retaliating_id, reaction.unit_id);
+        assert_eq!(DEBUG_RETALIATE_ID, reaction.reaction);
+        assert_eq!(Hex::ZERO, reaction.target_coordinates);
+        assert!(match_data.combat_data.units[&user_id].hp < old_hp);
+        assert!(!match_data.combat_data.units[&retaliating_id].is_reaction_ready);
+        assert!(match_data.combat_data.units[&counterspell_id].is_reaction_ready);
+
+        match_data
+            .combat_data
+            .current_turn
+            .as_unit_turn_mut()
+            .unwrap()
+            .remaining_actions = 1;
+        let message = client_to_server::UseSkill {
+            id: 1,
+            target_coordinates: target_position,
+        };
+        let result = use_skill(ActorId::Player(1), message, &mut match_data, &game_data).unwrap();
+        assert_eq!(
+            1,
+            result.len(),
+            "Reactions shouldn't trigger again before recharging"
+        );
+    }
 }
@@ -12,8 +12,9 @@ use crate::in_game::states::waiting_for_others::{WaitingForOthers, WaitingForOth
 use crate::message_processor::ServerToClientMessageVariant;
 use game_common::game_data::GameData;
 use game_common::network_events::client_to_server::ClientToServerMessage;
-use game_common::network_events::server_to_client::ServerToClientMessage;
+use game_common::network_events::server_to_client::{GameSnapshot, ServerToClientMessage};
 use game_common::player::{Player, PlayerId};
+use game_common::player_resources::PlayerResources;
 use hashbrown::HashMap;
 
 pub enum StateTransitionKind {
@@ -36,7 +37,7 @@ impl StateTransitionKind {
                 transition.execute(game_data, in_game_data, affected_players)
             }
             StateTransitionKind::Combat(transition) => {
-                transition.execute(in_game_data, affected_players)
+                transition.execute(game_data, in_game_data, affected_players)
             }
             StateTransitionKind::CombatFinished(transition) => {
                 transition.execute(in_game_data, affected_players)
@@ -57,6 +58,39 @@ pub enum InGameState {
 }
 
 impl InGameState {
+    #[must_use]
+    pub fn snapshot(&self) -> GameSnapshot {
+        match self {
+            InGameState::StartingGame | InGameState::WaitingForOthers(_) => {
+                GameSnapshot::WaitingForOthers
+            }
+            InGameState::PickUnit(state) => state.snapshot(),
+            InGameState::Combat(state) => GameSnapshot::Combat {
+                map_hash: state.loaded_map.content_hash(),
+                combat_data: state.combat_data.clone(),
+            },
+            InGameState::CombatFinished(_) => GameSnapshot::CombatFinished,
+        }
+    }
+
+    /// What an absent `player_id` would need to send for the game to stop waiting for them, if anything.
+    #[must_use]
+    pub fn action_awaited_from(
+        &self,
+        player_id: &PlayerId,
+        players: &HashMap<PlayerId, Player>,
+        player_resources: &HashMap<PlayerId, PlayerResources>,
+    ) -> Option<ClientToServerMessage> {
+        match self {
+            InGameState::StartingGame | InGameState::WaitingForOthers(_) => None,
+            InGameState::PickUnit(state) => state.action_awaited(),
+            InGameState::Combat(state) => {
+                state.action_awaited_from(player_id, players, player_resources)
+            }
+            InGameState::CombatFinished(_) => Some(ClientToServerMessage::Proceed),
+        }
+    }
+
     pub fn process_message(
         &mut self,
         sender: PlayerId,
@@ -9,12 +9,13 @@ use game_common::combat_unit::get_unique_unit_id;
 use game_common::game_data::level::Level;
 use game_common::game_data::unit_definition::UnitDefinition;
 use game_common::game_data::GameData;
-use game_common::network_events::client_to_server::ClientToServerMessage;
+use game_common::network_events::client_to_server::{ClientToServerMessage, PickUnit};
 use game_common::network_events::server_to_client::{
-    AddUnit, ChooseBetweenUnits, ServerToClientMessage,
+    AddUnit, ChooseBetweenUnits, GameSnapshot, ServerToClientMessage,
 };
 use game_common::player::PlayerId;
 use hashbrown::HashMap;
+use rand::seq::IteratorRandom;
 use rand::Rng;
 
 pub struct PickUnitStateTransition {
@@ -59,6 +60,22 @@ impl PickUnitStateTransition {
 }
 
 impl PickUnitState {
+    #[must_use]
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot::PickUnit {
+            units: self.units.clone(),
+        }
+    }
+
+    /// Picks the first unit on behalf of absent players.
+    #[must_use]
+    pub fn action_awaited(&self) -> Option<ClientToServerMessage> {
+        let unit = self.units.first()?;
+        Some(ClientToServerMessage::PickUnit(PickUnit {
+            unit_id: unit.id,
+        }))
+    }
+
     pub fn on_message(
         &mut self,
         sender: PlayerId,
@@ -123,38 +140,39 @@ fn create_unit(game_data: &GameData) -> UnitDefinition {
 
     let mut rng = rand::thread_rng();
 
-    let class = {
-        let index = rng.gen_range(1..=game_data.classes.len());
-        game_data.classes.get(&index).unwrap().id
-    };
+    let class = *game_data
+        .classes
+        .keys()
+        .choose(&mut rng)
+        .expect("GameData should contain at least one class!");
     let levels = HashMap::from([(class, Level::default())]);
 
-    let race = {
-        let index = rng.gen_range(1..=game_data.races.len());
-        game_data.races.get(&index).unwrap().id
-    };
+    let race = *game_data
+        .races
+        .keys()
+        .choose(&mut rng)
+        .expect("GameData should contain at least one race!");
 
     let weapon = if rng.gen_bool(0.5) {
-        let index = rng.gen_range(1..=game_data.weapons.len());
-        game_data.weapons.get(&index).map(|x| x.id)
+        game_data.weapons.keys().choose(&mut rng).cloned()
     } else {
         None
     };
 
     let armor = if rng.gen_bool(0.5) {
-        let index = rng.gen_range(1..=game_data.armor.len());
-        game_data.armor.get(&index).map(|x| x.id)
+        game_data.armor.keys().choose(&mut rng).cloned()
     } else {
         None
     };
 
     let accessory = if rng.gen_bool(0.5) {
-        let index = rng.gen_range(1..=game_data.accessories.len());
-        game_data.accessories.get(&index).map(|x| x.id)
+        game_data.accessories.keys().choose(&mut rng).cloned()
     } else {
         None
     };
 
+    let reaction = game_data.reactions.keys().choose(&mut rng).cloned();
+
     UnitDefinition {
         id,
         owner: 0,
@@ -166,5 +184,6 @@ fn create_unit(game_data: &GameData) -> UnitDefinition {
         weapon,
         armor,
         accessory,
+        reaction,
     }
 }
@@ -0,0 +1,228 @@
+use crate::lobby::Lobby;
+use crate::message_processor::{create_error_response, ServerToClientMessageVariant};
+use crate::server_state::ServerState;
+use crate::shared_state::SharedState;
+use game_common::lobby::LobbyInfo;
+use game_common::network_events::client_to_server::{CreateLobby, JoinLobby};
+use game_common::network_events::server_to_client::{
+    JoinedLobby, LeftLobby, LobbyList, PlayerJoinedLobby, PlayerLeftLobby, ServerToClientMessage,
+};
+use game_common::player::{PlayerId, ReadyState};
+
+pub const MAX_LOBBY_NAME_LENGTH: usize = 32;
+
+pub fn list_lobbies(
+    shared_state: &SharedState,
+) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
+    let mut lobbies: Vec<LobbyInfo> = shared_state.lobbies.values().map(Lobby::info).collect();
+    lobbies.sort_by_key(|x| x.id);
+
+    Ok(vec![ServerToClientMessageVariant::SendToSender(
+        ServerToClientMessage::LobbyList(LobbyList { lobbies }),
+    )])
+}
+
+pub fn create_lobby(
+    shared_state: &mut SharedState,
+    sender: PlayerId,
+    message: CreateLobby,
+) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
+    let name = message.name.trim();
+    if name.is_empty() {
+        return Err(create_error_response("Lobby name must not be empty."));
+    }
+    if name.chars().count() > MAX_LOBBY_NAME_LENGTH {
+        return Err(create_error_response(format!(
+            "Lobby name must not be longer than {} characters.",
+            MAX_LOBBY_NAME_LENGTH
+        )));
+    }
+    let Some(mut player) = shared_state.players_without_lobby.remove(&sender) else {
+        return Err(create_error_response("Leave your lobby first!"));
+    };
+
+    player.ready_state = ReadyState::ReadyInLobby;
+    let lobby_id = shared_state.next_lobby_id();
+    let mut lobby = Lobby::new(lobby_id, name.to_string());
+    lobby.players.insert(sender, player.clone());
+
+    let message = ServerToClientMessage::JoinedLobby(JoinedLobby {
+        lobby: lobby.info(),
+        players: vec![player],
+    });
+    shared_state.lobbies.insert(lobby_id, lobby);
+    shared_state.player_lobbies.insert(sender, lobby_id);
+
+    Ok(vec![ServerToClientMessageVariant::SendToSender(message)])
+}
+
+pub fn join_lobby(
+    shared_state: &mut SharedState,
+    sender: PlayerId,
+    message: JoinLobby,
+) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
+    if !shared_state.players_without_lobby.contains_key(&sender) {
+        return Err(create_error_response("Leave your lobby first!"));
+    }
+    let Some(lobby) = shared_state.lobbies.get_mut(&message.lobby_id) else {
+        return Err(create_error_response(format!(
+            "Lobby {} does not exist.",
+            message.lobby_id
+        )));
+    };
+    if let ServerState::InGame(_) = lobby.state {
+        return Err(create_error_response(format!(
+            "The game in {} has already started.",
+            lobby.name
+        )));
+    }
+
+    let Some(mut player) = shared_state.players_without_lobby.remove(&sender) else {
+        return Err(create_error_response("Leave your lobby first!"));
+    };
+    player.ready_state = ReadyState::ReadyInLobby;
+    lobby.players.insert(sender, player.clone());
+    shared_state.player_lobbies.insert(sender, message.lobby_id);
+
+    let mut messages = vec![ServerToClientMessageVariant::SendToSender(
+        ServerToClientMessage::JoinedLobby(JoinedLobby {
+            lobby: lobby.info(),
+            players: lobby.players.values().cloned().collect(),
+        }),
+    )];
+    for player_id in lobby.players.keys().filter(|x| x != &&sender) {
+        messages.push(ServerToClientMessageVariant::SendTo((
+            *player_id,
+            ServerToClientMessage::PlayerJoinedLobby(PlayerJoinedLobby {
+                player: player.clone(),
+            }),
+        )));
+    }
+
+    Ok(messages)
+}
+
+pub fn leave_lobby(
+    shared_state: &mut SharedState,
+    sender: PlayerId,
+) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
+    match shared_state.lobby_of(&sender) {
+        None => return Err(create_error_response("You are not in a lobby.")),
+        Some(Lobby {
+            state: ServerState::InGame(_),
+            ..
+        }) => return Err(create_error_response("Can't leave a game in progress.")),
+        Some(_) => {}
+    }
+
+    let Some((player, remaining_players)) = shared_state.take_player_out_of_lobby(&sender) else {
+        return Err(create_error_response("You are not in a lobby."));
+    };
+    shared_state.players_without_lobby.insert(sender, player);
+
+    let mut messages = vec![ServerToClientMessageVariant::SendToSender(
+        ServerToClientMessage::LeftLobby(LeftLobby {}),
+    )];
+    for player_id in remaining_players {
+        messages.push(ServerToClientMessageVariant::SendTo((
+            player_id,
+            ServerToClientMessage::PlayerLeftLobby(PlayerLeftLobby { player_id: sender }),
+        )));
+    }
+
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lobby::lobby_management::{create_lobby, join_lobby, leave_lobby, list_lobbies};
+    use crate::map_registry::MapRegistry;
+    use crate::shared_state::SharedState;
+    use game_common::game_data::GameData;
+    use game_common::network_events::client_to_server::{CreateLobby, JoinLobby};
+    use game_common::player::PlayerId;
+
+    fn add_players(state: &mut SharedState, amount: usize) -> Vec<PlayerId> {
+        (0..amount)
+            .map(|connection_id| state.add_player_and_notify(connection_id))
+            .collect()
+    }
+
+    fn create(state: &mut SharedState, sender: PlayerId, name: &str) {
+        create_lobby(state, sender, CreateLobby { name: name.into() }).unwrap();
+    }
+
+    #[test]
+    fn created_lobbies_should_be_listed() {
+        let mut state = SharedState::new(GameData::create_mock(), MapRegistry::default());
+        let players = add_players(&mut state, 2);
+        create(&mut state, players[0], "  First  ");
+        create(&mut state, players[1], "Second");
+
+        let messages = list_lobbies(&state).unwrap();
+        let lobbies = &messages[0].as_send_to_sender().unwrap();
+        let lobbies = &lobbies.as_lobby_list().unwrap().lobbies;
+        let names: Vec<&str> = lobbies.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(vec!["First", "Second"], names);
+        assert!(lobbies.iter().all(|x| x.player_count == 1 && !x.in_game));
+    }
+
+    #[test]
+    fn invalid_lobby_names_should_be_rejected() {
+        let mut state = SharedState::new(GameData::create_mock(), MapRegistry::default());
+        let player = add_players(&mut state, 1)[0];
+
+        assert!(create_lobby(&mut state, player, CreateLobby { name: "   ".into() }).is_err());
+        let name = "x".repeat(33);
+        assert!(create_lobby(&mut state, player, CreateLobby { name }).is_err());
+        assert!(state.lobbies.is_empty());
+        assert!(state.players_without_lobby.contains_key(&player));
+    }
+
+    #[test]
+    fn joining_should_notify_everyone_else_in_the_lobby() {
+        let mut state = SharedState::new(GameData::create_mock(), MapRegistry::default());
+        let players = add_players(&mut state, 3);
+        create(&mut state, players[0], "Lobby");
+        let lobby_id = state.player_lobbies[&players[0]];
+
+        join_lobby(&mut state, players[1], JoinLobby { lobby_id }).unwrap();
+        let messages = join_lobby(&mut state, players[2], JoinLobby { lobby_id }).unwrap();
+
+        let joined = messages[0].as_send_to_sender().unwrap();
+        let joined = joined.as_joined_lobby().unwrap();
+        assert_eq!(3, joined.players.len());
+        assert_eq!(3, joined.lobby.player_count);
+        let mut notified: Vec<PlayerId> = messages[1..]
+            .iter()
+            .map(|x| x.as_send_to().unwrap().0)
+            .collect();
+        notified.sort();
+        assert_eq!(vec![players[0], players[1]], notified);
+        assert!(state.players_without_lobby.is_empty());
+    }
+
+    #[test]
+    fn lobbies_should_be_closed_once_the_last_player_leaves() {
+        let mut state = SharedState::new(GameData::create_mock(), MapRegistry::default());
+        let players = add_players(&mut state, 2);
+        create(&mut state, players[0], "Lobby");
+        let lobby_id = state.player_lobbies[&players[0]];
+        join_lobby(&mut state, players[1], JoinLobby { lobby_id }).unwrap();
+
+        let messages = leave_lobby(&mut state, players[0]).unwrap();
+        assert!(messages[0]
+            .as_send_to_sender()
+            .unwrap()
+            .as_left_lobby()
+            .is_some());
+        assert_eq!(players[1], messages[1].as_send_to().unwrap().0);
+        assert!(state.lobbies.contains_key(&lobby_id));
+
+        leave_lobby(&mut state, players[1]).unwrap();
+        assert!(state.lobbies.is_empty());
+        assert!(state.player_lobbies.is_empty());
+        assert_eq!(2, state.players_without_lobby.len());
+        assert!(leave_lobby(&mut state, players[1]).is_err());
+    }
+}
@@ -1,18 +1,58 @@
+use crate::map_registry::MapRegistry;
 use crate::message_processor::{create_error_response, ServerToClientMessageVariant};
-use crate::shared_state::SharedState;
+use crate::server_state::ServerState;
+use game_common::game_data::GameData;
+use game_common::lobby::{LobbyId, LobbyInfo};
 use game_common::network_events::client_to_server::ClientToServerMessage;
 use game_common::network_events::server_to_client::ServerToClientMessage;
-use game_common::player::PlayerId;
+use game_common::player::{Player, PlayerId};
+use hashbrown::HashMap;
+use std::sync::Arc;
 
+pub mod lobby_management;
 pub mod start_game;
 
+/// A group of players playing together. Every lobby runs its own game, independent of all others on the server.
+pub struct Lobby {
+    pub id: LobbyId,
+    pub name: String,
+    pub players: HashMap<PlayerId, Player>,
+    pub state: ServerState,
+}
+
+impl Lobby {
+    #[must_use]
+    pub fn new(id: LobbyId, name: String) -> Self {
+        Lobby {
+            id,
+            name,
+            players: Default::default(),
+            state: ServerState::Lobby,
+        }
+    }
+
+    #[must_use]
+    pub fn info(&self) -> LobbyInfo {
+        LobbyInfo {
+            id: self.id,
+            name: self.name.clone(),
+            player_count: self.players.len(),
+            in_game: matches!(self.state, ServerState::InGame(_)),
+        }
+    }
+}
+
 pub fn process_message(
-    shared_state: &mut SharedState,
     sender: PlayerId,
     message: ClientToServerMessage,
+    game_data: &GameData,
+    map_registry: &Arc<MapRegistry>,
+    lobby: &mut Lobby,
 ) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
     match message {
-        ClientToServerMessage::StartGame => start_game::start_game(shared_state, sender),
+        ClientToServerMessage::StartGame => {
+            start_game::start_game(sender, game_data, map_registry, lobby)
+        }
         _ => Err(create_error_response(format!(
             "Unexpected message for server state WaitingForConnection: {:?}",
             message
@@ -5,26 +5,31 @@ use game_common::validation;
 use crate::in_game::in_game_data::InGameData;
 use crate::in_game::states::pick_unit::PickUnitStateTransition;
 use crate::in_game::states::StateTransitionKind;
+use crate::lobby::Lobby;
+use crate::map_registry::MapRegistry;
 use crate::message_processor::ServerToClientMessageVariant;
 use crate::server_state::ServerState;
-use crate::shared_state::SharedState;
+use game_common::game_data::GameData;
+use std::sync::Arc;
 
 pub fn start_game(
-    shared_state: &mut SharedState,
     sender: PlayerId,
+    game_data: &GameData,
+    map_registry: &Arc<MapRegistry>,
+    lobby: &mut Lobby,
 ) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
-    validation::validate_player_readiness(&shared_state.players, &ReadyState::ReadyInLobby)?;
+    validation::validate_player_readiness(&lobby.players, &ReadyState::ReadyInLobby)?;
 
     let mut messages = Vec::new();
     messages.push(ServerToClientMessageVariant::Broadcast(
         ServerToClientMessage::StartGame(StartGame {}),
     ));
 
-    let mut in_game_data = InGameData::new(&shared_state);
-    for (id, _) in &shared_state.players {
+    let mut in_game_data = InGameData::new(&lobby.players, Arc::clone(map_registry));
+    for (id, _) in &lobby.players {
         let start_state = StateTransitionKind::PickUnit(PickUnitStateTransition { remaining: 3 });
         for message in start_state
-            .on_state_enter(&shared_state.game_data, &mut in_game_data, vec![id.clone()])
+            .on_state_enter(game_data, &mut in_game_data, vec![id.clone()])
             .into_iter()
         {
             if let ServerToClientMessageVariant::SendToSender(message) = message {
@@ -35,6 +40,6 @@ pub fn start_game(
         }
     }
 
-    shared_state.server_state = ServerState::InGame(in_game_data);
+    lobby.state = ServerState::InGame(in_game_data);
     Ok(messages)
 }
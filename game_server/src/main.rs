@@ -1,15 +1,21 @@
+use game_common::game_data::GameData;
 use game_common::network_events::NETWORK_IDLE_TIMEOUT;
+use game_common::MAP_DIRECTORY;
+use map_registry::MapRegistry;
 use shared_state::SharedState;
 use std::error::Error;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
-use tracing::{info, Level};
+use tracing::{error, info, Level};
 use tracing_subscriber::EnvFilter;
 use wtransport::{Endpoint, Identity, ServerConfig};
 
 mod connection_handler;
+mod handshake;
 mod in_game;
 mod lobby;
+mod map_registry;
 mod message_processor;
 mod server_state;
 mod shared_state;
@@ -18,6 +24,14 @@ mod shared_state;
 async fn main() -> Result<(), Box<dyn Error>> {
     setup_tracing();
 
+    let game_data = match GameData::load() {
+        Ok(game_data) => game_data,
+        Err(e) => {
+            error!("{}", e);
+            return Err(e.into());
+        }
+    };
+
     let port = 4433;
     let config = ServerConfig::builder()
         .with_bind_default(port)
@@ -30,7 +44,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let server = Endpoint::server(config)?;
 
-    let state = Arc::new(Mutex::new(SharedState::default()));
+    let map_registry = MapRegistry::load_from_directory(MAP_DIRECTORY);
+    info!("Loaded {} maps from {}", map_registry.len(), MAP_DIRECTORY);
+
+    let state = Arc::new(Mutex::new(SharedState::new(game_data, map_registry)));
+
+    tokio::spawn(handle_absent_players(Arc::clone(&state)));
 
     for _ in 1.. {
         let incoming_session = server.accept().await;
@@ -45,6 +64,17 @@ async fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+async fn handle_absent_players(state: Arc<Mutex<SharedState>>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        interval.tick().await;
+        let mut state = state.lock().await;
+        let now = Instant::now();
+        state.act_for_absent_players(now);
+        state.remove_abandoned_lobbies(now);
+    }
+}
+
 fn setup_tracing() {
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -0,0 +1,142 @@
+use game_common::game_map::map_validation::validate_map;
+use game_common::game_map::GameMap;
+use game_common::MAP_FILE_EXTENSION;
+use rand::seq::IteratorRandom;
+use rand::Rng;
+use std::fs;
+use std::path::Path;
+use tracing::{error, warn};
+
+/// The pool of maps the server picks from whenever a new combat starts.
+#[derive(Default)]
+pub struct MapRegistry {
+    maps: Vec<GameMap>,
+}
+
+impl MapRegistry {
+    /// Loads every map file inside `directory`. Maps which fail to load or validate are skipped.
+    pub fn load_from_directory<P: AsRef<Path>>(directory: P) -> Self {
+        let mut registry = MapRegistry::default();
+
+        let entries = match fs::read_dir(directory.as_ref()) {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!(
+                    "Was unable to read map directory {:?}: {:?}",
+                    directory.as_ref(),
+                    e
+                );
+                return registry;
+            }
+        };
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|x| x == MAP_FILE_EXTENSION))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let map = match GameMap::load_from_file(&path.to_string_lossy()) {
+                Ok(map) => map,
+                Err(e) => {
+                    warn!("Skipping map {:?}: {}", path, e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = registry.add(map) {
+                warn!("Skipping map {:?}: {}", path, e);
+            }
+        }
+
+        registry
+    }
+
+    /// Adds `map` to the pool, unless it can't be played the way its metadata describes.
+    pub fn add(&mut self, map: GameMap) -> Result<(), String> {
+        let report = validate_map(&map);
+        if report.has_errors() {
+            return Err(report
+                .errors()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" "));
+        }
+
+        for warning in report.warnings() {
+            warn!("Map {}: {}", map.metadata.name, warning);
+        }
+
+        self.maps.push(map);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.maps.len()
+    }
+
+    /// Randomly picks one of the maps which support `player_count` players.
+    #[must_use]
+    pub fn pick_map(&self, player_count: usize, rng: &mut impl Rng) -> Option<&GameMap> {
+        self.maps
+            .iter()
+            .filter(|map| map.metadata.supports_player_count(player_count))
+            .choose(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::map_registry::MapRegistry;
+    use game_common::game_map::GameMap;
+    use hexx::Hex;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use tempfile::TempDir;
+
+    fn create_map(name: &str, max_players: u8, spawn_tiles_per_team: i32) -> GameMap {
+        let mut map = GameMap::new(3);
+        map.metadata.name = name.into();
+        map.metadata.max_players = max_players;
+        for team in 1..=map.metadata.team_count {
+            for x in 0..spawn_tiles_per_team {
+                let hex = if team == 1 {
+                    Hex::new(x, -3)
+                } else {
+                    Hex::new(-x, 3)
+                };
+                map.tiles.get_mut(&hex).unwrap().spawn_zone = Some(team);
+            }
+        }
+
+        map
+    }
+
+    #[test]
+    fn maps_with_too_small_spawn_zones_should_be_rejected() {
+        let mut registry = MapRegistry::default();
+        assert!(registry.add(create_map("Valid", 1, 3)).is_ok());
+        assert!(registry.add(create_map("Too Small", 1, 2)).is_err());
+        assert_eq!(1, registry.len());
+    }
+
+    #[test]
+    fn picked_maps_should_support_player_count() {
+        let dir = TempDir::new().unwrap();
+        create_map("Solo", 1, 3).write_to_disk(dir.path().join("solo.map").to_str().unwrap());
+        create_map("Duo", 2, 3).write_to_disk(dir.path().join("duo.map").to_str().unwrap());
+        create_map("Broken", 2, 0).write_to_disk(dir.path().join("broken.map").to_str().unwrap());
+
+        let registry = MapRegistry::load_from_directory(dir.path());
+        assert_eq!(2, registry.len());
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..10 {
+            let map = registry.pick_map(2, &mut rng).unwrap();
+            assert_eq!("Duo", map.metadata.name);
+        }
+        assert!(registry.pick_map(3, &mut rng).is_none());
+    }
+}
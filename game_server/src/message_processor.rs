@@ -5,6 +5,7 @@ use game_common::network_events::server_to_client::{
 };
 use game_common::player::PlayerId;
 
+use crate::lobby::lobby_management;
 use crate::server_state::ServerState;
 use crate::{in_game, lobby};
 #[cfg(test)]
@@ -23,12 +24,38 @@ pub fn process_message(
     sender: PlayerId,
     message: ClientToServerMessage,
 ) -> Result<Vec<ServerToClientMessageVariant>, ServerToClientMessage> {
-    match &mut shared_state.server_state {
-        ServerState::Lobby => lobby::process_message(shared_state, sender, message),
-        ServerState::InGame(ref mut in_game_data) => {
-            let players = &mut shared_state.players;
+    match message {
+        ClientToServerMessage::ListLobbies => lobby_management::list_lobbies(shared_state),
+        ClientToServerMessage::CreateLobby(message) => {
+            lobby_management::create_lobby(shared_state, sender, message)
+        }
+        ClientToServerMessage::JoinLobby(message) => {
+            lobby_management::join_lobby(shared_state, sender, message)
+        }
+        ClientToServerMessage::LeaveLobby => lobby_management::leave_lobby(shared_state, sender),
+        message => {
             let game_data = &shared_state.game_data;
-            in_game::process_message(sender, message, game_data, players, in_game_data)
+            let map_registry = &shared_state.map_registry;
+            let Some(lobby) = shared_state
+                .player_lobbies
+                .get(&sender)
+                .and_then(|lobby_id| shared_state.lobbies.get_mut(lobby_id))
+            else {
+                return Err(create_error_response(format!(
+                    "Join a lobby before sending {:?}",
+                    message
+                )));
+            };
+
+            match &mut lobby.state {
+                ServerState::Lobby => {
+                    lobby::process_message(sender, message, game_data, map_registry, lobby)
+                }
+                ServerState::InGame(ref mut in_game_data) => {
+                    let players = &mut lobby.players;
+                    in_game::process_message(sender, message, game_data, players, in_game_data)
+                }
+            }
         }
     }
 }
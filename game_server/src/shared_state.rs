@@ -1,46 +1,80 @@
 use crate::connection_handler::ConnectionId;
+use crate::lobby::Lobby;
+use crate::map_registry::MapRegistry;
+use crate::message_processor;
+use crate::message_processor::ServerToClientMessageVariant;
 use crate::server_state::ServerState;
 use bytes::Bytes;
 use game_common::game_data::GameData;
+use game_common::lobby::LobbyId;
+use game_common::network_events::client_to_server::ClientToServerMessage;
 use game_common::network_events::server_to_client::{
-    OtherPlayerConnected, ServerToClientMessage, YouConnected,
+    JoinedLobby, PlayerDisconnected, PlayerLeftLobby, PlayerReconnected, ServerToClientMessage,
+    StateChecksum, YouConnected,
 };
 use game_common::network_events::NetworkMessage;
-use game_common::player::{Player, PlayerId, ReadyState};
-use hashbrown::HashMap;
+use game_common::player::{Player, PlayerId, ReadyState, SessionToken};
+use hashbrown::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
-use tracing::error;
+use tracing::{debug, error, info};
+
+/// Once a player has been gone for this long, whatever the game is waiting for them to do will be done automatically.
+/// Until then, the game just waits for them to come back.
+pub const ABSENT_PLAYER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Games in which every player has been gone for this long get closed.
+pub const ABANDONED_LOBBY_TIMEOUT: Duration = Duration::from_secs(10 * 60);
 
 pub struct SharedState {
     pub connections: HashMap<ConnectionId, mpsc::UnboundedSender<Bytes>>,
     pub game_data: GameData,
-    pub players: HashMap<PlayerId, Player>,
+    /// Sent by clients during the handshake, to make sure they're using the same data.
+    pub game_data_hash: u64,
+    pub map_registry: Arc<MapRegistry>,
+    /// Players who haven't joined a lobby yet. Everyone else is stored within their [Lobby].
+    pub players_without_lobby: HashMap<PlayerId, Player>,
+    pub lobbies: HashMap<LobbyId, Lobby>,
+    pub player_lobbies: HashMap<PlayerId, LobbyId>,
     pub player_to_connection_map: HashMap<PlayerId, ConnectionId>,
     pub connection_to_player_map: HashMap<ConnectionId, PlayerId>, // We would want to allow multiple players from the same connection for local/split-screen multiplayer, tho for now that'd just be an extra headache I guess
-    pub server_state: ServerState,
+    pub session_tokens: HashMap<SessionToken, PlayerId>,
+    /// Players whose connection dropped while in game, and when that happened.
+    pub absent_players: HashMap<PlayerId, Instant>,
+    last_player_id: PlayerId,
+    last_lobby_id: LobbyId,
 }
 
-impl Default for SharedState {
-    fn default() -> Self {
+impl SharedState {
+    pub fn new(game_data: GameData, map_registry: MapRegistry) -> Self {
         Self {
             connections: Default::default(),
-            game_data: GameData::load(),
-            players: Default::default(),
+            game_data_hash: game_data.content_hash(),
+            game_data,
+            map_registry: Arc::new(map_registry),
+            players_without_lobby: Default::default(),
+            lobbies: Default::default(),
+            player_lobbies: Default::default(),
             player_to_connection_map: Default::default(),
             connection_to_player_map: Default::default(),
-            server_state: Default::default(),
+            session_tokens: Default::default(),
+            absent_players: Default::default(),
+            last_player_id: 0,
+            last_lobby_id: 0,
         }
     }
-}
 
-impl SharedState {
-    pub fn broadcast(&mut self, message: ServerToClientMessage) {
+    pub fn send_to(&mut self, sender: &ConnectionId, message: ServerToClientMessage) {
         match message.serialize() {
-            Ok(bytes) => {
-                for (_, tx) in self.connections.iter_mut() {
-                    let _ = tx.send(bytes.clone());
+            Ok(bytes) => match self.connections.get(sender) {
+                None => {
+                    error!("Unable to send Response {:?} - Sender {:?} of message was not found inside the connections array?", message, sender)
                 }
-            }
+                Some(tx) => {
+                    let _ = tx.send(bytes);
+                }
+            },
             Err(e) => {
                 error!(
                     "Error when trying to serialize NetworkMessage {:?} - Error: {:?}",
@@ -50,72 +84,706 @@ impl SharedState {
         }
     }
 
-    pub fn send_to(&mut self, sender: &ConnectionId, message: ServerToClientMessage) {
-        match message.serialize() {
-            Ok(bytes) => match self.connections.get(sender) {
-                None => {
-                    error!("Unable to send Response {:?} - Sender {:?} of message was not found inside the connections array?", message, sender)
-                }
-                Some(tx) => {
-                    let _ = tx.send(bytes);
-                }
-            },
+    /// Sends `message` to the connection of `player_id`, unless they are currently absent.
+    pub fn send_to_player(&mut self, player_id: &PlayerId, message: ServerToClientMessage) {
+        match self.player_to_connection_map.get(player_id) {
+            Some(connection_id) => {
+                let connection_id = *connection_id;
+                self.send_to(&connection_id, message);
+            }
+            None => debug!("Player {} is absent, dropping {:?}", player_id, message),
+        }
+    }
+
+    /// Sends `message` to each of `player_ids` who is currently present.
+    pub fn send_to_players(&mut self, player_ids: &[PlayerId], message: ServerToClientMessage) {
+        let bytes = match message.serialize() {
+            Ok(bytes) => bytes,
             Err(e) => {
                 error!(
                     "Error when trying to serialize NetworkMessage {:?} - Error: {:?}",
                     message, e
                 );
+                return;
             }
+        };
+
+        for player_id in player_ids {
+            let Some(tx) = self
+                .player_to_connection_map
+                .get(player_id)
+                .and_then(|connection_id| self.connections.get(connection_id))
+            else {
+                continue;
+            };
+
+            let _ = tx.send(bytes.clone());
         }
     }
 
-    pub fn send_to_everyone_except_one(
+    /// Sends `message` to everyone in the same lobby as `player_id`, except for `player_id` themselves.
+    pub fn send_to_other_lobby_members(
         &mut self,
-        id_to_ignore: &ConnectionId,
+        player_id: &PlayerId,
         message: ServerToClientMessage,
     ) {
-        match message.serialize() {
-            Ok(bytes) => {
-                for (id, tx) in self.connections.iter_mut() {
-                    if id != id_to_ignore {
-                        let _ = tx.send(bytes.clone());
-                    }
+        let members: Vec<PlayerId> = self
+            .lobby_members(player_id)
+            .into_iter()
+            .filter(|x| x != player_id)
+            .collect();
+        self.send_to_players(&members, message);
+    }
+
+    /// Everyone in the same lobby as `player_id`, including them.
+    #[must_use]
+    pub fn lobby_members(&self, player_id: &PlayerId) -> Vec<PlayerId> {
+        self.lobby_of(player_id)
+            .map(|lobby| lobby.players.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn lobby_of(&self, player_id: &PlayerId) -> Option<&Lobby> {
+        self.lobbies.get(self.player_lobbies.get(player_id)?)
+    }
+
+    #[must_use]
+    pub fn next_lobby_id(&mut self) -> LobbyId {
+        self.last_lobby_id += 1;
+        self.last_lobby_id
+    }
+
+    /// Sends all `messages` resulting from a message sent by `sender`. Broadcasts only reach the sender's lobby.
+    /// While in game, every recipient also receives a [StateChecksum] afterwards.
+    pub fn dispatch(&mut self, sender: PlayerId, messages: Vec<ServerToClientMessageVariant>) {
+        let mut recipients = HashSet::new();
+        for message in messages {
+            debug!("Sending {:?}", message);
+            match message {
+                ServerToClientMessageVariant::SendToSender(message) => {
+                    recipients.insert(sender);
+                    self.send_to_player(&sender, message);
+                }
+                ServerToClientMessageVariant::SendTo((player_id, message)) => {
+                    recipients.insert(player_id);
+                    self.send_to_player(&player_id, message);
+                }
+                ServerToClientMessageVariant::Broadcast(message) => {
+                    let members = self.lobby_members(&sender);
+                    recipients.extend(members.iter().copied());
+                    self.send_to_players(&members, message);
                 }
             }
-            Err(e) => {
-                error!(
-                    "Error when trying to serialize NetworkMessage {:?} - Error: {:?}",
-                    message, e
+        }
+
+        let Some(Lobby {
+            state: ServerState::InGame(in_game_data),
+            ..
+        }) = self.lobby_of(&sender)
+        else {
+            return;
+        };
+
+        let checksums: Vec<(PlayerId, StateChecksum)> = recipients
+            .into_iter()
+            .filter(|player_id| in_game_data.player_resources.contains_key(player_id))
+            .map(|player_id| {
+                (
+                    player_id,
+                    in_game_data.state_checksum_for_player(&player_id),
+                )
+            })
+            .collect();
+        for (player_id, checksum) in checksums {
+            self.send_to_player(&player_id, ServerToClientMessage::StateChecksum(checksum));
+        }
+    }
+
+    /// Decides which player a freshly connected client plays as.
+    /// Clients with a known `session_token` resume their session, as long as it isn't in use by another connection.
+    pub fn accept_client(
+        &mut self,
+        connection_id: ConnectionId,
+        session_token: Option<SessionToken>,
+    ) -> Result<PlayerId, String> {
+        let known_player = session_token.and_then(|token| self.session_tokens.get(&token).copied());
+        match known_player {
+            Some(player_id) if self.player_to_connection_map.contains_key(&player_id) => {
+                Err("This player is already connected.".into())
+            }
+            Some(player_id) => {
+                self.resume_session_and_notify(connection_id, player_id);
+                Ok(player_id)
+            }
+            None => Ok(self.add_player_and_notify(connection_id)),
+        }
+    }
+
+    /// Forgets about `connection_id`. While in game, its player is kept around so they can resume their session later.
+    pub fn remove_connection(&mut self, connection_id: &ConnectionId) {
+        self.connections.remove(connection_id);
+        let Some(player_id) = self.connection_to_player_map.remove(connection_id) else {
+            return;
+        };
+
+        self.player_to_connection_map.remove(&player_id);
+        let is_in_game = self
+            .lobby_of(&player_id)
+            .map(|lobby| matches!(lobby.state, ServerState::InGame(_)));
+        match is_in_game {
+            Some(true) => {
+                info!("Player {} is now absent.", player_id);
+                self.absent_players.insert(player_id, Instant::now());
+                self.send_to_other_lobby_members(
+                    &player_id,
+                    ServerToClientMessage::PlayerDisconnected(PlayerDisconnected { player_id }),
                 );
             }
+            Some(false) => {
+                if let Some((_, remaining_players)) = self.take_player_out_of_lobby(&player_id) {
+                    self.send_to_players(
+                        &remaining_players,
+                        ServerToClientMessage::PlayerLeftLobby(PlayerLeftLobby { player_id }),
+                    );
+                }
+                self.session_tokens.retain(|_, x| x != &player_id);
+            }
+            None => {
+                self.players_without_lobby.remove(&player_id);
+                self.session_tokens.retain(|_, x| x != &player_id);
+            }
+        }
+    }
+
+    /// Removes `player_id` from their lobby, closing it if they were the last one in there.
+    /// Returns the player and everyone who is still in the lobby.
+    pub fn take_player_out_of_lobby(
+        &mut self,
+        player_id: &PlayerId,
+    ) -> Option<(Player, Vec<PlayerId>)> {
+        let lobby_id = self.player_lobbies.remove(player_id)?;
+        let lobby = self.lobbies.get_mut(&lobby_id)?;
+        let player = lobby.players.remove(player_id)?;
+        let remaining_players: Vec<PlayerId> = lobby.players.keys().copied().collect();
+        if remaining_players.is_empty() {
+            info!(
+                "Closing lobby {} [{}], as it is empty.",
+                lobby.name, lobby_id
+            );
+            self.lobbies.remove(&lobby_id);
+        }
+
+        Some((player, remaining_players))
+    }
+
+    /// Acts on behalf of players who have been absent for longer than [ABSENT_PLAYER_TIMEOUT], so the others
+    /// don't need to wait for them forever. Their units' turns are ended and any choices are made for them.
+    pub fn act_for_absent_players(&mut self, now: Instant) {
+        let awaited_actions: Vec<(PlayerId, ClientToServerMessage)> = self
+            .absent_players
+            .iter()
+            .filter(|(_, since)| now.duration_since(**since) >= ABSENT_PLAYER_TIMEOUT)
+            .filter_map(|(player_id, _)| match self.lobby_of(player_id) {
+                Some(Lobby {
+                    state: ServerState::InGame(in_game_data),
+                    players,
+                    ..
+                }) => in_game_data
+                    .action_awaited_from(player_id, players)
+                    .map(|message| (*player_id, message)),
+                _ => None,
+            })
+            .collect();
+
+        for (player_id, message) in awaited_actions {
+            info!("Sending {:?} for absent player {}.", message, player_id);
+            match message_processor::process_message(self, player_id, message) {
+                Ok(messages) => self.dispatch(player_id, messages),
+                Err(e) => error!("Unable to act for absent player {}: {:?}", player_id, e),
+            }
+        }
+    }
+
+    /// Closes games in which every player has been absent for longer than [ABANDONED_LOBBY_TIMEOUT].
+    pub fn remove_abandoned_lobbies(&mut self, now: Instant) {
+        let abandoned_lobbies: Vec<LobbyId> = self
+            .lobbies
+            .values()
+            .filter(|lobby| {
+                lobby.players.keys().all(|player_id| {
+                    self.absent_players
+                        .get(player_id)
+                        .is_some_and(|since| now.duration_since(*since) >= ABANDONED_LOBBY_TIMEOUT)
+                })
+            })
+            .map(|lobby| lobby.id)
+            .collect();
+
+        for lobby_id in abandoned_lobbies {
+            let Some(lobby) = self.lobbies.remove(&lobby_id) else {
+                continue;
+            };
+
+            info!(
+                "Closing lobby {} [{}], as everyone left.",
+                lobby.name, lobby_id
+            );
+            for player_id in lobby.players.keys() {
+                self.player_lobbies.remove(player_id);
+                self.absent_players.remove(player_id);
+                self.session_tokens.retain(|_, x| x != player_id);
+            }
+        }
+    }
+
+    fn resume_session_and_notify(&mut self, connection_id: ConnectionId, player_id: PlayerId) {
+        info!("Player {} resumed their session.", player_id);
+        self.absent_players.remove(&player_id);
+        self.player_to_connection_map
+            .insert(player_id, connection_id);
+        self.connection_to_player_map
+            .insert(connection_id, player_id);
+
+        self.send_you_connected(connection_id, player_id);
+        let Some(lobby) = self.lobby_of(&player_id) else {
+            return;
+        };
+
+        let joined_lobby = ServerToClientMessage::JoinedLobby(JoinedLobby {
+            lobby: lobby.info(),
+            players: lobby.players.values().cloned().collect(),
+        });
+        let resume_game = match &lobby.state {
+            ServerState::Lobby => None,
+            ServerState::InGame(in_game_data) => Some(ServerToClientMessage::ResumeGame(
+                in_game_data.resume_game_for_player(&player_id),
+            )),
+        };
+
+        self.send_to(&connection_id, joined_lobby);
+        self.send_to_other_lobby_members(
+            &player_id,
+            ServerToClientMessage::PlayerReconnected(PlayerReconnected { player_id }),
+        );
+        if let Some(resume_game) = resume_game {
+            self.send_to(&connection_id, resume_game);
         }
     }
 
+    fn send_you_connected(&mut self, connection_id: ConnectionId, player_id: PlayerId) {
+        let Some(session_token) = self
+            .session_tokens
+            .iter()
+            .find(|(_, x)| x == &&player_id)
+            .map(|(token, _)| *token)
+        else {
+            error!("Player {} has no session token!", player_id);
+            return;
+        };
+
+        self.send_to(
+            &connection_id,
+            ServerToClientMessage::YouConnected(YouConnected {
+                player_id,
+                session_token,
+            }),
+        );
+    }
+
     pub fn add_player_and_notify(&mut self, connection_id: ConnectionId) -> PlayerId {
-        let player_id = self.players.len() + 1;
+        self.last_player_id += 1;
+        let player_id = self.last_player_id;
         let player = Player {
             id: player_id,
             name: format!("Player {player_id}"),
             ready_state: ReadyState::ReadyInLobby,
         };
 
-        self.players.insert(player_id, player.clone());
+        self.players_without_lobby.insert(player_id, player);
         self.player_to_connection_map
             .insert(player_id, connection_id);
         self.connection_to_player_map
             .insert(connection_id, player_id);
+        self.session_tokens
+            .insert(SessionToken(rand::random()), player_id);
 
-        self.send_to(
-            &connection_id,
-            ServerToClientMessage::YouConnected(YouConnected {
+        self.send_you_connected(connection_id, player_id);
+        player_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::in_game::in_game_data::InGameData;
+    use crate::in_game::states::combat::ai::AiController;
+    use crate::in_game::states::combat::CombatState;
+    use crate::in_game::states::combat_finished::CombatFinishedState;
+    use crate::in_game::states::pick_unit::PickUnitStateTransition;
+    use crate::in_game::states::InGameState;
+    use crate::lobby::lobby_management;
+    use crate::map_registry::MapRegistry;
+    use crate::message_processor;
+    use crate::message_processor::ServerToClientMessageVariant;
+    use crate::server_state::ServerState;
+    use crate::shared_state::{SharedState, ABANDONED_LOBBY_TIMEOUT, ABSENT_PLAYER_TIMEOUT};
+    use bytes::Bytes;
+    use game_common::combat_data::CombatData;
+    use game_common::combat_turn::CombatTurn;
+    use game_common::game_data::level::Level;
+    use game_common::game_data::unit_definition::UnitDefinition;
+    use game_common::game_data::GameData;
+    use game_common::game_map::GameMap;
+    use game_common::lobby::LobbyId;
+    use game_common::network_events::client_to_server::{
+        ClientToServerMessage, CreateLobby, JoinLobby,
+    };
+    use game_common::network_events::server_to_client::{
+        GameSnapshot, ServerToClientMessage, StartGame,
+    };
+    use game_common::network_events::NetworkMessage;
+    use game_common::network_helpers::FrameBuffer;
+    use game_common::player::{PlayerId, ReadyState, SessionToken};
+    use game_common::player_resources::PlayerResources;
+    use hexx::Hex;
+    use std::sync::Arc;
+    use std::time::Instant;
+    use tokio::sync::mpsc;
+
+    fn connect(state: &mut SharedState, connection_id: usize) -> mpsc::UnboundedReceiver<Bytes> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        state.connections.insert(connection_id, tx);
+        rx
+    }
+
+    fn received_messages(rx: &mut mpsc::UnboundedReceiver<Bytes>) -> Vec<ServerToClientMessage> {
+        let mut frame_buffer = FrameBuffer::default();
+        while let Ok(bytes) = rx.try_recv() {
+            frame_buffer.extend_from_slice(&bytes);
+        }
+
+        let mut result = Vec::new();
+        while let Some(frame) = frame_buffer.next_frame().unwrap() {
+            result.push(ServerToClientMessage::deserialize(&frame).unwrap());
+        }
+        result
+    }
+
+    fn session_token(messages: &[ServerToClientMessage]) -> SessionToken {
+        messages
+            .iter()
+            .find_map(|x| x.as_you_connected())
+            .unwrap()
+            .session_token
+    }
+
+    fn create_lobby(state: &mut SharedState, player_id: PlayerId) -> LobbyId {
+        let name = format!("Lobby of {player_id}");
+        lobby_management::create_lobby(state, player_id, CreateLobby { name }).unwrap();
+        state.player_lobbies[&player_id]
+    }
+
+    fn start_game(state: &mut SharedState, lobby_id: LobbyId) {
+        let lobby = state.lobbies.get_mut(&lobby_id).unwrap();
+        let in_game_data = InGameData::new(&lobby.players, Arc::clone(&state.map_registry));
+        lobby.state = ServerState::InGame(in_game_data);
+    }
+
+    /// Starts a game for a single player who then loses their connection while in the state created by `create_state`.
+    fn absent_player_in(
+        state: &mut SharedState,
+        create_state: impl FnOnce(&GameData, &mut InGameData, PlayerId),
+    ) -> (PlayerId, LobbyId) {
+        let _rx = connect(state, 1);
+        let player_id = state.accept_client(1, None).unwrap();
+        let lobby_id = create_lobby(state, player_id);
+        start_game(state, lobby_id);
+
+        let ServerState::InGame(in_game_data) =
+            &mut state.lobbies.get_mut(&lobby_id).unwrap().state
+        else {
+            unreachable!()
+        };
+        create_state(&state.game_data, in_game_data, player_id);
+        state.remove_connection(&1);
+
+        (player_id, lobby_id)
+    }
+
+    fn in_game_data(state: &SharedState, lobby_id: LobbyId) -> &InGameData {
+        match &state.lobbies[&lobby_id].state {
+            ServerState::InGame(in_game_data) => in_game_data,
+            ServerState::Lobby => panic!("Lobby {lobby_id} is not in game"),
+        }
+    }
+
+    fn create_combat_state(combat_data: CombatData) -> InGameState {
+        let mut loaded_map = GameMap::new(3);
+        for hex in [Hex::new(1, 0), Hex::new(0, 1)] {
+            loaded_map.tiles.get_mut(&hex).unwrap().spawn_zone = Some(1);
+        }
+
+        InGameState::Combat(CombatState {
+            combat_data,
+            loaded_map,
+            ai: AiController::new(0),
+        })
+    }
+
+    fn create_game_data() -> GameData {
+        GameData::create_mock()
+            .with_all_mock_races()
+            .with_all_mock_classes()
+    }
+
+    #[test]
+    fn absent_players_should_pick_units_once_timed_out() {
+        let mut state = SharedState::new(create_game_data(), MapRegistry::default());
+        let (player_id, lobby_id) = absent_player_in(&mut state, |game_data, data, player_id| {
+            let _ =
+                PickUnitStateTransition { remaining: 2 }.execute(game_data, data, vec![player_id]);
+        });
+
+        let now = Instant::now();
+        state.act_for_absent_players(now);
+        let units = |state: &SharedState| {
+            in_game_data(state, lobby_id).player_resources[&player_id]
+                .units
+                .len()
+        };
+        assert_eq!(0, units(&state));
+
+        state.act_for_absent_players(now + ABSENT_PLAYER_TIMEOUT);
+        assert_eq!(1, units(&state));
+        state.act_for_absent_players(now + ABSENT_PLAYER_TIMEOUT);
+        assert_eq!(2, units(&state));
+    }
+
+    #[test]
+    fn absent_players_should_finish_loading_once_timed_out() {
+        let mut state = SharedState::new(create_game_data(), MapRegistry::default());
+        let (player_id, lobby_id) = absent_player_in(&mut state, |_, data, player_id| {
+            data.insert_state_for_player(player_id, create_combat_state(CombatData::create_mock()));
+        });
+
+        state.act_for_absent_players(Instant::now() + ABSENT_PLAYER_TIMEOUT);
+        assert_eq!(
+            ReadyState::LoadedInGame,
+            state.lobbies[&lobby_id].players[&player_id].ready_state
+        );
+        let GameSnapshot::Combat { combat_data, .. } =
+            in_game_data(&state, lobby_id).snapshot_for_player(&player_id)
+        else {
+            panic!("Player should still be in combat");
+        };
+        assert_eq!(CombatTurn::place_unit(player_id), combat_data.current_turn);
+    }
+
+    #[test]
+    fn absent_players_should_place_units_once_timed_out() {
+        let mut state = SharedState::new(create_game_data(), MapRegistry::default());
+        let (player_id, lobby_id) = absent_player_in(&mut state, |game_data, data, player_id| {
+            let mut combat_data = CombatData::create_mock();
+            combat_data.current_turn = CombatTurn::place_unit(player_id);
+            data.insert_state_for_player(player_id, create_combat_state(combat_data));
+
+            let class = *game_data.classes.keys().min().unwrap();
+            data.player_resources
+                .get_mut(&player_id)
+                .unwrap()
+                .units
+                .push(UnitDefinition {
+                    id: 42,
+                    owner: player_id,
+                    name: "Unit".into(),
+                    active_class: class,
+                    race: *game_data.races.keys().min().unwrap(),
+                    levels: [(class, Level::default())].into_iter().collect(),
+                    permanently_unlocked_skills: Vec::new(),
+                    weapon: None,
+                    armor: None,
+                    accessory: None,
+                    reaction: None,
+                });
+        });
+        state
+            .lobbies
+            .get_mut(&lobby_id)
+            .unwrap()
+            .players
+            .get_mut(&player_id)
+            .unwrap()
+            .ready_state = ReadyState::LoadedInGame;
+
+        state.act_for_absent_players(Instant::now() + ABSENT_PLAYER_TIMEOUT);
+        let GameSnapshot::Combat { combat_data, .. } =
+            in_game_data(&state, lobby_id).snapshot_for_player(&player_id)
+        else {
+            panic!("Player should still be in combat");
+        };
+        assert_eq!(Some(&42), combat_data.unit_positions.get(&Hex::new(0, 1)));
+    }
+
+    #[test]
+    fn absent_players_should_proceed_after_combat_once_timed_out() {
+        let mut state = SharedState::new(create_game_data(), MapRegistry::default());
+        let (player_id, lobby_id) = absent_player_in(&mut state, |_, data, player_id| {
+            data.insert_state_for_player(
                 player_id,
-                connected_players: self.players.values().cloned().collect(),
-            }),
+                InGameState::CombatFinished(CombatFinishedState),
+            );
+        });
+
+        state.act_for_absent_players(Instant::now() + ABSENT_PLAYER_TIMEOUT);
+        assert!(matches!(
+            in_game_data(&state, lobby_id).snapshot_for_player(&player_id),
+            GameSnapshot::PickUnit { .. }
+        ));
+    }
+
+    #[test]
+    fn absent_players_should_be_able_to_resume_their_session() {
+        let mut state = SharedState::new(GameData::create_mock(), MapRegistry::default());
+        let mut rx = connect(&mut state, 1);
+        let player_id = state.accept_client(1, None).unwrap();
+        let token = session_token(&received_messages(&mut rx));
+        let lobby_id = create_lobby(&mut state, player_id);
+        start_game(&mut state, lobby_id);
+
+        state.remove_connection(&1);
+        assert!(state.absent_players.contains_key(&player_id));
+        assert!(state.lobbies[&lobby_id].players.contains_key(&player_id));
+
+        let mut rx = connect(&mut state, 2);
+        assert_eq!(Ok(player_id), state.accept_client(2, Some(token)));
+        assert!(state.absent_players.is_empty());
+        assert_eq!(Some(&2), state.player_to_connection_map.get(&player_id));
+
+        let messages = received_messages(&mut rx);
+        assert_eq!(token, session_token(&messages));
+        let joined = messages.iter().find_map(|x| x.as_joined_lobby()).unwrap();
+        assert_eq!(lobby_id, joined.lobby.id);
+        let resume = messages.iter().find_map(|x| x.as_resume_game()).unwrap();
+        assert_eq!(GameSnapshot::WaitingForOthers, resume.state);
+    }
+
+    #[test]
+    fn sessions_should_not_be_taken_over() {
+        let mut state = SharedState::new(GameData::create_mock(), MapRegistry::default());
+        let mut rx = connect(&mut state, 1);
+        state.accept_client(1, None).unwrap();
+        let token = session_token(&received_messages(&mut rx));
+
+        let _rx = connect(&mut state, 2);
+        assert!(
+            state.accept_client(2, Some(token)).is_err(),
+            "Sessions in use by another connection must not be taken over"
         );
-        self.send_to_everyone_except_one(
-            &connection_id,
-            ServerToClientMessage::OtherPlayerConnected(OtherPlayerConnected { player }),
+        assert!(state
+            .accept_client(2, Some(SessionToken(token.0 + 1)))
+            .is_ok());
+    }
+
+    #[test]
+    fn lobbies_with_a_game_in_progress_should_not_be_joinable() {
+        let mut state = SharedState::new(GameData::create_mock(), MapRegistry::default());
+        let host = state.add_player_and_notify(1);
+        let other = state.add_player_and_notify(2);
+        let lobby_id = create_lobby(&mut state, host);
+        start_game(&mut state, lobby_id);
+
+        assert!(lobby_management::join_lobby(&mut state, other, JoinLobby { lobby_id }).is_err());
+        assert!(state.players_without_lobby.contains_key(&other));
+    }
+
+    #[test]
+    fn broadcasts_should_only_reach_the_senders_lobby() {
+        let mut state = SharedState::new(GameData::create_mock(), MapRegistry::default());
+        let mut receivers: Vec<_> = (1..=3).map(|x| connect(&mut state, x)).collect();
+        let players: Vec<PlayerId> = (1..=3)
+            .map(|x| state.accept_client(x, None).unwrap())
+            .collect();
+        let lobby_id = create_lobby(&mut state, players[0]);
+        lobby_management::join_lobby(&mut state, players[1], JoinLobby { lobby_id }).unwrap();
+        create_lobby(&mut state, players[2]);
+        receivers.iter_mut().for_each(|rx| {
+            received_messages(rx);
+        });
+
+        state.dispatch(
+            players[1],
+            vec![ServerToClientMessageVariant::Broadcast(
+                ServerToClientMessage::StartGame(StartGame {}),
+            )],
         );
-        player_id
+
+        let received: Vec<usize> = receivers
+            .iter_mut()
+            .map(|rx| received_messages(rx).len())
+            .collect();
+        assert_eq!(vec![1, 1, 0], received);
+    }
+
+    #[test]
+    fn in_game_updates_should_be_followed_by_checksums() {
+        let mut state = SharedState::new(GameData::create_mock(), MapRegistry::default());
+        let mut rx = connect(&mut state, 1);
+        let player_id = state.accept_client(1, None).unwrap();
+        let lobby_id = create_lobby(&mut state, player_id);
+        start_game(&mut state, lobby_id);
+        received_messages(&mut rx);
+
+        let messages = message_processor::process_message(
+            &mut state,
+            player_id,
+            ClientToServerMessage::RequestResync,
+        )
+        .unwrap();
+        state.dispatch(player_id, messages);
+
+        let messages = received_messages(&mut rx);
+        assert_eq!(2, messages.len());
+        assert!(messages[0].as_resume_game().is_some());
+        let checksum = messages[1].as_state_checksum().unwrap();
+        assert_eq!(
+            PlayerResources::default().content_hash(),
+            checksum.player_resources
+        );
+        assert_eq!(None, checksum.combat_data);
+    }
+
+    #[test]
+    fn lobby_players_should_be_removed_on_disconnect() {
+        let mut state = SharedState::new(GameData::create_mock(), MapRegistry::default());
+        let _rx = connect(&mut state, 1);
+        let player_id = state.accept_client(1, None).unwrap();
+        create_lobby(&mut state, player_id);
+
+        state.remove_connection(&1);
+        assert!(state.lobbies.is_empty());
+        assert!(state.player_lobbies.is_empty());
+        assert!(state.players_without_lobby.is_empty());
+        assert!(state.session_tokens.is_empty());
+        assert!(state.absent_players.is_empty());
+    }
+
+    #[test]
+    fn abandoned_games_should_be_closed() {
+        let mut state = SharedState::new(GameData::create_mock(), MapRegistry::default());
+        let _rx = connect(&mut state, 1);
+        let player_id = state.accept_client(1, None).unwrap();
+        let lobby_id = create_lobby(&mut state, player_id);
+        start_game(&mut state, lobby_id);
+        state.remove_connection(&1);
+
+        let now = Instant::now();
+        state.remove_abandoned_lobbies(now);
+        assert!(state.lobbies.contains_key(&lobby_id));
+
+        state.remove_abandoned_lobbies(now + ABANDONED_LOBBY_TIMEOUT);
+        assert!(state.lobbies.is_empty());
+        assert!(state.player_lobbies.is_empty());
+        assert!(state.session_tokens.is_empty());
+        assert!(state.absent_players.is_empty());
     }
 }